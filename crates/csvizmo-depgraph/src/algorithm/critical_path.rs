@@ -0,0 +1,404 @@
+use std::collections::{HashMap, HashSet};
+
+use clap::Parser;
+use indexmap::IndexMap;
+use petgraph::Direction;
+use petgraph::algo::toposort;
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+
+use crate::{DepGraph, FlatGraphView};
+
+#[derive(Clone, Debug, Parser)]
+pub struct CriticalPathArgs {
+    /// Node attr holding each node's weight, like a build step's duration
+    #[clap(long, default_value = "duration")]
+    pub weight: String,
+
+    /// Edge attr holding each edge's weight, added to the paths that take it
+    #[clap(long)]
+    pub edge_weight: Option<String>,
+}
+
+impl Default for CriticalPathArgs {
+    fn default() -> Self {
+        Self {
+            weight: "duration".to_string(),
+            edge_weight: None,
+        }
+    }
+}
+
+impl CriticalPathArgs {
+    pub fn weight(mut self, attr: impl Into<String>) -> Self {
+        self.weight = attr.into();
+        self
+    }
+
+    pub fn edge_weight(mut self, attr: impl Into<String>) -> Self {
+        self.edge_weight = Some(attr.into());
+        self
+    }
+}
+
+/// The schedule of a node, if everything started as early as its dependencies allow.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NodeTiming {
+    pub id: String,
+    pub weight: f64,
+    pub start: f64,
+    pub finish: f64,
+    /// How much later the node could finish without delaying the whole graph.
+    pub slack: f64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CriticalPath {
+    /// The finish time of the last node.
+    pub length: f64,
+    /// Every node, with dependencies before the nodes that depend on them.
+    pub nodes: Vec<NodeTiming>,
+    /// The longest chain of dependencies, as indices into `nodes`, in the same order.
+    pub chain: Vec<usize>,
+}
+
+fn parse_weight(attrs: &IndexMap<String, String>, attr: &str) -> eyre::Result<f64> {
+    match attrs.get(attr) {
+        Some(value) => value
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|w| w.is_finite())
+            .ok_or_else(|| eyre::eyre!("non-numeric {attr} attr: {value:?}")),
+        None => Ok(0.0),
+    }
+}
+
+/// Find the longest weighted path through a DAG, and the slack of every node.
+///
+/// Edges point from a node to its dependencies, which have to finish before it can start. Node
+/// weights come from the `--weight` attr and edge weights from the `--edge-weight` attr, and
+/// missing weights count as zero. This is the same DP over a topological order as the max depth
+/// in `depquery metrics`, with real durations, like the `duration` attrs from a `.ninja_log`.
+pub fn critical_path(graph: &DepGraph, args: &CriticalPathArgs) -> eyre::Result<CriticalPath> {
+    let view = FlatGraphView::new(graph);
+    let all_nodes = graph.all_nodes();
+
+    let sorted = toposort(&view.pg, None).map_err(|_| {
+        eyre::eyre!(
+            "graph contains cycles; the critical path requires a DAG. \
+             Use `depfilter cycles` to identify them."
+        )
+    })?;
+    // Dependencies first
+    let order: Vec<usize> = sorted.iter().rev().map(|idx| idx.index()).collect();
+
+    let weights = view
+        .idx_to_id
+        .iter()
+        .map(|id| {
+            parse_weight(&all_nodes[*id].attrs, &args.weight)
+                .map_err(|e| e.wrap_err(format!("invalid weight for node {id:?}")))
+        })
+        .collect::<eyre::Result<Vec<f64>>>()?;
+    // Parallel edges take the heaviest
+    let mut edge_weights: HashMap<(usize, usize), f64> = HashMap::new();
+    if let Some(attr) = &args.edge_weight {
+        for edge in graph.all_edges() {
+            let (Some(from), Some(to)) = (
+                view.id_to_idx.get(edge.from.as_str()),
+                view.id_to_idx.get(edge.to.as_str()),
+            ) else {
+                continue;
+            };
+            let weight = parse_weight(&edge.attrs, attr).map_err(|e| {
+                e.wrap_err(format!(
+                    "invalid weight for edge {:?} -> {:?}",
+                    edge.from, edge.to
+                ))
+            })?;
+            let entry = edge_weights
+                .entry((from.index(), to.index()))
+                .or_insert(weight);
+            *entry = entry.max(weight);
+        }
+    }
+    let edge_weight =
+        |from: usize, to: usize| edge_weights.get(&(from, to)).copied().unwrap_or(0.0);
+
+    // Forward pass, from the dependencies up
+    let n = view.idx_to_id.len();
+    let mut start = vec![0.0f64; n];
+    let mut finish = vec![0.0f64; n];
+    for &node in &order {
+        for edge in view.pg.edges(NodeIndex::new(node)) {
+            let dep = edge.target().index();
+            start[node] = start[node].max(finish[dep] + edge_weight(node, dep));
+        }
+        finish[node] = start[node] + weights[node];
+    }
+    let length = finish.iter().copied().fold(0.0, f64::max);
+
+    // Backward pass, from the dependents down
+    let mut latest_finish = vec![length; n];
+    for &node in order.iter().rev() {
+        for edge in view
+            .pg
+            .edges_directed(NodeIndex::new(node), Direction::Incoming)
+        {
+            let dependent = edge.source().index();
+            let latest_start = latest_finish[dependent] - weights[dependent];
+            latest_finish[node] =
+                latest_finish[node].min(latest_start - edge_weight(dependent, node));
+        }
+    }
+
+    // Follow the dependencies that determined each start time, from the node that finishes last
+    let mut chain = Vec::new();
+    let mut current = order
+        .iter()
+        .copied()
+        .filter(|&node| finish[node] == length)
+        .min();
+    while let Some(node) = current {
+        chain.push(node);
+        current = view
+            .pg
+            .edges(NodeIndex::new(node))
+            .map(|edge| edge.target().index())
+            .filter(|&dep| finish[dep] + edge_weight(node, dep) == start[node])
+            .min();
+    }
+    let position: HashMap<usize, usize> = order.iter().enumerate().map(|(i, &n)| (n, i)).collect();
+    let mut chain: Vec<usize> = chain.iter().map(|node| position[node]).collect();
+    chain.reverse();
+
+    Ok(CriticalPath {
+        length,
+        nodes: order
+            .iter()
+            .map(|&node| NodeTiming {
+                id: view.idx_to_id[node].to_string(),
+                weight: weights[node],
+                start: start[node],
+                finish: finish[node],
+                slack: latest_finish[node] - finish[node],
+            })
+            .collect(),
+        chain,
+    })
+}
+
+/// Annotate a graph with the `slack` of each node, and highlight the critical chain.
+///
+/// The nodes and edges along the chain get a red `color` and a `penwidth` of 2, which the DOT,
+/// Mermaid, SVG, and HTML outputs all draw.
+pub fn annotate(graph: &DepGraph, critical: &CriticalPath) -> DepGraph {
+    let slack: HashMap<&str, f64> = critical
+        .nodes
+        .iter()
+        .map(|n| (n.id.as_str(), n.slack))
+        .collect();
+    let chain: Vec<&str> = critical
+        .chain
+        .iter()
+        .map(|&i| critical.nodes[i].id.as_str())
+        .collect();
+    let edges: HashSet<(&str, &str)> = chain.windows(2).map(|w| (w[1], w[0])).collect();
+    let chain: HashSet<&str> = chain.into_iter().collect();
+    annotate_inner(graph, &slack, &chain, &edges)
+}
+
+fn highlight(attrs: &mut IndexMap<String, String>) {
+    attrs.insert("color".to_string(), "red".to_string());
+    attrs.insert("penwidth".to_string(), "2".to_string());
+}
+
+fn annotate_inner(
+    graph: &DepGraph,
+    slack: &HashMap<&str, f64>,
+    chain: &HashSet<&str>,
+    edges: &HashSet<(&str, &str)>,
+) -> DepGraph {
+    let mut nodes = graph.nodes.clone();
+    for (id, info) in &mut nodes {
+        if let Some(slack) = slack.get(id.as_str()) {
+            info.attrs.insert("slack".to_string(), slack.to_string());
+        }
+        if chain.contains(id.as_str()) {
+            highlight(&mut info.attrs);
+        }
+    }
+    let mut new_edges = graph.edges.clone();
+    for edge in &mut new_edges {
+        if edges.contains(&(edge.from.as_str(), edge.to.as_str())) {
+            highlight(&mut edge.attrs);
+        }
+    }
+    DepGraph {
+        id: graph.id.clone(),
+        attrs: graph.attrs.clone(),
+        nodes,
+        edges: new_edges,
+        subgraphs: graph
+            .subgraphs
+            .iter()
+            .map(|sg| annotate_inner(sg, slack, chain, edges))
+            .collect(),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Edge, NodeInfo};
+
+    fn make_graph(
+        nodes: &[(&str, Option<&str>)],
+        edges: &[(&str, &str, Option<&str>)],
+    ) -> DepGraph {
+        DepGraph {
+            nodes: nodes
+                .iter()
+                .map(|(id, duration)| {
+                    let mut info = NodeInfo::new(*id);
+                    if let Some(d) = duration {
+                        info.attrs.insert("duration".into(), d.to_string());
+                    }
+                    (id.to_string(), info)
+                })
+                .collect(),
+            edges: edges
+                .iter()
+                .map(|(from, to, latency)| Edge {
+                    from: from.to_string(),
+                    to: to.to_string(),
+                    attrs: latency
+                        .iter()
+                        .map(|l| ("latency".to_string(), l.to_string()))
+                        .collect(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn chain(critical: &CriticalPath) -> Vec<&str> {
+        critical
+            .chain
+            .iter()
+            .map(|&i| critical.nodes[i].id.as_str())
+            .collect()
+    }
+
+    fn slack(critical: &CriticalPath, id: &str) -> f64 {
+        critical.nodes.iter().find(|n| n.id == id).unwrap().slack
+    }
+
+    /// app -> lib -> core, app -> codegen -> core
+    fn build() -> DepGraph {
+        make_graph(
+            &[
+                ("app", Some("2")),
+                ("lib", Some("5")),
+                ("codegen", Some("1.5")),
+                ("core", Some("3")),
+            ],
+            &[
+                ("app", "lib", None),
+                ("app", "codegen", Some("4")),
+                ("lib", "core", None),
+                ("codegen", "core", None),
+            ],
+        )
+    }
+
+    #[test]
+    fn empty_graph() {
+        let critical = critical_path(&DepGraph::default(), &CriticalPathArgs::default()).unwrap();
+        assert_eq!(critical, CriticalPath::default());
+    }
+
+    #[test]
+    fn node_weights() {
+        let critical = critical_path(&build(), &CriticalPathArgs::default()).unwrap();
+        assert_eq!(critical.length, 10.0);
+        assert_eq!(chain(&critical), ["core", "lib", "app"]);
+        assert_eq!(slack(&critical, "core"), 0.0);
+        assert_eq!(slack(&critical, "lib"), 0.0);
+        assert_eq!(slack(&critical, "codegen"), 3.5);
+        assert_eq!(slack(&critical, "app"), 0.0);
+    }
+
+    #[test]
+    fn edge_weights() {
+        let args = CriticalPathArgs::default().edge_weight("latency");
+        let critical = critical_path(&build(), &args).unwrap();
+        assert_eq!(critical.length, 10.5);
+        assert_eq!(chain(&critical), ["core", "codegen", "app"]);
+        assert_eq!(slack(&critical, "lib"), 0.5);
+        assert_eq!(slack(&critical, "codegen"), 0.0);
+    }
+
+    #[test]
+    fn missing_weights_are_zero() {
+        // Only the attr named by --weight counts
+        let args = CriticalPathArgs::default().weight("size");
+        let critical = critical_path(&build(), &args).unwrap();
+        assert_eq!(critical.length, 0.0);
+        assert_eq!(critical.nodes.len(), 4);
+        assert!(critical.nodes.iter().all(|n| n.slack == 0.0));
+    }
+
+    #[test]
+    fn disconnected_nodes_have_slack() {
+        let graph = make_graph(&[("a", Some("4")), ("b", Some("1"))], &[]);
+        let critical = critical_path(&graph, &CriticalPathArgs::default()).unwrap();
+        assert_eq!(chain(&critical), ["a"]);
+        assert_eq!(slack(&critical, "b"), 3.0);
+    }
+
+    #[test]
+    fn cycles_rejected() {
+        let graph = make_graph(
+            &[("a", None), ("b", None)],
+            &[("a", "b", None), ("b", "a", None)],
+        );
+        let err = critical_path(&graph, &CriticalPathArgs::default()).unwrap_err();
+        assert!(err.to_string().contains("cycles"));
+    }
+
+    #[test]
+    fn non_numeric_weight_rejected() {
+        let graph = make_graph(&[("a", Some("fast"))], &[]);
+        let err = critical_path(&graph, &CriticalPathArgs::default()).unwrap_err();
+        assert_eq!(err.to_string(), "invalid weight for node \"a\"");
+        assert_eq!(
+            err.root_cause().to_string(),
+            "non-numeric duration attr: \"fast\""
+        );
+    }
+
+    #[test]
+    fn annotate_highlights_chain() {
+        let graph = build();
+        let critical = critical_path(&graph, &CriticalPathArgs::default()).unwrap();
+        let annotated = annotate(&graph, &critical);
+
+        assert_eq!(annotated.nodes["lib"].attrs["color"], "red");
+        assert_eq!(annotated.nodes["lib"].attrs["penwidth"], "2");
+        assert_eq!(annotated.nodes["lib"].attrs["slack"], "0");
+        assert!(!annotated.nodes["codegen"].attrs.contains_key("color"));
+        assert_eq!(annotated.nodes["codegen"].attrs["slack"], "3.5");
+
+        let highlighted: Vec<_> = annotated
+            .edges
+            .iter()
+            .filter(|e| e.attrs.contains_key("color"))
+            .map(|e| (e.from.as_str(), e.to.as_str()))
+            .collect();
+        assert_eq!(highlighted, [("app", "lib"), ("lib", "core")]);
+    }
+}
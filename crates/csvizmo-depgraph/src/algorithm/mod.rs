@@ -1,5 +1,6 @@
 pub mod between;
 pub mod cluster;
+pub mod critical_path;
 pub mod cycles;
 pub mod diff;
 pub mod flatten;
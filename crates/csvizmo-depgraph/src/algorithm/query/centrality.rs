@@ -0,0 +1,319 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+
+use petgraph::Direction;
+
+use crate::FlatGraphView;
+
+/// The neighbors of each node in one direction, without self-loops or parallel edges.
+fn adjacency(view: &FlatGraphView, direction: Direction) -> Vec<Vec<usize>> {
+    view.pg
+        .node_indices()
+        .map(|idx| {
+            let mut neighbors: Vec<usize> = view
+                .pg
+                .neighbors_directed(idx, direction)
+                .filter(|&n| n != idx)
+                .map(|n| n.index())
+                .collect();
+            neighbors.sort_unstable();
+            neighbors.dedup();
+            neighbors
+        })
+        .collect()
+}
+
+/// Betweenness centrality: how many shortest dependency paths pass through each node.
+///
+/// Uses Brandes' algorithm on the directed graph. Scores aren't normalized, so a node on the only
+/// shortest path between 10 pairs of other nodes scores 10, and ties between several shortest
+/// paths are split evenly. Unlike in-degree, this ranks the nodes that connect otherwise separate
+/// parts of the graph, not the leaves everything depends on.
+pub fn betweenness(view: &FlatGraphView) -> Vec<f64> {
+    let n = view.pg.node_count();
+    let succ = adjacency(view, Direction::Outgoing);
+
+    let mut centrality = vec![0.0; n];
+    let mut dist = vec![usize::MAX; n];
+    let mut sigma = vec![0.0f64; n];
+    let mut delta = vec![0.0f64; n];
+    let mut preds: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut stack = Vec::new();
+    let mut queue = VecDeque::new();
+    for source in 0..n {
+        dist.fill(usize::MAX);
+        sigma.fill(0.0);
+        delta.fill(0.0);
+        preds.iter_mut().for_each(Vec::clear);
+
+        // Count the shortest paths from the source to every node
+        dist[source] = 0;
+        sigma[source] = 1.0;
+        queue.push_back(source);
+        while let Some(v) = queue.pop_front() {
+            stack.push(v);
+            for &w in &succ[v] {
+                if dist[w] == usize::MAX {
+                    dist[w] = dist[v] + 1;
+                    queue.push_back(w);
+                }
+                if dist[w] == dist[v] + 1 {
+                    sigma[w] += sigma[v];
+                    preds[w].push(v);
+                }
+            }
+        }
+
+        // Then credit each node with its share of the paths through it, farthest nodes first
+        while let Some(w) = stack.pop() {
+            for &v in &preds[w] {
+                delta[v] += sigma[v] / sigma[w] * (1.0 + delta[w]);
+            }
+            if w != source {
+                centrality[w] += delta[w];
+            }
+        }
+    }
+    centrality
+}
+
+/// Closeness centrality: how near each node is to the nodes that depend on it.
+///
+/// This is the inverse of the average distance from the node's ancestors, scaled by the fraction
+/// of the graph they make up (the Wasserman and Faust formula), so that a node with one close
+/// dependent doesn't outrank one that half the graph depends on from a little further away.
+pub fn closeness(view: &FlatGraphView) -> Vec<f64> {
+    let n = view.pg.node_count();
+    let pred = adjacency(view, Direction::Incoming);
+
+    let mut dist = vec![usize::MAX; n];
+    let mut queue = VecDeque::new();
+    (0..n)
+        .map(|target| {
+            dist.fill(usize::MAX);
+            dist[target] = 0;
+            queue.push_back(target);
+            let mut reached = 0usize;
+            let mut total = 0usize;
+            while let Some(v) = queue.pop_front() {
+                for &w in &pred[v] {
+                    if dist[w] == usize::MAX {
+                        dist[w] = dist[v] + 1;
+                        reached += 1;
+                        total += dist[w];
+                        queue.push_back(w);
+                    }
+                }
+            }
+            if total == 0 {
+                return 0.0;
+            }
+            let reached = reached as f64;
+            reached / (n - 1) as f64 * reached / total as f64
+        })
+        .collect()
+}
+
+/// PageRank, with rank flowing from each node to its dependencies.
+///
+/// Nodes without dependencies spread their rank over the whole graph. Uses the usual damping
+/// factor of 0.85, and iterates until the ranks converge. The ranks sum to 1.
+pub fn page_rank(view: &FlatGraphView) -> Vec<f64> {
+    const DAMPING: f64 = 0.85;
+    const MAX_ITERATIONS: usize = 100;
+    const TOLERANCE: f64 = 1e-10;
+
+    let n = view.pg.node_count();
+    if n == 0 {
+        return Vec::new();
+    }
+    let succ = adjacency(view, Direction::Outgoing);
+
+    let mut rank = vec![1.0 / n as f64; n];
+    for _ in 0..MAX_ITERATIONS {
+        let dangling: f64 = (0..n)
+            .filter(|&v| succ[v].is_empty())
+            .map(|v| rank[v])
+            .sum();
+        let mut next = vec![(1.0 - DAMPING + DAMPING * dangling) / n as f64; n];
+        for (v, deps) in succ.iter().enumerate() {
+            let share = DAMPING * rank[v] / deps.len() as f64;
+            for &w in deps {
+                next[w] += share;
+            }
+        }
+        let change: f64 = next.iter().zip(&rank).map(|(a, b)| (a - b).abs()).sum();
+        rank = next;
+        if change < TOLERANCE * n as f64 {
+            break;
+        }
+    }
+    rank
+}
+
+/// The k-core number of each node, ignoring edge direction.
+///
+/// A node's core number is the largest k for which it's part of a subgraph where every node has
+/// at least k neighbors. Chains and trees hanging off the graph get 1, while densely interdependent
+/// groups of nodes get higher numbers. Computed by repeatedly removing the lowest degree node.
+pub fn k_core(view: &FlatGraphView) -> Vec<usize> {
+    let n = view.pg.node_count();
+    let mut neighbors = adjacency(view, Direction::Outgoing);
+    for (v, preds) in adjacency(view, Direction::Incoming).into_iter().enumerate() {
+        neighbors[v].extend(preds);
+        neighbors[v].sort_unstable();
+        neighbors[v].dedup();
+    }
+
+    let mut degree: Vec<usize> = neighbors.iter().map(Vec::len).collect();
+    let mut heap: BinaryHeap<_> = (0..n).map(|v| Reverse((degree[v], v))).collect();
+    let mut removed = vec![false; n];
+    let mut core = vec![0; n];
+    let mut k = 0;
+    while let Some(Reverse((d, v))) = heap.pop() {
+        // Skip stale entries, from before a neighbor was removed
+        if removed[v] || d != degree[v] {
+            continue;
+        }
+        k = k.max(d);
+        core[v] = k;
+        removed[v] = true;
+        for &w in &neighbors[v] {
+            if !removed[w] {
+                degree[w] -= 1;
+                heap.push(Reverse((degree[w], w)));
+            }
+        }
+    }
+    core
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DepGraph, Edge, NodeInfo};
+
+    fn make_graph(nodes: &[&str], edges: &[(&str, &str)]) -> DepGraph {
+        DepGraph {
+            nodes: nodes
+                .iter()
+                .map(|id| (id.to_string(), NodeInfo::new(*id)))
+                .collect(),
+            edges: edges
+                .iter()
+                .map(|(from, to)| Edge {
+                    from: from.to_string(),
+                    to: to.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn assert_close(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-6, "{actual:?} != {expected:?}");
+        }
+    }
+
+    #[test]
+    fn empty_graph() {
+        let graph = DepGraph::default();
+        let view = FlatGraphView::new(&graph);
+        assert!(betweenness(&view).is_empty());
+        assert!(closeness(&view).is_empty());
+        assert!(page_rank(&view).is_empty());
+        assert!(k_core(&view).is_empty());
+    }
+
+    #[test]
+    fn betweenness_chain() {
+        // a -> b -> c -> d
+        let graph = make_graph(&["a", "b", "c", "d"], &[("a", "b"), ("b", "c"), ("c", "d")]);
+        let view = FlatGraphView::new(&graph);
+        assert_close(&betweenness(&view), &[0.0, 2.0, 2.0, 0.0]);
+    }
+
+    #[test]
+    fn betweenness_choke_point() {
+        // app and cli both reach serde and log through core, and also depend on serde directly
+        let graph = make_graph(
+            &["app", "cli", "core", "serde", "log"],
+            &[
+                ("app", "core"),
+                ("cli", "core"),
+                ("core", "serde"),
+                ("core", "log"),
+                ("app", "serde"),
+                ("cli", "serde"),
+            ],
+        );
+        let view = FlatGraphView::new(&graph);
+        // serde has the highest in-degree, but only core is on the paths to log
+        assert_close(&betweenness(&view), &[0.0, 0.0, 2.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn betweenness_splits_ties() {
+        // a -> b -> d, a -> c -> d, with a parallel a -> b edge that shouldn't count twice
+        let graph = make_graph(
+            &["a", "b", "c", "d"],
+            &[("a", "b"), ("a", "b"), ("a", "c"), ("b", "d"), ("c", "d")],
+        );
+        let view = FlatGraphView::new(&graph);
+        assert_close(&betweenness(&view), &[0.0, 0.5, 0.5, 0.0]);
+    }
+
+    #[test]
+    fn closeness_star() {
+        // a, b, and c all depend on d, and c also depends on a
+        let graph = make_graph(
+            &["a", "b", "c", "d"],
+            &[("a", "d"), ("b", "d"), ("c", "d"), ("c", "a")],
+        );
+        let view = FlatGraphView::new(&graph);
+        // d is 1 away from all 3 others; a is 1 away from only c
+        assert_close(&closeness(&view), &[1.0 / 3.0, 0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn page_rank_flows_to_dependencies() {
+        // a -> c, b -> c
+        let graph = make_graph(&["a", "b", "c"], &[("a", "c"), ("b", "c")]);
+        let view = FlatGraphView::new(&graph);
+        let rank = page_rank(&view);
+        assert!((rank.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(rank[2] > rank[0]);
+        assert!((rank[0] - rank[1]).abs() < 1e-12);
+    }
+
+    #[test]
+    fn page_rank_cycle_is_uniform() {
+        let graph = make_graph(&["a", "b", "c"], &[("a", "b"), ("b", "c"), ("c", "a")]);
+        let view = FlatGraphView::new(&graph);
+        assert_close(&page_rank(&view), &[1.0 / 3.0; 3]);
+    }
+
+    #[test]
+    fn k_core_clique_with_tail() {
+        // a, b, c, and d all depend on each other one way, with a chain e -> f hanging off d
+        let graph = make_graph(
+            &["a", "b", "c", "d", "e", "f", "g"],
+            &[
+                ("a", "b"),
+                ("a", "c"),
+                ("a", "d"),
+                ("b", "c"),
+                ("b", "d"),
+                ("c", "d"),
+                ("d", "e"),
+                ("e", "f"),
+                ("g", "g"),
+            ],
+        );
+        let view = FlatGraphView::new(&graph);
+        assert_eq!(k_core(&view), [3, 3, 3, 3, 1, 1, 0]);
+    }
+}
@@ -0,0 +1,226 @@
+use std::cmp::Reverse;
+
+use clap::Parser;
+use petgraph::algo::dominators::simple_fast;
+use petgraph::graph::NodeIndex;
+
+use super::OutputFields;
+use crate::algorithm::{MatchKey, build_globset};
+use crate::{DepGraph, FlatGraphView};
+
+#[derive(Clone, Debug, Default, Parser)]
+pub struct DominatorsArgs {
+    /// Pattern for the nodes to start from (repeatable, OR; defaults to the roots)
+    #[clap(short = 'f', long)]
+    pub from: Vec<String>,
+
+    /// What patterns match against
+    #[clap(long, default_value_t = MatchKey::default())]
+    pub key: MatchKey,
+
+    /// Show only first N results
+    #[clap(short = 'n', long)]
+    pub limit: Option<usize>,
+
+    /// What to print
+    #[clap(long, default_value_t = OutputFields::Label)]
+    pub format: OutputFields,
+}
+
+/// Returns (node, dominated, immediate dominator) tuples for every node reachable from the roots.
+///
+/// A node dominates another if every path from the roots to it passes through the node, so the
+/// dominated count is how many nodes would drop out of the graph along with it. Results are
+/// sorted by that count, largest first. Nodes only dominated by the roots as a whole have no
+/// immediate dominator.
+pub fn dominators(
+    graph: &DepGraph,
+    args: &DominatorsArgs,
+) -> eyre::Result<Vec<(String, usize, Option<String>)>> {
+    let view = FlatGraphView::new(graph);
+    let all_nodes = graph.all_nodes();
+
+    let roots: Vec<_> = if args.from.is_empty() {
+        view.roots().collect()
+    } else {
+        let globset = build_globset(&args.from)?;
+        view.pg
+            .node_indices()
+            .filter(|&idx| {
+                let id = view.idx_to_id[idx.index()];
+                let text = match args.key {
+                    MatchKey::Id => id,
+                    MatchKey::Label => all_nodes[id].label.as_str(),
+                };
+                globset.is_match(text)
+            })
+            .collect()
+    };
+
+    // A virtual root above all the real ones, since dominators are computed from a single root
+    let mut pg = view.pg.clone();
+    let root = pg.add_node(());
+    for &r in &roots {
+        pg.add_edge(root, r, ());
+    }
+    let doms = simple_fast(&pg, root);
+
+    // Walk the dominator tree from the root, so that sizes can be summed from the leaves up
+    let mut children = vec![Vec::new(); pg.node_count()];
+    for idx in view.pg.node_indices() {
+        if let Some(idom) = doms.immediate_dominator(idx) {
+            children[idom.index()].push(idx);
+        }
+    }
+    let mut order = vec![root];
+    let mut i = 0;
+    while let Some(&idx) = order.get(i) {
+        i += 1;
+        order.extend(&children[idx.index()]);
+    }
+    let mut dominated = vec![0usize; pg.node_count()];
+    for &idx in order.iter().rev() {
+        dominated[idx.index()] = children[idx.index()]
+            .iter()
+            .map(|child| dominated[child.index()] + 1)
+            .sum();
+    }
+
+    let display = |idx: NodeIndex| {
+        let id = view.idx_to_id[idx.index()];
+        match args.format {
+            OutputFields::Id => id.to_string(),
+            OutputFields::Label => all_nodes[id].label.clone(),
+        }
+    };
+    let mut reachable: Vec<_> = order[1..].to_vec();
+    reachable.sort_by_key(|idx| (Reverse(dominated[idx.index()]), idx.index()));
+    if let Some(limit) = args.limit {
+        reachable.truncate(limit);
+    }
+    Ok(reachable
+        .into_iter()
+        .map(|idx| {
+            let idom = doms.immediate_dominator(idx).filter(|&d| d != root);
+            (display(idx), dominated[idx.index()], idom.map(display))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Edge, NodeInfo};
+
+    fn make_graph(nodes: &[&str], edges: &[(&str, &str)]) -> DepGraph {
+        DepGraph {
+            nodes: nodes
+                .iter()
+                .map(|id| (id.to_string(), NodeInfo::new(*id)))
+                .collect(),
+            edges: edges
+                .iter()
+                .map(|(from, to)| Edge {
+                    from: from.to_string(),
+                    to: to.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn report(graph: &DepGraph, args: &DominatorsArgs) -> Vec<(String, usize, Option<String>)> {
+        dominators(graph, args).unwrap()
+    }
+
+    fn row(node: &str, dominated: usize, idom: Option<&str>) -> (String, usize, Option<String>) {
+        (node.to_string(), dominated, idom.map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn empty_graph() {
+        assert!(report(&DepGraph::default(), &DominatorsArgs::default()).is_empty());
+    }
+
+    #[test]
+    fn diamond() {
+        // a -> b -> d, a -> c -> d, d -> e
+        let graph = make_graph(
+            &["a", "b", "c", "d", "e"],
+            &[("a", "b"), ("a", "c"), ("b", "d"), ("c", "d"), ("d", "e")],
+        );
+        assert_eq!(
+            report(&graph, &DominatorsArgs::default()),
+            [
+                row("a", 4, None),
+                row("d", 1, Some("a")),
+                row("b", 0, Some("a")),
+                row("c", 0, Some("a")),
+                row("e", 0, Some("d")),
+            ]
+        );
+    }
+
+    #[test]
+    fn sole_gateway() {
+        // app -> http -> tls -> crypto, app -> json, cli -> json
+        let graph = make_graph(
+            &["app", "cli", "http", "tls", "crypto", "json"],
+            &[
+                ("app", "http"),
+                ("http", "tls"),
+                ("tls", "crypto"),
+                ("app", "json"),
+                ("cli", "json"),
+            ],
+        );
+        assert_eq!(
+            report(&graph, &DominatorsArgs::default()),
+            [
+                row("app", 3, None),
+                row("http", 2, Some("app")),
+                row("tls", 1, Some("http")),
+                row("cli", 0, None),
+                row("crypto", 0, Some("tls")),
+                row("json", 0, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn from_and_limit() {
+        // a -> b -> c, x -> c
+        let graph = make_graph(&["a", "b", "c", "x"], &[("a", "b"), ("b", "c"), ("x", "c")]);
+        let args = DominatorsArgs {
+            from: vec!["b".into()],
+            ..Default::default()
+        };
+        assert_eq!(
+            report(&graph, &args),
+            [row("b", 1, None), row("c", 0, Some("b"))]
+        );
+        let args = DominatorsArgs {
+            limit: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(report(&graph, &args), [row("a", 1, None)]);
+    }
+
+    #[test]
+    fn cycles() {
+        // a -> b -> c -> b, with nothing leading into the cycle d -> e -> d
+        let graph = make_graph(
+            &["a", "b", "c", "d", "e"],
+            &[("a", "b"), ("b", "c"), ("c", "b"), ("d", "e"), ("e", "d")],
+        );
+        assert_eq!(
+            report(&graph, &DominatorsArgs::default()),
+            [
+                row("a", 2, None),
+                row("b", 1, Some("a")),
+                row("c", 0, Some("b"))
+            ]
+        );
+    }
+}
@@ -1,8 +1,10 @@
 use std::collections::HashSet;
 
+use clap::Parser;
 use petgraph::Direction;
 use petgraph::algo::{connected_components, tarjan_scc, toposort};
 
+use super::centrality;
 use crate::{DepGraph, FlatGraphView};
 
 #[derive(Debug)]
@@ -21,6 +23,22 @@ pub struct GraphMetrics {
     pub components: usize,
 }
 
+#[derive(Clone, Debug, Default, Parser)]
+pub struct MetricsArgs {
+    /// Also report the maximum node centralities, which are quadratic in the graph size
+    #[clap(long)]
+    pub centrality: bool,
+}
+
+/// The largest node centralities in the graph, from [`centrality`].
+#[derive(Debug)]
+pub struct CentralityMetrics {
+    pub max_betweenness: f64,
+    pub max_page_rank: f64,
+    pub max_closeness: f64,
+    pub max_k_core: usize,
+}
+
 pub fn metrics(graph: &DepGraph) -> GraphMetrics {
     let view = FlatGraphView::new(graph);
     let node_count = view.pg.node_count();
@@ -106,6 +124,21 @@ pub fn metrics(graph: &DepGraph) -> GraphMetrics {
     }
 }
 
+/// Compute the largest betweenness, PageRank, closeness, and k-core number of any node.
+///
+/// Unlike [`metrics`], betweenness and closeness take a traversal from every node, so this is
+/// opt-in for large graphs.
+pub fn centrality_metrics(graph: &DepGraph) -> CentralityMetrics {
+    let view = FlatGraphView::new(graph);
+    let max = |scores: Vec<f64>| scores.into_iter().fold(0.0, f64::max);
+    CentralityMetrics {
+        max_betweenness: max(centrality::betweenness(&view)),
+        max_page_rank: max(centrality::page_rank(&view)),
+        max_closeness: max(centrality::closeness(&view)),
+        max_k_core: centrality::k_core(&view).into_iter().max().unwrap_or(0),
+    }
+}
+
 // Count "merge points" -- nodes with 2+ parents that share a common ancestor.
 fn count_diamonds(view: &FlatGraphView) -> usize {
     let mut count = 0;
@@ -162,6 +195,16 @@ impl std::fmt::Display for GraphMetrics {
     }
 }
 
+impl std::fmt::Display for CentralityMetrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "max_betweenness\t{:.6}", self.max_betweenness)?;
+        writeln!(f, "max_page_rank\t{:.6}", self.max_page_rank)?;
+        writeln!(f, "max_closeness\t{:.6}", self.max_closeness)?;
+        writeln!(f, "max_k_core\t{}", self.max_k_core)?;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -201,6 +244,12 @@ mod tests {
         assert_eq!(m.cycles, 0);
         assert_eq!(m.diamonds, 0);
         assert_eq!(m.components, 0);
+
+        let c = centrality_metrics(&g);
+        assert_eq!(c.max_betweenness, 0.0);
+        assert_eq!(c.max_page_rank, 0.0);
+        assert_eq!(c.max_closeness, 0.0);
+        assert_eq!(c.max_k_core, 0);
     }
 
     #[test]
@@ -256,6 +305,11 @@ mod tests {
         assert_eq!(m.cycles, 0);
         assert_eq!(m.diamonds, 1);
         assert_eq!(m.components, 1);
+
+        let c = centrality_metrics(&g);
+        assert_eq!(c.max_betweenness, 0.5);
+        assert_eq!(c.max_closeness, 0.75);
+        assert_eq!(c.max_k_core, 2);
     }
 
     #[test]
@@ -1,6 +1,9 @@
+pub mod centrality;
+pub mod dominators;
 pub mod edges;
 pub mod metrics;
 pub mod nodes;
+pub mod paths;
 
 #[derive(Debug, Default, Clone, Copy, clap::ValueEnum)]
 pub enum OutputFields {
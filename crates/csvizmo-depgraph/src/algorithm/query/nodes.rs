@@ -5,7 +5,7 @@ use petgraph::Direction;
 use petgraph::algo::toposort;
 use petgraph::graph::NodeIndex;
 
-use super::OutputFields;
+use super::{OutputFields, centrality};
 use crate::algorithm::{MatchKey, build_globset};
 use crate::{DepGraph, FlatGraphView};
 
@@ -34,6 +34,10 @@ pub enum NodeSort {
     OutDegree,
     Ancestors,
     Descendants,
+    Betweenness,
+    PageRank,
+    Closeness,
+    KCore,
 }
 
 impl std::fmt::Display for NodeSort {
@@ -44,6 +48,31 @@ impl std::fmt::Display for NodeSort {
     }
 }
 
+/// The value a node was sorted by.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NodeScore {
+    Count(usize),
+    Score(f64),
+}
+
+impl NodeScore {
+    fn value(self) -> f64 {
+        match self {
+            Self::Count(n) => n as f64,
+            Self::Score(x) => x,
+        }
+    }
+}
+
+impl std::fmt::Display for NodeScore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Count(n) => write!(f, "{n}"),
+            Self::Score(x) => write!(f, "{x:.6}"),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default, Parser)]
 pub struct NodesArgs {
     /// Which nodes to start from
@@ -95,14 +124,15 @@ pub struct NodesArgs {
     pub depth: Option<usize>,
 }
 
-/// Returns (id, label, count) tuples for matching nodes.
+/// Returns (id, label, score) tuples for matching nodes.
 ///
-/// The count is `Some(n)` when the sort order is numeric (in-degree,
-/// out-degree, ancestors, descendants) and `None` otherwise.
+/// The score is `Some` when the sort order is numeric (in-degree, out-degree,
+/// ancestors, descendants, and the centralities) and `None` otherwise.
+/// Centralities are computed over the whole graph, not just the matching nodes.
 pub fn nodes(
     graph: &DepGraph,
     args: &NodesArgs,
-) -> eyre::Result<Vec<(String, String, Option<usize>)>> {
+) -> eyre::Result<Vec<(String, String, Option<NodeScore>)>> {
     let view = FlatGraphView::new(graph);
     let all_nodes = graph.all_nodes();
 
@@ -200,8 +230,8 @@ fn sort_nodes(
     sort: &NodeSort,
     reverse: bool,
     view: &FlatGraphView,
-) -> Vec<(NodeIndex, Option<usize>)> {
-    let mut result: Vec<(NodeIndex, Option<usize>)> = match sort {
+) -> Vec<(NodeIndex, Option<NodeScore>)> {
+    let mut result: Vec<(NodeIndex, Option<NodeScore>)> = match sort {
         NodeSort::None => nodes.iter().map(|&idx| (idx, None)).collect(),
         NodeSort::Topo => {
             if let Ok(sorted) = toposort(&view.pg, Option::None) {
@@ -216,61 +246,33 @@ fn sort_nodes(
                 nodes.iter().map(|&idx| (idx, None)).collect()
             }
         }
-        NodeSort::InDegree => {
-            let mut v: Vec<(NodeIndex, Option<usize>)> = nodes
-                .iter()
-                .map(|&idx| {
-                    let count = view.pg.neighbors_directed(idx, Direction::Incoming).count();
-                    (idx, Some(count))
-                })
-                .collect();
-            v.sort_by(|a, b| {
-                b.1.cmp(&a.1)
-                    .then_with(|| view.idx_to_id[a.0.index()].cmp(view.idx_to_id[b.0.index()]))
-            });
-            v
+        NodeSort::InDegree => sort_by_score(nodes, view, |idx| {
+            NodeScore::Count(view.pg.neighbors_directed(idx, Direction::Incoming).count())
+        }),
+        NodeSort::OutDegree => sort_by_score(nodes, view, |idx| {
+            NodeScore::Count(view.pg.neighbors_directed(idx, Direction::Outgoing).count())
+        }),
+        NodeSort::Ancestors => sort_by_score(nodes, view, |idx| {
+            NodeScore::Count(view.bfs([idx], Direction::Incoming, None).len() - 1)
+        }),
+        NodeSort::Descendants => sort_by_score(nodes, view, |idx| {
+            NodeScore::Count(view.bfs([idx], Direction::Outgoing, None).len() - 1)
+        }),
+        NodeSort::Betweenness => {
+            let scores = centrality::betweenness(view);
+            sort_by_score(nodes, view, |idx| NodeScore::Score(scores[idx.index()]))
         }
-        NodeSort::OutDegree => {
-            let mut v: Vec<(NodeIndex, Option<usize>)> = nodes
-                .iter()
-                .map(|&idx| {
-                    let count = view.pg.neighbors_directed(idx, Direction::Outgoing).count();
-                    (idx, Some(count))
-                })
-                .collect();
-            v.sort_by(|a, b| {
-                b.1.cmp(&a.1)
-                    .then_with(|| view.idx_to_id[a.0.index()].cmp(view.idx_to_id[b.0.index()]))
-            });
-            v
+        NodeSort::PageRank => {
+            let scores = centrality::page_rank(view);
+            sort_by_score(nodes, view, |idx| NodeScore::Score(scores[idx.index()]))
         }
-        NodeSort::Ancestors => {
-            let mut v: Vec<(NodeIndex, Option<usize>)> = nodes
-                .iter()
-                .map(|&idx| {
-                    let count = view.bfs([idx], Direction::Incoming, None).len() - 1;
-                    (idx, Some(count))
-                })
-                .collect();
-            v.sort_by(|a, b| {
-                b.1.cmp(&a.1)
-                    .then_with(|| view.idx_to_id[a.0.index()].cmp(view.idx_to_id[b.0.index()]))
-            });
-            v
+        NodeSort::Closeness => {
+            let scores = centrality::closeness(view);
+            sort_by_score(nodes, view, |idx| NodeScore::Score(scores[idx.index()]))
         }
-        NodeSort::Descendants => {
-            let mut v: Vec<(NodeIndex, Option<usize>)> = nodes
-                .iter()
-                .map(|&idx| {
-                    let count = view.bfs([idx], Direction::Outgoing, None).len() - 1;
-                    (idx, Some(count))
-                })
-                .collect();
-            v.sort_by(|a, b| {
-                b.1.cmp(&a.1)
-                    .then_with(|| view.idx_to_id[a.0.index()].cmp(view.idx_to_id[b.0.index()]))
-            });
-            v
+        NodeSort::KCore => {
+            let cores = centrality::k_core(view);
+            sort_by_score(nodes, view, |idx| NodeScore::Count(cores[idx.index()]))
         }
     };
     if reverse {
@@ -279,6 +281,23 @@ fn sort_nodes(
     result
 }
 
+/// Sort nodes by descending score, breaking ties by ID.
+fn sort_by_score(
+    nodes: &[NodeIndex],
+    view: &FlatGraphView,
+    score: impl Fn(NodeIndex) -> NodeScore,
+) -> Vec<(NodeIndex, Option<NodeScore>)> {
+    let mut v: Vec<(NodeIndex, NodeScore)> = nodes.iter().map(|&idx| (idx, score(idx))).collect();
+    v.sort_by(|a, b| {
+        b.1.value()
+            .total_cmp(&a.1.value())
+            .then_with(|| view.idx_to_id[a.0.index()].cmp(view.idx_to_id[b.0.index()]))
+    });
+    v.into_iter()
+        .map(|(idx, score)| (idx, Some(score)))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -302,18 +321,22 @@ mod tests {
         }
     }
 
-    fn labels(result: &[(String, String, Option<usize>)]) -> Vec<&str> {
+    fn labels(result: &[(String, String, Option<NodeScore>)]) -> Vec<&str> {
         result.iter().map(|(_, l, _)| l.as_str()).collect()
     }
 
-    fn ids(result: &[(String, String, Option<usize>)]) -> Vec<&str> {
+    fn ids(result: &[(String, String, Option<NodeScore>)]) -> Vec<&str> {
         result.iter().map(|(id, _, _)| id.as_str()).collect()
     }
 
-    fn counts(result: &[(String, String, Option<usize>)]) -> Vec<Option<usize>> {
+    fn counts(result: &[(String, String, Option<NodeScore>)]) -> Vec<Option<NodeScore>> {
         result.iter().map(|(_, _, c)| *c).collect()
     }
 
+    const fn count(n: usize) -> Option<NodeScore> {
+        Some(NodeScore::Count(n))
+    }
+
     #[test]
     fn all_nodes_default() {
         let g = make_graph(
@@ -423,7 +446,7 @@ mod tests {
         let result = nodes(&g, &args).unwrap();
         // a has out-degree 2, b has 1, c has 0 (descending)
         assert_eq!(ids(&result), vec!["a", "b", "c"]);
-        assert_eq!(counts(&result), vec![Some(2), Some(1), Some(0)]);
+        assert_eq!(counts(&result), vec![count(2), count(1), count(0)]);
     }
 
     #[test]
@@ -440,7 +463,7 @@ mod tests {
         let result = nodes(&g, &args).unwrap();
         // c has in-degree 2, a and b have 0
         assert_eq!(ids(&result)[0], "c");
-        assert_eq!(counts(&result), vec![Some(2), Some(0), Some(0)]);
+        assert_eq!(counts(&result), vec![count(2), count(0), count(0)]);
     }
 
     #[test]
@@ -535,4 +558,51 @@ mod tests {
         let result = nodes(&g, &args).unwrap();
         assert_eq!(ids(&result), vec!["node1"]);
     }
+
+    #[test]
+    fn sort_betweenness() {
+        // a -> b -> c, a -> c, d -> b
+        let g = make_graph(
+            &[("a", "A"), ("b", "B"), ("c", "C"), ("d", "D")],
+            &[("a", "b"), ("b", "c"), ("a", "c"), ("d", "b")],
+        );
+        let args = NodesArgs {
+            sort: NodeSort::Betweenness,
+            limit: Some(2),
+            ..Default::default()
+        };
+        let result = nodes(&g, &args).unwrap();
+        // Only d's path to c goes through b
+        assert_eq!(ids(&result), vec!["b", "a"]);
+        assert_eq!(
+            counts(&result),
+            vec![Some(NodeScore::Score(1.0)), Some(NodeScore::Score(0.0))]
+        );
+    }
+
+    #[test]
+    fn sort_k_core_reversed() {
+        // A triangle a-b-c, with d hanging off c
+        let g = make_graph(
+            &[("a", "A"), ("b", "B"), ("c", "C"), ("d", "D")],
+            &[("a", "b"), ("b", "c"), ("a", "c"), ("c", "d")],
+        );
+        let args = NodesArgs {
+            sort: NodeSort::KCore,
+            reverse: true,
+            ..Default::default()
+        };
+        let result = nodes(&g, &args).unwrap();
+        assert_eq!(ids(&result), vec!["d", "c", "b", "a"]);
+        assert_eq!(
+            counts(&result),
+            vec![count(1), count(2), count(2), count(2)]
+        );
+    }
+
+    #[test]
+    fn score_display() {
+        assert_eq!(NodeScore::Count(3).to_string(), "3");
+        assert_eq!(NodeScore::Score(0.25).to_string(), "0.250000");
+    }
 }
@@ -0,0 +1,566 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use clap::Parser;
+
+use super::OutputFields;
+use crate::algorithm::{MatchKey, build_globset};
+use crate::{DepGraph, FlatGraphView};
+
+#[derive(Clone, Debug, Default, Parser)]
+pub struct PathArgs {
+    /// Pattern for the nodes paths start from (repeatable, OR; defaults to the roots)
+    #[clap(short = 'f', long)]
+    pub from: Vec<String>,
+
+    /// Pattern for the nodes paths end at (repeatable, OR)
+    #[clap(short = 't', long, required = true)]
+    pub to: Vec<String>,
+
+    /// Pattern for nodes paths may not pass through (repeatable, OR)
+    #[clap(short = 'x', long)]
+    pub exclude: Vec<String>,
+
+    /// What patterns match against
+    #[clap(long, default_value_t = MatchKey::default())]
+    pub key: MatchKey,
+
+    /// Show every shortest path, instead of only the first
+    #[clap(long, conflicts_with = "shortest")]
+    pub all: bool,
+
+    /// Show the K shortest paths, even if they're longer than the shortest one
+    #[clap(short = 'k', long, value_name = "K")]
+    pub shortest: Option<usize>,
+
+    /// Show only first N paths
+    #[clap(short = 'n', long)]
+    pub limit: Option<usize>,
+
+    /// What to print for nodes
+    #[clap(long, default_value_t = OutputFields::Label)]
+    pub format: OutputFields,
+}
+
+/// A chain of nodes, with the label (or `kind` attr) of each edge between them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DepPath {
+    pub nodes: Vec<String>,
+    /// The edge from `nodes[i]` to `nodes[i + 1]`.
+    pub edges: Vec<Option<String>>,
+}
+
+impl std::fmt::Display for DepPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(first) = self.nodes.first() {
+            f.write_str(first)?;
+        }
+        for (edge, node) in self.edges.iter().zip(&self.nodes[1..]) {
+            match edge {
+                Some(label) => write!(f, " -[{label}]-> {node}")?,
+                None => write!(f, " -> {node}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The graph paths are searched in.
+///
+/// It's the flattened graph, with a virtual start node linked to every source. A path ends as
+/// soon as an edge reaches a target, so every path has at least one edge, even when a node is
+/// both a source and a target, in which case its paths are its cycles.
+struct SearchGraph {
+    outgoing: Vec<Vec<usize>>,
+    /// The edge (as an index into `all_edges`) between two nodes, if it's real.
+    edges: HashMap<(usize, usize), usize>,
+    targets: Vec<bool>,
+    start: usize,
+}
+
+impl SearchGraph {
+    /// Link two nodes, unless a real edge already links them.
+    fn link(&mut self, from: usize, to: usize, edge: Option<usize>) {
+        if let Some(edge) = edge {
+            if self.edges.contains_key(&(from, to)) {
+                return;
+            }
+            self.edges.insert((from, to), edge);
+        }
+        self.outgoing[from].push(to);
+    }
+
+    /// Whether the edge from `from` to `to` ends a path.
+    fn ends(&self, from: usize, to: usize) -> bool {
+        from != self.start && self.targets[to]
+    }
+
+    /// Breadth-first search for a shortest path from `from` to a target, avoiding some nodes and
+    /// edges.
+    ///
+    /// A path may end at the first node after the start even when it's banned, to close a cycle.
+    fn shortest(
+        &self,
+        from: usize,
+        first: Option<usize>,
+        banned_nodes: &HashSet<usize>,
+        banned_edges: &HashSet<(usize, usize)>,
+    ) -> Option<Vec<usize>> {
+        let path = |parents: &HashMap<usize, usize>, mut node: usize| {
+            let mut path = vec![node];
+            while node != from {
+                node = parents[&node];
+                path.push(node);
+            }
+            path.reverse();
+            path
+        };
+        let mut parents = HashMap::from([(from, from)]);
+        let mut queue = VecDeque::from([from]);
+        while let Some(node) = queue.pop_front() {
+            for &next in &self.outgoing[node] {
+                if banned_edges.contains(&(node, next)) {
+                    continue;
+                }
+                if self.ends(node, next) && (!banned_nodes.contains(&next) || first == Some(next)) {
+                    let mut path = path(&parents, node);
+                    path.push(next);
+                    return Some(path);
+                }
+                if !banned_nodes.contains(&next) && !parents.contains_key(&next) {
+                    parents.insert(next, node);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// Every shortest path, in edge order, stopping after `limit`.
+    fn all_shortest(&self, limit: usize) -> Vec<Vec<usize>> {
+        let mut distance = vec![usize::MAX; self.outgoing.len()];
+        distance[self.start] = 0;
+        let mut order = vec![self.start];
+        let mut i = 0;
+        while let Some(&node) = order.get(i) {
+            i += 1;
+            for &next in &self.outgoing[node] {
+                if distance[next] == usize::MAX {
+                    distance[next] = distance[node] + 1;
+                    order.push(next);
+                }
+            }
+        }
+        // The length of the shortest paths, to the node before their target
+        let Some(last) = order
+            .iter()
+            .filter(|&&node| {
+                self.outgoing[node]
+                    .iter()
+                    .any(|&next| self.ends(node, next))
+            })
+            .map(|&node| distance[node])
+            .min()
+        else {
+            return Vec::new();
+        };
+
+        // Nodes that lead to a target along shortest paths, working back from the last layer
+        let mut useful = vec![false; self.outgoing.len()];
+        for &node in order.iter().rev() {
+            useful[node] = if distance[node] == last {
+                self.outgoing[node]
+                    .iter()
+                    .any(|&next| self.ends(node, next))
+            } else {
+                distance[node] < last
+                    && self.outgoing[node]
+                        .iter()
+                        .any(|&next| distance[next] == distance[node] + 1 && useful[next])
+            };
+        }
+
+        let mut paths = Vec::new();
+        if useful[self.start] {
+            self.walk(
+                &mut vec![self.start],
+                &distance,
+                &useful,
+                last,
+                &mut paths,
+                limit,
+            );
+        }
+        paths
+    }
+
+    fn walk(
+        &self,
+        path: &mut Vec<usize>,
+        distance: &[usize],
+        useful: &[bool],
+        last: usize,
+        paths: &mut Vec<Vec<usize>>,
+        limit: usize,
+    ) {
+        let node = *path.last().unwrap();
+        for &next in &self.outgoing[node] {
+            if paths.len() >= limit {
+                return;
+            }
+            if distance[node] == last {
+                if self.ends(node, next) {
+                    let mut found = path.clone();
+                    found.push(next);
+                    paths.push(found);
+                }
+            } else if distance[next] == distance[node] + 1 && useful[next] {
+                path.push(next);
+                self.walk(path, distance, useful, last, paths, limit);
+                path.pop();
+            }
+        }
+    }
+
+    /// The `k` shortest simple paths, with Yen's algorithm.
+    fn k_shortest(&self, k: usize) -> Vec<Vec<usize>> {
+        let mut found = Vec::new();
+        let Some(first) = self.shortest(self.start, None, &HashSet::new(), &HashSet::new()) else {
+            return found;
+        };
+        found.push(first);
+        let mut candidates: Vec<Vec<usize>> = Vec::new();
+        while found.len() < k {
+            let last: &Vec<usize> = found.last().unwrap();
+            for i in 0..last.len() - 1 {
+                // Deviate from the last path at its i-th node, without reusing the root before
+                // it or an edge that an already found path takes from the same root
+                let root = &last[..=i];
+                let banned_edges = found
+                    .iter()
+                    .filter(|p| p.len() > i + 1 && p[..=i] == *root)
+                    .map(|p| (p[i], p[i + 1]))
+                    .collect();
+                let banned_nodes = root[..i].iter().copied().collect();
+                let spur =
+                    self.shortest(last[i], root.get(1).copied(), &banned_nodes, &banned_edges);
+                if let Some(spur) = spur {
+                    let mut path = root[..i].to_vec();
+                    path.extend(spur);
+                    if !found.contains(&path) && !candidates.contains(&path) {
+                        candidates.push(path);
+                    }
+                }
+            }
+            // The first of the shortest candidates, to keep ties in edge order
+            let Some((best, _)) = candidates.iter().enumerate().min_by_key(|(_, p)| p.len()) else {
+                break;
+            };
+            found.push(candidates.remove(best));
+        }
+        found.truncate(k);
+        found
+    }
+}
+
+/// Find the paths from nodes matching `--from` to nodes matching `--to`.
+///
+/// By default, this is a shortest path, or with `--all`, every shortest path. With `-k`, it's
+/// the `k` shortest simple paths, which answers why one node depends on another when the
+/// shortest path isn't the only reason. Parallel edges count as one, and when a node matches
+/// both patterns, its paths back to itself are its cycles.
+pub fn paths(graph: &DepGraph, args: &PathArgs) -> eyre::Result<Vec<DepPath>> {
+    let view = FlatGraphView::new(graph);
+    let all_nodes = graph.all_nodes();
+    let all_edges = graph.all_edges();
+
+    let text = |id: &str| match args.key {
+        MatchKey::Id => id.to_string(),
+        MatchKey::Label => all_nodes[id].label.clone(),
+    };
+    let matching = |patterns: &[String]| -> eyre::Result<Vec<bool>> {
+        let globset = build_globset(patterns)?;
+        Ok(view
+            .idx_to_id
+            .iter()
+            .map(|id| globset.is_match(text(id)))
+            .collect())
+    };
+    let excluded = if args.exclude.is_empty() {
+        vec![false; view.idx_to_id.len()]
+    } else {
+        matching(&args.exclude)?
+    };
+    let sources: Vec<usize> = if args.from.is_empty() {
+        view.roots().map(|idx| idx.index()).collect()
+    } else {
+        let from = matching(&args.from)?;
+        (0..from.len()).filter(|&i| from[i]).collect()
+    };
+    let to = matching(&args.to)?;
+
+    let start = view.idx_to_id.len();
+    let mut search = SearchGraph {
+        outgoing: vec![Vec::new(); start + 1],
+        edges: HashMap::new(),
+        targets: (0..=start)
+            .map(|i| i < start && to[i] && !excluded[i])
+            .collect(),
+        start,
+    };
+    for &source in sources.iter().filter(|&&s| !excluded[s]) {
+        search.link(start, source, None);
+    }
+    for (i, edge) in all_edges.iter().enumerate() {
+        let from = view.id_to_idx.get(edge.from.as_str());
+        let to = view.id_to_idx.get(edge.to.as_str());
+        if let (Some(from), Some(to)) = (from, to)
+            && !excluded[from.index()]
+            && !excluded[to.index()]
+        {
+            search.link(from.index(), to.index(), Some(i));
+        }
+    }
+
+    let limit = args.limit.unwrap_or(usize::MAX);
+    let mut found = match (args.all, args.shortest) {
+        (_, Some(k)) => search.k_shortest(k),
+        (true, None) => search.all_shortest(limit),
+        (false, None) => search.k_shortest(1),
+    };
+    found.truncate(limit);
+
+    let display = |node: usize| {
+        let id = view.idx_to_id[node];
+        match args.format {
+            OutputFields::Id => id.to_string(),
+            OutputFields::Label => all_nodes[id].label.clone(),
+        }
+    };
+    Ok(found
+        .iter()
+        .map(|path| DepPath {
+            nodes: path[1..].iter().map(|&n| display(n)).collect(),
+            edges: path[1..]
+                .windows(2)
+                .map(|pair| {
+                    let edge = &all_edges[search.edges[&(pair[0], pair[1])]];
+                    edge.label
+                        .clone()
+                        .or_else(|| edge.attrs.get("kind").cloned())
+                })
+                .collect(),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexMap;
+
+    use super::*;
+    use crate::{Edge, NodeInfo};
+
+    fn make_graph(nodes: &[&str], edges: &[(&str, &str)]) -> DepGraph {
+        DepGraph {
+            nodes: nodes
+                .iter()
+                .map(|id| (id.to_string(), NodeInfo::new(*id)))
+                .collect(),
+            edges: edges
+                .iter()
+                .map(|(from, to)| Edge {
+                    from: from.to_string(),
+                    to: to.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn path_args(from: &[&str], to: &[&str]) -> PathArgs {
+        PathArgs {
+            from: from.iter().map(|s| s.to_string()).collect(),
+            to: to.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn path_strings(graph: &DepGraph, args: &PathArgs) -> Vec<String> {
+        paths(graph, args)
+            .unwrap()
+            .iter()
+            .map(|p| p.to_string())
+            .collect()
+    }
+
+    // a -> b -> d, a -> c -> d
+    fn diamond() -> DepGraph {
+        make_graph(
+            &["a", "b", "c", "d"],
+            &[("a", "b"), ("a", "c"), ("b", "d"), ("c", "d")],
+        )
+    }
+
+    #[test]
+    fn shortest_from_roots() {
+        assert_eq!(
+            path_strings(&diamond(), &path_args(&[], &["d"])),
+            ["a -> b -> d"]
+        );
+    }
+
+    #[test]
+    fn all_shortest() {
+        let args = PathArgs {
+            all: true,
+            ..path_args(&[], &["d"])
+        };
+        assert_eq!(
+            path_strings(&diamond(), &args),
+            ["a -> b -> d", "a -> c -> d"]
+        );
+    }
+
+    #[test]
+    fn all_shortest_limit() {
+        let args = PathArgs {
+            all: true,
+            limit: Some(1),
+            ..path_args(&[], &["d"])
+        };
+        assert_eq!(path_strings(&diamond(), &args), ["a -> b -> d"]);
+    }
+
+    #[test]
+    fn k_shortest() {
+        // a -> b -> c -> d, a -> d, b -> d
+        let graph = make_graph(
+            &["a", "b", "c", "d"],
+            &[("a", "b"), ("b", "c"), ("c", "d"), ("a", "d"), ("b", "d")],
+        );
+        let args = PathArgs {
+            shortest: Some(5),
+            ..path_args(&["a"], &["d"])
+        };
+        assert_eq!(
+            path_strings(&graph, &args),
+            ["a -> d", "a -> b -> d", "a -> b -> c -> d"]
+        );
+    }
+
+    #[test]
+    fn parallel_edges_count_once() {
+        let graph = make_graph(&["a", "b"], &[("a", "b"), ("a", "b")]);
+        let args = PathArgs {
+            shortest: Some(3),
+            ..path_args(&["a"], &["b"])
+        };
+        assert_eq!(path_strings(&graph, &args), ["a -> b"]);
+    }
+
+    #[test]
+    fn exclude() {
+        let args = PathArgs {
+            exclude: vec!["b".into()],
+            ..path_args(&[], &["d"])
+        };
+        assert_eq!(path_strings(&diamond(), &args), ["a -> c -> d"]);
+    }
+
+    #[test]
+    fn no_path() {
+        assert!(path_strings(&diamond(), &path_args(&["b"], &["c"])).is_empty());
+        assert!(path_strings(&diamond(), &path_args(&["d"], &["a"])).is_empty());
+        assert!(path_strings(&diamond(), &path_args(&["a"], &["nope"])).is_empty());
+    }
+
+    #[test]
+    fn source_is_not_its_own_path() {
+        // Matching every node still needs an edge
+        assert_eq!(
+            path_strings(&diamond(), &path_args(&["*"], &["*"])),
+            ["a -> b"]
+        );
+    }
+
+    #[test]
+    fn cycles() {
+        // a -> b -> c -> a, c -> c
+        let graph = make_graph(
+            &["a", "b", "c"],
+            &[("a", "b"), ("b", "c"), ("c", "a"), ("c", "c")],
+        );
+        assert_eq!(
+            path_strings(&graph, &path_args(&["a"], &["a"])),
+            ["a -> b -> c -> a"]
+        );
+        let args = PathArgs {
+            shortest: Some(3),
+            ..path_args(&["c"], &["c"])
+        };
+        assert_eq!(path_strings(&graph, &args), ["c -> c", "c -> a -> b -> c"]);
+
+        // a -> b -> a, a -> c -> a
+        let graph = make_graph(
+            &["a", "b", "c"],
+            &[("a", "b"), ("b", "a"), ("a", "c"), ("c", "a")],
+        );
+        let args = PathArgs {
+            shortest: Some(3),
+            ..path_args(&["a"], &["a"])
+        };
+        assert_eq!(path_strings(&graph, &args), ["a -> b -> a", "a -> c -> a"]);
+    }
+
+    #[test]
+    fn k_shortest_paths_are_simple() {
+        // a -> t -> x -> t would pass through the target twice
+        let graph = make_graph(
+            &["a", "t", "x", "y"],
+            &[("a", "t"), ("t", "x"), ("x", "t"), ("a", "y"), ("y", "x")],
+        );
+        let args = PathArgs {
+            shortest: Some(5),
+            ..path_args(&["a"], &["t"])
+        };
+        assert_eq!(path_strings(&graph, &args), ["a -> t", "a -> y -> x -> t"]);
+    }
+
+    #[test]
+    fn edge_labels_and_kinds() {
+        let mut graph = make_graph(&["app", "lib", "sys"], &[("app", "lib"), ("lib", "sys")]);
+        graph.edges[0].label = Some("uses".into());
+        graph.edges[1].attrs = IndexMap::from([("kind".into(), "build".into())]);
+        assert_eq!(
+            path_strings(&graph, &path_args(&[], &["sys"])),
+            ["app -[uses]-> lib -[build]-> sys"]
+        );
+    }
+
+    #[test]
+    fn format_and_key() {
+        let graph = DepGraph {
+            nodes: IndexMap::from([
+                ("1".into(), NodeInfo::new("openssl")),
+                ("2".into(), NodeInfo::new("openssl-sys")),
+            ]),
+            edges: vec![Edge {
+                from: "1".into(),
+                to: "2".into(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let args = PathArgs {
+            key: MatchKey::Id,
+            format: OutputFields::Id,
+            ..path_args(&[], &["2"])
+        };
+        assert_eq!(path_strings(&graph, &args), ["1 -> 2"]);
+        assert_eq!(
+            path_strings(&graph, &path_args(&[], &["*-sys"])),
+            ["openssl -> openssl-sys"]
+        );
+    }
+}
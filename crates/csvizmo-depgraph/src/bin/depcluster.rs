@@ -1,11 +1,11 @@
-use std::io::{IsTerminal, Read};
+use std::io::IsTerminal;
 use std::path::PathBuf;
 
 use clap::Parser;
 use csvizmo_depgraph::algorithm::cluster::{graphrs_bridge, lpa};
 use csvizmo_depgraph::emit::OutputFormat;
 use csvizmo_depgraph::parse::InputFormat;
-use csvizmo_utils::stdio::{get_input_reader, get_output_writer};
+use csvizmo_utils::stdio::get_output_writer;
 
 /// Cluster nodes in a dependency graph using community detection algorithms.
 ///
@@ -96,9 +96,7 @@ fn main() -> eyre::Result<()> {
     let input_path = args.input.filter(|p| !is_stdio(p));
     let output_path = args.output.filter(|p| !is_stdio(p));
 
-    let mut input = get_input_reader(&input_path)?;
-    let mut input_text = String::new();
-    input.read_to_string(&mut input_text)?;
+    let input_text = csvizmo_depgraph::parse::read_input(input_path.as_deref(), args.input_format)?;
 
     let input_format = csvizmo_depgraph::parse::resolve_input_format(
         args.input_format,
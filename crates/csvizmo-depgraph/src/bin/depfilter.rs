@@ -1,4 +1,4 @@
-use std::io::{IsTerminal, Read};
+use std::io::IsTerminal;
 use std::path::PathBuf;
 
 use clap::{Parser, Subcommand};
@@ -9,7 +9,7 @@ use csvizmo_depgraph::algorithm::select::SelectArgs;
 use csvizmo_depgraph::algorithm::slice::SliceArgs;
 use csvizmo_depgraph::emit::OutputFormat;
 use csvizmo_depgraph::parse::InputFormat;
-use csvizmo_utils::stdio::{get_input_reader, get_output_writer};
+use csvizmo_utils::stdio::get_output_writer;
 
 /// Select or exclude nodes from dependency graphs.
 ///
@@ -77,9 +77,7 @@ fn main() -> eyre::Result<()> {
     let input_path = args.input.filter(|p| !is_stdio(p));
     let output_path = args.output.filter(|p| !is_stdio(p));
 
-    let mut input = get_input_reader(&input_path)?;
-    let mut input_text = String::new();
-    input.read_to_string(&mut input_text)?;
+    let input_text = csvizmo_depgraph::parse::read_input(input_path.as_deref(), args.input_format)?;
 
     let input_format = csvizmo_depgraph::parse::resolve_input_format(
         args.input_format,
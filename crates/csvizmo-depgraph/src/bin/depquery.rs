@@ -1,17 +1,39 @@
-use std::io::{IsTerminal, Read, Write};
+use std::io::{IsTerminal, Write};
 use std::path::PathBuf;
 
 use clap::{Parser, Subcommand};
+use csvizmo_depgraph::algorithm::critical_path::CriticalPathArgs;
+use csvizmo_depgraph::algorithm::query::OutputFields;
+use csvizmo_depgraph::algorithm::query::dominators::DominatorsArgs;
 use csvizmo_depgraph::algorithm::query::edges::EdgesArgs;
+use csvizmo_depgraph::algorithm::query::metrics::{self, MetricsArgs};
 use csvizmo_depgraph::algorithm::query::nodes::NodesArgs;
-use csvizmo_depgraph::algorithm::query::{OutputFields, metrics};
+use csvizmo_depgraph::algorithm::query::paths::PathArgs;
 use csvizmo_depgraph::parse::InputFormat;
-use csvizmo_utils::stdio::get_input_reader;
+
+/// Arguments for the `critical-path` subcommand.
+#[derive(Debug, clap::Parser)]
+struct CriticalArgs {
+    #[clap(flatten)]
+    weights: CriticalPathArgs,
+
+    /// List the slack of every node, least first, instead of the critical chain
+    #[clap(long)]
+    slack: bool,
+
+    /// Show only first N results
+    #[clap(short = 'n', long)]
+    limit: Option<usize>,
+
+    /// What to print
+    #[clap(long, default_value_t = OutputFields::Label)]
+    format: OutputFields,
+}
 
 /// Query properties of dependency graphs.
 ///
 /// Produces plain text output (not graph output) answering
-/// "what's in this graph?" -- listing nodes, edges, and computing metrics.
+/// "what's in this graph?" -- listing nodes, edges, and paths, and computing metrics.
 #[derive(Debug, Parser)]
 #[clap(version, verbatim_doc_comment)]
 struct Args {
@@ -38,7 +60,16 @@ enum Command {
     /// List edges with optional filtering and sorting
     Edges(EdgesArgs),
     /// Compute and display graph metrics
-    Metrics,
+    Metrics(MetricsArgs),
+    /// Explain why one node depends on another, by listing the paths between them
+    Path(PathArgs),
+    /// List each node's immediate dominator, and how many nodes only it pulls in
+    Dominators(DominatorsArgs),
+    /// Find the longest weighted chain of dependencies through a DAG
+    ///
+    /// Prints the critical chain in build order as node, weight, and finish time, or with
+    /// --slack, every node's weight and how much it could slip without delaying the whole graph.
+    CriticalPath(CriticalArgs),
 }
 
 fn main() -> eyre::Result<()> {
@@ -63,9 +94,7 @@ fn main() -> eyre::Result<()> {
     let is_stdio = |p: &PathBuf| p.as_os_str() == "-";
     let input_path = args.input.filter(|p| !is_stdio(p));
 
-    let mut input = get_input_reader(&input_path)?;
-    let mut input_text = String::new();
-    input.read_to_string(&mut input_text)?;
+    let input_text = csvizmo_depgraph::parse::read_input(input_path.as_deref(), args.input_format)?;
 
     let input_format = csvizmo_depgraph::parse::resolve_input_format(
         args.input_format,
@@ -107,9 +136,68 @@ fn main() -> eyre::Result<()> {
                 }
             }
         }
-        Command::Metrics => {
+        Command::Metrics(metrics_args) => {
             let m = metrics::metrics(&graph);
             write!(out, "{m}")?;
+            if metrics_args.centrality {
+                let c = metrics::centrality_metrics(&graph);
+                write!(out, "{c}")?;
+            }
+        }
+        Command::Path(path_args) => {
+            let result = csvizmo_depgraph::algorithm::query::paths::paths(&graph, path_args)?;
+            if result.is_empty() {
+                tracing::warn!("No paths found");
+            }
+            for path in &result {
+                writeln!(out, "{path}")?;
+            }
+        }
+        Command::Dominators(dominators_args) => {
+            let result = csvizmo_depgraph::algorithm::query::dominators::dominators(
+                &graph,
+                dominators_args,
+            )?;
+            for (node, dominated, idom) in &result {
+                let idom = idom.as_deref().unwrap_or_default();
+                writeln!(out, "{node}\t{dominated}\t{idom}")?;
+            }
+        }
+        Command::CriticalPath(critical_args) => {
+            let critical = csvizmo_depgraph::algorithm::critical_path::critical_path(
+                &graph,
+                &critical_args.weights,
+            )?;
+            tracing::info!("Critical path length: {}", critical.length);
+            let all_nodes = graph.all_nodes();
+            let display = |id: &str| match critical_args.format {
+                OutputFields::Id => id.to_string(),
+                OutputFields::Label => all_nodes[id].label.clone(),
+            };
+            let rows: Vec<(usize, f64)> = if critical_args.slack {
+                let mut order: Vec<usize> = (0..critical.nodes.len()).collect();
+                order.sort_by(|&a, &b| {
+                    critical.nodes[a]
+                        .slack
+                        .total_cmp(&critical.nodes[b].slack)
+                        .then(a.cmp(&b))
+                });
+                order
+                    .into_iter()
+                    .map(|i| (i, critical.nodes[i].slack))
+                    .collect()
+            } else {
+                critical
+                    .chain
+                    .iter()
+                    .map(|&i| (i, critical.nodes[i].finish))
+                    .collect()
+            };
+            let limit = critical_args.limit.unwrap_or(usize::MAX);
+            for (i, value) in rows.into_iter().take(limit) {
+                let node = &critical.nodes[i];
+                writeln!(out, "{}\t{}\t{value}", display(&node.id), node.weight)?;
+            }
         }
     }
 
@@ -1,13 +1,14 @@
-use std::io::{IsTerminal, Read};
+use std::io::IsTerminal;
 use std::path::PathBuf;
 
 use clap::{Parser, Subcommand};
+use csvizmo_depgraph::algorithm::critical_path::CriticalPathArgs;
 use csvizmo_depgraph::algorithm::shorten::ShortenArgs;
 use csvizmo_depgraph::algorithm::sub::{SubKey, Substitution};
 use csvizmo_depgraph::emit::OutputFormat;
 use csvizmo_depgraph::parse::InputFormat;
 use csvizmo_depgraph::{DepGraph, algorithm};
-use csvizmo_utils::stdio::{get_input_reader, get_output_writer};
+use csvizmo_utils::stdio::get_output_writer;
 
 /// Arguments for the `sub` subcommand.
 #[derive(Debug, clap::Parser)]
@@ -84,6 +85,11 @@ enum Command {
     Merge(MergeArgs),
     /// Flatten subgraphs into a single top-level graph
     Flatten,
+    /// Highlight the longest weighted chain of dependencies through a DAG
+    ///
+    /// Colors the critical chain red, and adds a `slack` attr to every node with how much it
+    /// could slip without delaying the whole graph.
+    CriticalPath(CriticalPathArgs),
 }
 
 fn main() -> eyre::Result<()> {
@@ -154,6 +160,10 @@ fn main() -> eyre::Result<()> {
                     algorithm::sub::sub(&graph, &substitution, &key)
                 }
                 Command::Flatten => algorithm::flatten::flatten(&graph),
+                Command::CriticalPath(critical_args) => {
+                    let critical = algorithm::critical_path::critical_path(&graph, critical_args)?;
+                    algorithm::critical_path::annotate(&graph, &critical)
+                }
                 Command::Merge(_) => unreachable!(),
             }
         }
@@ -169,9 +179,7 @@ fn main() -> eyre::Result<()> {
 fn read_graph(path: Option<&PathBuf>, input_format: Option<InputFormat>) -> eyre::Result<DepGraph> {
     let is_stdio = |p: &PathBuf| p.as_os_str() == "-";
     let file_path: Option<PathBuf> = path.filter(|p| !is_stdio(p)).cloned();
-    let mut reader = get_input_reader(&file_path)?;
-    let mut text = String::new();
-    reader.read_to_string(&mut text)?;
+    let text = csvizmo_depgraph::parse::read_input(file_path.as_deref(), input_format)?;
     let fmt =
         csvizmo_depgraph::parse::resolve_input_format(input_format, file_path.as_deref(), &text)?;
     csvizmo_depgraph::parse::parse(fmt, &text)
@@ -1,4 +1,4 @@
-use std::io::{IsTerminal, Read};
+use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
 
 use clap::{Parser, Subcommand};
@@ -6,7 +6,7 @@ use csvizmo_depgraph::DepGraph;
 use csvizmo_depgraph::algorithm::diff;
 use csvizmo_depgraph::emit::OutputFormat;
 use csvizmo_depgraph::parse::InputFormat;
-use csvizmo_utils::stdio::{get_input_reader, get_output_writer};
+use csvizmo_utils::stdio::get_output_writer;
 
 /// Compute differences between two dependency graphs.
 ///
@@ -160,9 +160,7 @@ fn read_graph(path: &Path, input_format: Option<InputFormat>) -> eyre::Result<De
     } else {
         Some(path.to_path_buf())
     };
-    let mut reader = get_input_reader(&file_path)?;
-    let mut text = String::new();
-    reader.read_to_string(&mut text)?;
+    let text = csvizmo_depgraph::parse::read_input(file_path.as_deref(), input_format)?;
     let fmt =
         csvizmo_depgraph::parse::resolve_input_format(input_format, file_path.as_deref(), &text)?;
     csvizmo_depgraph::parse::parse(fmt, &text)
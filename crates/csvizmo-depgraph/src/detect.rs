@@ -18,9 +18,31 @@ impl InputFormat {
     /// new variant without a detection rule is a compile error.
     fn matches_content(&self, input: &str) -> bool {
         match self {
-            Self::CargoMetadata => is_json(input),
+            Self::CargoMetadata => is_cargo_metadata(input),
+            Self::Json => first_json_key(input) == Some("graph"),
+            Self::CycloneDx => is_cyclonedx(input),
+            Self::Spdx => is_spdx(input),
+            Self::CmakeFileApi => is_cmake_file_api(input),
+            Self::CompileCommands => is_compile_commands(input),
+            Self::GoList => matches!(first_json_key(input), Some("Dir" | "ImportPath")),
+            Self::BazelQuery => is_bazel_query(input),
+            Self::NpmLock => is_npm_lock(input),
+            Self::Pipdeptree => is_pipdeptree(input),
+            Self::GraphMl => is_graphml(input),
+            Self::PnpmLock => first_nonblank(input).starts_with("lockfileVersion:"),
+            Self::UvLock => is_uv_lock(input),
+            Self::PoetryLock => is_poetry_lock(input),
+            Self::RustModules => is_rust_modules(input),
             Self::Mermaid => is_mermaid(input),
+            Self::PlantUml => first_nonblank(input).starts_with("@startuml"),
             Self::Dot => is_dot(input),
+            Self::Ninja => is_ninja(input),
+            Self::GoModGraph => is_go_mod_graph(input),
+            Self::Elf => is_elf(input),
+            Self::Gradle => is_gradle(input),
+            Self::Maven => is_maven(input),
+            Self::Dpkg => first_nonblank(input).starts_with("Package: "),
+            Self::Csv => is_csv(input),
             Self::Tgf => is_tgf(input),
             Self::Depfile => is_depfile(input),
             Self::CargoTree => is_cargo_tree(input),
@@ -30,10 +52,95 @@ impl InputFormat {
     }
 }
 
-/// First non-blank line starts with `{`.
-fn is_json(input: &str) -> bool {
-    // TODO: This will need some attention if we add more JSON formats (like conan)
+/// The first key of a top-level JSON object, if the input starts with one.
+///
+/// Only looks at the start of the input, so truncated or very large documents are cheap to
+/// detect. Keys containing escapes are not unescaped.
+fn first_json_key(input: &str) -> Option<&str> {
+    let rest = input.trim_start().strip_prefix('{')?;
+    let rest = rest.trim_start().strip_prefix('"')?;
+    let end = rest.find('"')?;
+    Some(&rest[..end])
+}
+
+/// A JSON object whose first key is `packages`, as cargo writes it, or with both `packages` and
+/// `workspace_members` keys anywhere, for re-sorted output like `jq -S`'s.
+fn is_cargo_metadata(input: &str) -> bool {
+    first_json_key(input) == Some("packages")
+        || (first_nonblank(input).starts_with('{')
+            && input.contains("\"packages\"")
+            && input.contains("\"workspace_members\""))
+}
+
+/// A JSON object with `"bomFormat": "CycloneDX"`. Generators don't agree on key order, and
+/// often write `$schema` first.
+fn is_cyclonedx(input: &str) -> bool {
     first_nonblank(input).starts_with('{')
+        && input.contains("\"bomFormat\"")
+        && input.contains("\"CycloneDX\"")
+}
+
+/// A JSON object with an `spdxVersion` key.
+fn is_spdx(input: &str) -> bool {
+    first_nonblank(input).starts_with('{') && input.contains("\"spdxVersion\"")
+}
+
+/// A JSON object stream containing a CMake File API codemodel or target object.
+///
+/// CMake writes reply keys in sorted order, so the first key can't be used. Every target object
+/// has a `backtraceGraph`, and the codemodel object has `"kind" : "codemodel"`.
+fn is_cmake_file_api(input: &str) -> bool {
+    first_nonblank(input).starts_with('{')
+        && (input.contains("\"backtraceGraph\"") || input.contains("\"codemodel\""))
+}
+
+/// A JSON array of objects with a compilation database's `directory` and `file` keys.
+fn is_compile_commands(input: &str) -> bool {
+    first_nonblank(input).starts_with('[')
+        && input.contains("\"directory\"")
+        && input.contains("\"file\"")
+}
+
+/// A Bazel `Target` (`streamed_jsonproto`) or `QueryResult` (`jsonproto`) object.
+fn is_bazel_query(input: &str) -> bool {
+    matches!(first_json_key(input), Some("type" | "target"))
+        && (input.contains("\"ruleClass\"") || input.contains("\"sourceFile\""))
+}
+
+/// A JSON object with a `lockfileVersion` key. npm writes `name` and `version` first.
+fn is_npm_lock(input: &str) -> bool {
+    first_nonblank(input).starts_with('{') && input.contains("\"lockfileVersion\"")
+}
+
+/// A JSON array of objects with pipdeptree's `package_name` and `installed_version` keys.
+fn is_pipdeptree(input: &str) -> bool {
+    first_nonblank(input).starts_with('[')
+        && input.contains("\"package_name\"")
+        && input.contains("\"installed_version\"")
+}
+
+/// Has `[[package]]` tables, and starts with uv's `version = N` header.
+///
+/// `Cargo.lock` has the same layout, but starts with an `@generated` comment.
+fn is_uv_lock(input: &str) -> bool {
+    first_nonblank(input).starts_with("version = ") && input.contains("[[package]]")
+}
+
+/// Has `[[package]]` tables and a `[metadata]` table with a `lock-version`.
+fn is_poetry_lock(input: &str) -> bool {
+    input.contains("[[package]]") && input.contains("[metadata]") && input.contains("lock-version")
+}
+
+/// First non-blank line is a `tail -n +1` style `==> path.rs <==` header.
+fn is_rust_modules(input: &str) -> bool {
+    let first = first_nonblank(input).trim_end();
+    first.starts_with("==> ") && first.ends_with(".rs <==")
+}
+
+/// First non-blank line starts with `<` (XML declaration or root element) and the document
+/// contains a `<graphml` root element.
+fn is_graphml(input: &str) -> bool {
+    first_nonblank(input).starts_with('<') && input.contains("<graphml")
 }
 
 /// First non-blank line starts with `flowchart`, or `graph` followed by a
@@ -57,6 +164,73 @@ fn is_dot(input: &str) -> bool {
     first.starts_with("digraph") || first.starts_with("strict graph") || first.starts_with("graph")
 }
 
+/// Any line is a `ninja -t deps` header or a `.ninja_log` version header.
+///
+/// `ninja -t targets` output on its own is indistinguishable from a depfile.
+fn is_ninja(input: &str) -> bool {
+    input
+        .lines()
+        .any(|l| l.contains(": #deps ") || l.starts_with("# ninja log v"))
+}
+
+/// Every non-blank line is two tokens, and the second is a `module@version`.
+fn is_go_mod_graph(input: &str) -> bool {
+    let mut any = false;
+    for line in input.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut parts = line.split_whitespace();
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(_), Some(to), None) if to.contains('@') => any = true,
+            _ => return false,
+        }
+    }
+    any
+}
+
+/// Any line is a `readelf -d` NEEDED entry, an `objdump -p` header for an ELF file, or an `ldd`
+/// resolution.
+fn is_elf(input: &str) -> bool {
+    input.lines().any(|l| {
+        (l.contains("(NEEDED)") && l.contains("Shared library: ["))
+            || l.contains(":     file format elf")
+            || (l.starts_with('\t')
+                && l.contains(" => ")
+                && (l.contains(" (0x") || l.ends_with("not found")))
+    })
+}
+
+/// Any line is a `gradle dependencies` tree line, which use `+--- ` and `\--- ` markers.
+fn is_gradle(input: &str) -> bool {
+    input.lines().any(|l| {
+        let l = l.trim_start_matches(['|', ' ']);
+        l.starts_with("+--- ") || l.starts_with("\\--- ")
+    })
+}
+
+/// Any line is a `mvn dependency:tree` tree line: a `+- ` or `\- ` marker followed by
+/// `groupId:artifactId:type:version:scope` coordinates, with or without the `[INFO]` log prefix.
+fn is_maven(input: &str) -> bool {
+    input.lines().any(|l| {
+        let l = l.strip_prefix("[INFO]").unwrap_or(l);
+        let l = l.trim_start_matches(['|', ' ']);
+        (l.starts_with("+- ") || l.starts_with("\\- "))
+            && l.split_whitespace()
+                .nth(1)
+                .is_some_and(|c| c.matches(':').count() >= 4)
+    })
+}
+
+/// A CSV header with `from` and `to` columns, or starting with an `id` column.
+fn is_csv(input: &str) -> bool {
+    let header: Vec<&str> = first_nonblank(input)
+        .split(',')
+        .map(|h| h.trim().trim_matches('"'))
+        .collect();
+    (header.contains(&"from") && header.contains(&"to")) || (header.len() > 1 && header[0] == "id")
+}
+
 /// Any line is exactly `#` (TGF node/edge separator).
 fn is_tgf(input: &str) -> bool {
     input.lines().any(|l| l.trim() == "#")
@@ -157,6 +331,199 @@ mod tests {
         assert_eq!(detect(input), Some(InputFormat::CargoMetadata));
     }
 
+    #[test]
+    fn detect_cargo_metadata_compact() {
+        let input = r#"{"packages":[],"workspace_members":[]}"#;
+        assert_eq!(detect(input), Some(InputFormat::CargoMetadata));
+    }
+
+    #[test]
+    fn detect_cargo_metadata_sorted_keys() {
+        // As written by `cargo metadata | jq -S`
+        let input = r#"{
+  "metadata": null,
+  "packages": [],
+  "resolve": null,
+  "target_directory": "/tmp/target",
+  "version": 1,
+  "workspace_members": []
+}"#;
+        assert_eq!(detect(input), Some(InputFormat::CargoMetadata));
+    }
+
+    #[test]
+    fn detect_jgf() {
+        let input = include_str!("../../../data/depconv/small.jgf");
+        assert_eq!(detect(input), Some(InputFormat::Json));
+        // Multi-graph documents aren't supported by the parser, so they aren't detected either
+        assert!(!InputFormat::Json.matches_content(r#"{"graphs": []}"#));
+    }
+
+    #[test]
+    fn detect_unknown_json() {
+        let input = r#"{"name": "foo", "version": "1.0"}"#;
+        assert!(!InputFormat::CargoMetadata.matches_content(input));
+        assert!(!InputFormat::Json.matches_content(input));
+    }
+
+    #[test]
+    fn detect_cyclonedx() {
+        let input = include_str!("../../../data/depconv/small.cdx.json");
+        assert_eq!(detect(input), Some(InputFormat::CycloneDx));
+        let input = r#"{"$schema": "http://cyclonedx.org/schema/bom-1.5.schema.json", "bomFormat": "CycloneDX"}"#;
+        assert_eq!(detect(input), Some(InputFormat::CycloneDx));
+    }
+
+    #[test]
+    fn detect_spdx() {
+        let input = include_str!("../../../data/depconv/small.spdx.json");
+        assert_eq!(detect(input), Some(InputFormat::Spdx));
+    }
+
+    #[test]
+    fn detect_ninja_deps() {
+        let input = "foo.o: #deps 1, deps mtime 1700000000 (VALID)\n    ../src/foo.cpp\n";
+        assert_eq!(detect(input), Some(InputFormat::Ninja));
+    }
+
+    #[test]
+    fn detect_ninja_log() {
+        let input = "# ninja log v5\n0\t120\t1700000000\tfoo.o\t1a2b3c4d\n";
+        assert_eq!(detect(input), Some(InputFormat::Ninja));
+    }
+
+    #[test]
+    fn detect_ninja_combined() {
+        let input = include_str!("../../../data/depconv/small.ninja");
+        assert_eq!(detect(input), Some(InputFormat::Ninja));
+    }
+
+    #[test]
+    fn detect_cmake_file_api() {
+        let input = include_str!("../../../data/depconv/cmake-file-api.json");
+        assert_eq!(detect(input), Some(InputFormat::CmakeFileApi));
+    }
+
+    #[test]
+    fn detect_compile_commands() {
+        let input = r#"[
+  {
+    "directory": "/src/build",
+    "command": "/usr/bin/cc -o foo.o -c /src/foo.c",
+    "file": "/src/foo.c",
+    "output": "foo.o"
+  }
+]"#;
+        assert_eq!(detect(input), Some(InputFormat::CompileCommands));
+    }
+
+    #[test]
+    fn detect_rust_modules() {
+        let input = "==> src/lib.rs <==\nmod parse;\n\n==> src/parse.rs <==\n";
+        assert_eq!(detect(input), Some(InputFormat::RustModules));
+    }
+
+    #[test]
+    fn detect_go_mod_graph() {
+        let input = "example.com/app golang.org/x/net@v0.20.0\ngolang.org/x/net@v0.20.0 golang.org/x/text@v0.14.0\n";
+        assert_eq!(detect(input), Some(InputFormat::GoModGraph));
+    }
+
+    #[test]
+    fn detect_go_list() {
+        let input = "{\n\t\"Dir\": \"/usr/lib/go/src/errors\",\n\t\"ImportPath\": \"errors\",\n";
+        assert_eq!(detect(input), Some(InputFormat::GoList));
+    }
+
+    #[test]
+    fn detect_elf() {
+        let input = include_str!("../../../data/depconv/elf-deps.txt");
+        assert_eq!(detect(input), Some(InputFormat::Elf));
+
+        let ldd = "\tlibc.so.6 => /lib/x86_64-linux-gnu/libc.so.6 (0x00007f3a1bc00000)\n";
+        assert_eq!(detect(ldd), Some(InputFormat::Elf));
+        let objdump = "\n/bin/ls:     file format elf64-x86-64\n\nDynamic Section:\n";
+        assert_eq!(detect(objdump), Some(InputFormat::Elf));
+    }
+
+    #[test]
+    fn detect_gradle() {
+        let input = include_str!("../../../data/depconv/gradle-dependencies.txt");
+        assert_eq!(detect(input), Some(InputFormat::Gradle));
+    }
+
+    #[test]
+    fn detect_maven() {
+        let input = include_str!("../../../data/depconv/mvn-dependency-tree.txt");
+        assert_eq!(detect(input), Some(InputFormat::Maven));
+        let input = "com.example:app:jar:1.0\n\\- junit:junit:jar:4.13.2:test\n";
+        assert_eq!(detect(input), Some(InputFormat::Maven));
+    }
+
+    #[test]
+    fn detect_dpkg() {
+        let input = include_str!("../../../data/depconv/dpkg-status");
+        assert_eq!(detect(input), Some(InputFormat::Dpkg));
+    }
+
+    #[test]
+    fn detect_bazel_query() {
+        let input = include_str!("../../../data/depconv/bazel-query.jsonl");
+        assert_eq!(detect(input), Some(InputFormat::BazelQuery));
+        let input = r#"{"target":[{"type":"RULE","rule":{"name":"//a","ruleClass":"genrule"}}]}"#;
+        assert_eq!(detect(input), Some(InputFormat::BazelQuery));
+    }
+
+    #[test]
+    fn detect_npm_lock() {
+        let input = include_str!("../../../data/depconv/package-lock.json");
+        assert_eq!(detect(input), Some(InputFormat::NpmLock));
+    }
+
+    #[test]
+    fn detect_pnpm_lock() {
+        let input = include_str!("../../../data/depconv/pnpm-lock.yaml");
+        assert_eq!(detect(input), Some(InputFormat::PnpmLock));
+    }
+
+    #[test]
+    fn detect_pipdeptree() {
+        let input = r#"[
+    {
+        "key": "flask",
+        "package_name": "Flask",
+        "installed_version": "3.0.0",
+        "required_version": "3.0.0",
+        "dependencies": []
+    }
+]"#;
+        assert_eq!(detect(input), Some(InputFormat::Pipdeptree));
+    }
+
+    #[test]
+    fn detect_uv_lock() {
+        let input = include_str!("../../../data/depconv/uv.lock");
+        assert_eq!(detect(input), Some(InputFormat::UvLock));
+    }
+
+    #[test]
+    fn detect_poetry_lock() {
+        let input = include_str!("../../../data/depconv/poetry.lock");
+        assert_eq!(detect(input), Some(InputFormat::PoetryLock));
+    }
+
+    #[test]
+    fn detect_graphml() {
+        let input = include_str!("../../../data/depconv/small.graphml");
+        assert_eq!(detect(input), Some(InputFormat::GraphMl));
+    }
+
+    #[test]
+    fn detect_graphml_without_declaration() {
+        let input = "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n</graphml>\n";
+        assert_eq!(detect(input), Some(InputFormat::GraphMl));
+    }
+
     #[test]
     fn detect_mermaid_flowchart() {
         let input = include_str!("../../../data/depconv/flowchart.mmd");
@@ -169,6 +536,12 @@ mod tests {
         assert_eq!(detect(input), Some(InputFormat::Mermaid));
     }
 
+    #[test]
+    fn detect_plantuml() {
+        let input = include_str!("../../../data/depconv/architecture.puml");
+        assert_eq!(detect(input), Some(InputFormat::PlantUml));
+    }
+
     #[test]
     fn detect_mermaid_graph_with_direction() {
         assert_eq!(detect("graph TD\n  A --> B\n"), Some(InputFormat::Mermaid));
@@ -200,6 +573,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn detect_csv() {
+        assert_eq!(detect("from,to,label\na,b,\n"), Some(InputFormat::Csv));
+        assert_eq!(
+            detect("id,label,type\na,Alpha,lib\n\nfrom,to\na,b\n"),
+            Some(InputFormat::Csv)
+        );
+        assert_ne!(detect("name,value\na,1\n"), Some(InputFormat::Csv));
+    }
+
     #[test]
     fn detect_tgf() {
         let input = include_str!("../../../data/depconv/small.tgf");
@@ -0,0 +1,159 @@
+use std::io::Write;
+
+use indexmap::{IndexMap, IndexSet};
+
+use crate::DepGraph;
+
+/// The union of the attr names, in first-seen order, skipping names that are already columns.
+fn attr_columns<'a>(
+    attrs: impl Iterator<Item = &'a IndexMap<String, String>>,
+    columns: &[&str],
+) -> IndexSet<&'a str> {
+    attrs
+        .flat_map(|a| a.keys())
+        .map(|k| k.as_str())
+        .filter(|k| !columns.contains(k))
+        .collect()
+}
+
+/// Emit a [`DepGraph`] as a CSV edge list.
+///
+/// Writes a `from,to,label` header, followed by a column for every edge attr. Edges without an
+/// attr get an empty field. Nodes, and the graph's subgraph structure, are dropped; use
+/// [`emit_nodes`] for a node table.
+pub fn emit(graph: &DepGraph, writer: &mut dyn Write) -> eyre::Result<()> {
+    const COLUMNS: [&str; 3] = ["from", "to", "label"];
+    let edges = graph.all_edges();
+    let attrs = attr_columns(edges.iter().map(|e| &e.attrs), &COLUMNS);
+
+    let mut csv = csv::Writer::from_writer(writer);
+    csv.write_record(COLUMNS.iter().chain(attrs.iter()).copied())?;
+    for edge in edges {
+        let label = edge.label.as_deref().unwrap_or_default();
+        let values = attrs
+            .iter()
+            .map(|a| edge.attrs.get(*a).map_or("", |v| v.as_str()));
+        csv.write_record(
+            [edge.from.as_str(), edge.to.as_str(), label]
+                .into_iter()
+                .chain(values),
+        )?;
+    }
+    csv.flush()?;
+    Ok(())
+}
+
+/// Emit a [`DepGraph`] as a CSV node table.
+///
+/// Writes an `id,label,type` header, followed by a column for every node attr. Edges are
+/// dropped; use [`emit`] for an edge list.
+pub fn emit_nodes(graph: &DepGraph, writer: &mut dyn Write) -> eyre::Result<()> {
+    const COLUMNS: [&str; 3] = ["id", "label", "type"];
+    let nodes = graph.all_nodes();
+    let attrs = attr_columns(nodes.values().map(|n| &n.attrs), &COLUMNS);
+
+    let mut csv = csv::Writer::from_writer(writer);
+    csv.write_record(COLUMNS.iter().chain(attrs.iter()).copied())?;
+    for (id, info) in nodes {
+        let node_type = info.node_type.as_deref().unwrap_or_default();
+        let values = attrs
+            .iter()
+            .map(|a| info.attrs.get(*a).map_or("", |v| v.as_str()));
+        csv.write_record(
+            [id.as_str(), info.label.as_str(), node_type]
+                .into_iter()
+                .chain(values),
+        )?;
+    }
+    csv.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emit::fixtures::sample_graph;
+    use crate::{Edge, NodeInfo};
+
+    fn emit_to_string(
+        graph: &DepGraph,
+        emit: fn(&DepGraph, &mut dyn Write) -> eyre::Result<()>,
+    ) -> String {
+        let mut buf = Vec::new();
+        emit(graph, &mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn sample_edges() {
+        let output = emit_to_string(&sample_graph(), emit);
+        assert_eq!(output, "from,to,label\na,b,depends\nb,c,\na,c,\n");
+    }
+
+    #[test]
+    fn sample_nodes() {
+        let output = emit_to_string(&sample_graph(), emit_nodes);
+        assert_eq!(output, "id,label,type\na,alpha,\nb,bravo,\nc,c,\n");
+    }
+
+    #[test]
+    fn attr_columns_and_quoting() {
+        let graph = DepGraph {
+            nodes: IndexMap::from([
+                (
+                    "a".into(),
+                    NodeInfo {
+                        label: "Alpha, Inc.".into(),
+                        node_type: Some("lib".into()),
+                        attrs: IndexMap::from([("version".into(), "1.0".into())]),
+                    },
+                ),
+                (
+                    "b".into(),
+                    NodeInfo {
+                        label: "b".into(),
+                        node_type: None,
+                        attrs: IndexMap::from([
+                            ("license".into(), "MIT".into()),
+                            ("version".into(), "2.0".into()),
+                        ]),
+                    },
+                ),
+            ]),
+            edges: vec![Edge {
+                from: "a".into(),
+                to: "b".into(),
+                label: None,
+                attrs: IndexMap::from([
+                    ("kind".into(), "dev".into()),
+                    ("label".into(), "dropped".into()),
+                ]),
+            }],
+            ..Default::default()
+        };
+        assert_eq!(
+            emit_to_string(&graph, emit_nodes),
+            "\
+id,label,type,version,license
+a,\"Alpha, Inc.\",lib,1.0,
+b,b,,2.0,MIT
+"
+        );
+        assert_eq!(
+            emit_to_string(&graph, emit),
+            "from,to,label,kind\na,b,,dev\n"
+        );
+    }
+
+    #[test]
+    fn roundtrip() {
+        let graph = sample_graph();
+        let nodes = emit_to_string(&graph, emit_nodes);
+        let edges = emit_to_string(&graph, emit);
+        let parsed =
+            crate::parse::parse(crate::parse::InputFormat::Csv, &format!("{nodes}\n{edges}"))
+                .unwrap();
+        assert_eq!(emit_to_string(&parsed, emit_nodes), nodes);
+        assert_eq!(emit_to_string(&parsed, emit), edges);
+    }
+}
@@ -0,0 +1,271 @@
+use std::collections::HashSet;
+use std::io::Write;
+
+use indexmap::IndexMap;
+
+use crate::parse::cyclonedx::{Bom, Component, Dependency, LicenseChoice, Metadata, Supplier};
+use crate::{DepGraph, NodeInfo};
+
+/// Map a node type onto a CycloneDX component type.
+///
+/// Rust target kinds (`lib`, `proc-macro`, ...) and unknown types are all libraries. Node types
+/// that are already CycloneDX component types pass through.
+fn component_type(node_type: Option<&str>) -> &str {
+    match node_type {
+        Some("bin") => "application",
+        Some(
+            t @ ("application"
+            | "framework"
+            | "container"
+            | "platform"
+            | "operating-system"
+            | "device"
+            | "device-driver"
+            | "firmware"
+            | "file"
+            | "machine-learning-model"
+            | "data"
+            | "cryptographic-asset"),
+        ) => t,
+        _ => "library",
+    }
+}
+
+/// Convert a license attr to an SPDX expression.
+///
+/// Older crates use `/` as a separator (`MIT/Apache-2.0`), which cargo treats as `OR`.
+fn license_expression(license: &str) -> String {
+    if license.contains('/') {
+        license
+            .split('/')
+            .map(str::trim)
+            .collect::<Vec<_>>()
+            .join(" OR ")
+    } else {
+        license.to_string()
+    }
+}
+
+fn convert_node(id: &str, info: &NodeInfo) -> Component {
+    let attr = |key: &str| info.attrs.get(key).cloned();
+    Component {
+        component_type: component_type(info.node_type.as_deref()).to_string(),
+        bom_ref: Some(id.to_string()),
+        supplier: attr("supplier").map(|name| Supplier { name: Some(name) }),
+        group: attr("group"),
+        name: info.label.clone(),
+        version: attr("version"),
+        description: attr("description"),
+        scope: attr("scope").filter(|s| matches!(s.as_str(), "required" | "optional" | "excluded")),
+        licenses: attr("license")
+            .map(|l| LicenseChoice::Expression {
+                expression: license_expression(&l),
+            })
+            .into_iter()
+            .collect(),
+        purl: attr("purl"),
+        components: Vec::new(),
+    }
+}
+
+/// Emit a [`DepGraph`] as a CycloneDX 1.5 JSON SBOM.
+///
+/// Each node becomes a component whose `bom-ref` is the node ID and whose `name` is the node
+/// label. The `version`, `group`, `purl`, `description`, `scope`, and `supplier` attrs map onto the
+/// component fields of the same name, and the `license` attr becomes an SPDX license expression.
+/// The node type sets the component `type`: `bin` becomes `application`, and everything else
+/// becomes `library` unless it's already a CycloneDX component type.
+///
+/// If the graph has a single root, it's written as `metadata.component`, the subject of the SBOM.
+/// Every component gets an entry in `dependencies`, listing the targets of its outgoing edges.
+/// Edge labels and attrs, graph attrs, and subgraph structure are dropped.
+pub fn emit(graph: &DepGraph, writer: &mut dyn Write) -> eyre::Result<()> {
+    let nodes = graph.all_nodes();
+    let mut depends_on: IndexMap<&str, Vec<String>> =
+        nodes.keys().map(|id| (id.as_str(), Vec::new())).collect();
+    let mut has_incoming = HashSet::new();
+    let mut seen = HashSet::new();
+    for edge in graph.all_edges() {
+        has_incoming.insert(edge.to.as_str());
+        if seen.insert((edge.from.as_str(), edge.to.as_str()))
+            && let Some(deps) = depends_on.get_mut(edge.from.as_str())
+        {
+            deps.push(edge.to.clone());
+        }
+    }
+
+    let mut roots = nodes
+        .keys()
+        .filter(|id| !has_incoming.contains(id.as_str()));
+    let root = match (roots.next(), roots.next()) {
+        (Some(root), None) => Some(root.as_str()),
+        _ => None,
+    };
+
+    let bom = Bom {
+        bom_format: "CycloneDX".to_string(),
+        spec_version: "1.5".to_string(),
+        serial_number: None,
+        version: Some(1),
+        metadata: root.map(|id| Metadata {
+            component: Some(convert_node(id, &nodes[id])),
+        }),
+        components: nodes
+            .iter()
+            .filter(|(id, _)| Some(id.as_str()) != root)
+            .map(|(id, info)| convert_node(id, info))
+            .collect(),
+        dependencies: depends_on
+            .into_iter()
+            .map(|(id, deps)| Dependency {
+                dep_ref: id.to_string(),
+                depends_on: deps,
+            })
+            .collect(),
+    };
+    serde_json::to_writer_pretty(&mut *writer, &bom)?;
+    writeln!(writer)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{Value, json};
+
+    use super::*;
+    use crate::emit::fixtures::sample_graph;
+    use crate::{Edge, NodeInfo};
+
+    fn emit_value(graph: &DepGraph) -> Value {
+        let mut buf = Vec::new();
+        emit(graph, &mut buf).unwrap();
+        serde_json::from_slice(&buf).unwrap()
+    }
+
+    #[test]
+    fn licenses() {
+        assert_eq!(license_expression("MIT OR Apache-2.0"), "MIT OR Apache-2.0");
+        assert_eq!(license_expression("MIT/Apache-2.0"), "MIT OR Apache-2.0");
+        assert_eq!(license_expression("MIT / Apache-2.0"), "MIT OR Apache-2.0");
+    }
+
+    #[test]
+    fn emit_sample() {
+        let output = emit_value(&sample_graph());
+        assert_eq!(
+            output,
+            json!({
+                "bomFormat": "CycloneDX",
+                "specVersion": "1.5",
+                "version": 1,
+                "metadata": {
+                    "component": {"type": "library", "bom-ref": "a", "name": "alpha"}
+                },
+                "components": [
+                    {"type": "library", "bom-ref": "b", "name": "bravo"},
+                    {"type": "library", "bom-ref": "c", "name": "c"}
+                ],
+                "dependencies": [
+                    {"ref": "a", "dependsOn": ["b", "c"]},
+                    {"ref": "b", "dependsOn": ["c"]},
+                    {"ref": "c", "dependsOn": []}
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn emit_component_fields() {
+        let mut graph = DepGraph::default();
+        graph.nodes.insert(
+            "app 1.0.0".into(),
+            NodeInfo {
+                label: "app".into(),
+                node_type: Some("bin".into()),
+                attrs: IndexMap::from([("version".into(), "1.0.0".into())]),
+            },
+        );
+        graph.nodes.insert(
+            "other 0.1.0".into(),
+            NodeInfo {
+                label: "other".into(),
+                node_type: Some("proc-macro".into()),
+                attrs: IndexMap::from([
+                    ("version".into(), "0.1.0".into()),
+                    ("license".into(), "MIT/Apache-2.0".into()),
+                    ("purl".into(), "pkg:cargo/other@0.1.0".into()),
+                    ("supplier".into(), "Other Corp".into()),
+                    ("features".into(), "default".into()),
+                ]),
+            },
+        );
+        graph.edges.push(Edge {
+            from: "app 1.0.0".into(),
+            to: "other 0.1.0".into(),
+            ..Default::default()
+        });
+        // Duplicate edges (e.g. normal and build) are listed once
+        graph.edges.push(Edge {
+            from: "app 1.0.0".into(),
+            to: "other 0.1.0".into(),
+            attrs: IndexMap::from([("kind".into(), "build".into())]),
+            ..Default::default()
+        });
+
+        let output = emit_value(&graph);
+        assert_eq!(
+            output["metadata"]["component"],
+            json!({"type": "application", "bom-ref": "app 1.0.0", "name": "app", "version": "1.0.0"})
+        );
+        assert_eq!(
+            output["components"],
+            json!([{
+                "type": "library",
+                "bom-ref": "other 0.1.0",
+                "supplier": {"name": "Other Corp"},
+                "name": "other",
+                "version": "0.1.0",
+                "licenses": [{"expression": "MIT OR Apache-2.0"}],
+                "purl": "pkg:cargo/other@0.1.0"
+            }])
+        );
+        assert_eq!(
+            output["dependencies"],
+            json!([
+                {"ref": "app 1.0.0", "dependsOn": ["other 0.1.0"]},
+                {"ref": "other 0.1.0", "dependsOn": []}
+            ])
+        );
+    }
+
+    #[test]
+    fn emit_multiple_roots() {
+        let mut graph = DepGraph::default();
+        graph.nodes.insert("a".into(), NodeInfo::new("a"));
+        graph.nodes.insert("b".into(), NodeInfo::new("b"));
+        let output = emit_value(&graph);
+        assert!(output.get("metadata").is_none());
+        assert_eq!(output["components"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn roundtrip() {
+        let input = include_str!("../../../../data/depconv/small.cdx.json");
+        let graph = crate::parse::parse(crate::parse::InputFormat::CycloneDx, input).unwrap();
+        let mut buf = Vec::new();
+        emit(&graph, &mut buf).unwrap();
+        let output = String::from_utf8(buf).unwrap();
+        let reparsed = crate::parse::parse(crate::parse::InputFormat::CycloneDx, &output).unwrap();
+
+        assert_eq!(
+            graph.nodes.keys().collect::<Vec<_>>(),
+            reparsed.nodes.keys().collect::<Vec<_>>()
+        );
+        for (id, info) in &graph.nodes {
+            assert_eq!(info.label, reparsed.nodes[id].label);
+            assert_eq!(info.node_type, reparsed.nodes[id].node_type);
+            assert_eq!(info.attrs, reparsed.nodes[id].attrs);
+        }
+        assert_eq!(graph.edges.len(), reparsed.edges.len());
+    }
+}
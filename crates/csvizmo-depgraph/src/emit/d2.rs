@@ -0,0 +1,374 @@
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+
+use crate::{DepGraph, NodeInfo};
+
+/// Quote a node ID or container name to be a valid D2 key.
+///
+/// Bare keys support alphanumeric, underscore, and dash characters. Anything
+/// else, notably `.` which D2 uses as a path separator, is double-quoted.
+fn quote_key(id: &str) -> String {
+    let is_bare = !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if is_bare { id.to_string() } else { quote(id) }
+}
+
+/// Double-quote a D2 string, escaping backslashes, quotes, and newlines.
+fn quote(s: &str) -> String {
+    let escaped = s
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{escaped}\"")
+}
+
+/// Map a DOT shape attribute to a D2 shape.
+///
+/// Returns None for rectangles, which are the D2 default, and for shapes
+/// without a good D2 equivalent.
+fn dot_shape_to_d2(shape: &str) -> Option<&'static str> {
+    match shape {
+        "circle" => Some("circle"),
+        "ellipse" | "oval" => Some("oval"),
+        "diamond" => Some("diamond"),
+        "hexagon" => Some("hexagon"),
+        "note" => Some("page"),
+        "cylinder" => Some("cylinder"),
+        "parallelogram" => Some("parallelogram"),
+        "folder" | "tab" => Some("package"),
+        _ => None,
+    }
+}
+
+/// Map a DOT `rankdir` to a D2 `direction`.
+fn rankdir_to_direction(rankdir: &str) -> Option<&'static str> {
+    match rankdir {
+        "LR" => Some("right"),
+        "RL" => Some("left"),
+        "TB" | "TD" => Some("down"),
+        "BT" => Some("up"),
+        _ => None,
+    }
+}
+
+/// Emit a [`DepGraph`] as a D2 diagram.
+///
+/// Preserves:
+/// - Graph direction from `rankdir` attr (LR, RL, TB, BT, TD)
+/// - Node labels
+/// - Node types as shapes (lossy mapping from DOT shapes)
+/// - Edge labels, and dashed or dotted edge styles as `stroke-dash`
+/// - Subgraphs as nested containers, labeled by their `label` attr or ID
+///
+/// Drops:
+/// - Other graph-level attrs
+/// - Arbitrary node and edge attrs
+pub fn emit(graph: &DepGraph, writer: &mut dyn Write) -> eyre::Result<()> {
+    let direction = graph
+        .attrs
+        .get("rankdir")
+        .or(graph.attrs.get("direction"))
+        .and_then(|d| rankdir_to_direction(d));
+    if let Some(direction) = direction {
+        writeln!(writer, "direction: {direction}")?;
+    }
+
+    // D2 edges refer to nodes by their full container path, so every edge can
+    // be emitted at the top level once each node's path is known.
+    let mut paths = HashMap::new();
+    let mut anonymous = 0;
+    emit_nodes(graph, "", &mut paths, &mut anonymous, writer, 0)?;
+
+    let path = |id: &str| paths.get(id).cloned().unwrap_or_else(|| quote_key(id));
+    for edge in graph.all_edges() {
+        let mut line = format!("{} -> {}", path(&edge.from), path(&edge.to));
+        let dashed = matches!(
+            edge.attrs.get("style").map(|s| s.as_str()),
+            Some("dashed" | "dotted")
+        );
+        if edge.label.is_some() || dashed {
+            line.push(':');
+        }
+        if let Some(label) = &edge.label {
+            line.push(' ');
+            line.push_str(&quote(label));
+        }
+        if dashed {
+            line.push_str(" {style.stroke-dash: 3}");
+        }
+        writeln!(writer, "{line}")?;
+    }
+    Ok(())
+}
+
+/// Emit the node declarations of a graph or subgraph, with subgraphs as containers.
+///
+/// Records the full path of each node, e.g. `backend.api`, in `paths`. Anonymous subgraphs are
+/// numbered by `anonymous`, skipping keys already used in the same scope, so that they don't merge
+/// into another container.
+fn emit_nodes(
+    graph: &DepGraph,
+    prefix: &str,
+    paths: &mut HashMap<String, String>,
+    anonymous: &mut usize,
+    writer: &mut dyn Write,
+    depth: usize,
+) -> eyre::Result<()> {
+    let indent = "  ".repeat(depth);
+    let keys: HashSet<String> = (graph.subgraphs.iter().filter_map(|sg| sg.id.as_deref()))
+        .chain(graph.nodes.keys().map(String::as_str))
+        .map(quote_key)
+        .collect();
+
+    for sg in &graph.subgraphs {
+        let key = match &sg.id {
+            Some(id) => quote_key(id),
+            // Anonymous subgraphs need a key; use a generic name
+            None => loop {
+                *anonymous += 1;
+                let key = format!("sg{anonymous}");
+                if !keys.contains(&key) {
+                    break key;
+                }
+            },
+        };
+        match sg.attrs.get("label") {
+            Some(label) => writeln!(writer, "{indent}{key}: {} {{", quote(label))?,
+            None => writeln!(writer, "{indent}{key}: {{")?,
+        }
+        let prefix = format!("{prefix}{key}.");
+        emit_nodes(sg, &prefix, paths, anonymous, writer, depth + 1)?;
+        writeln!(writer, "{indent}}}")?;
+    }
+
+    for (id, info) in &graph.nodes {
+        let key = quote_key(id);
+        writeln!(writer, "{indent}{}", node_declaration(&key, id, info))?;
+        paths.insert(id.clone(), format!("{prefix}{key}"));
+    }
+
+    Ok(())
+}
+
+fn node_declaration(key: &str, id: &str, info: &NodeInfo) -> String {
+    let shape = info.attrs.get("shape").and_then(|s| dot_shape_to_d2(s));
+    let mut declaration = key.to_string();
+    if info.label != id || shape.is_some() {
+        declaration.push(':');
+    }
+    if info.label != id {
+        declaration.push(' ');
+        declaration.push_str(&quote(&info.label));
+    }
+    if let Some(shape) = shape {
+        declaration.push_str(&format!(" {{shape: {shape}}}"));
+    }
+    declaration
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexMap;
+
+    use super::*;
+    use crate::Edge;
+    use crate::emit::fixtures::sample_graph;
+
+    fn emit_to_string(graph: &DepGraph) -> String {
+        let mut buf = Vec::new();
+        emit(graph, &mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn keys() {
+        assert_eq!(quote_key("foo-bar_1"), "foo-bar_1");
+        assert_eq!(quote_key("main.o"), "\"main.o\"");
+        assert_eq!(quote_key("myapp v0.1.0"), "\"myapp v0.1.0\"");
+        assert_eq!(quote_key("has\"quotes"), "\"has\\\"quotes\"");
+        assert_eq!(quote_key(""), "\"\"");
+    }
+
+    #[test]
+    fn empty_graph() {
+        let output = emit_to_string(&DepGraph::default());
+        assert_eq!(output, "");
+    }
+
+    #[test]
+    fn sample() {
+        let output = emit_to_string(&sample_graph());
+        assert_eq!(
+            output,
+            "\
+a: \"alpha\"
+b: \"bravo\"
+c
+a -> b: \"depends\"
+b -> c
+a -> c
+"
+        );
+    }
+
+    #[test]
+    fn shapes_and_styles() {
+        let graph = DepGraph {
+            attrs: IndexMap::from([("rankdir".into(), "TB".into())]),
+            nodes: IndexMap::from([
+                (
+                    "serde_derive".into(),
+                    NodeInfo {
+                        label: "serde_derive".into(),
+                        node_type: Some("proc-macro".into()),
+                        attrs: IndexMap::from([("shape".into(), "diamond".into())]),
+                    },
+                ),
+                (
+                    "app".into(),
+                    NodeInfo {
+                        label: "My App".into(),
+                        node_type: Some("bin".into()),
+                        attrs: IndexMap::from([("shape".into(), "box".into())]),
+                    },
+                ),
+            ]),
+            edges: vec![Edge {
+                from: "app".into(),
+                to: "serde_derive".into(),
+                label: Some("build".into()),
+                attrs: IndexMap::from([("style".into(), "dashed".into())]),
+            }],
+            ..Default::default()
+        };
+        let output = emit_to_string(&graph);
+        assert_eq!(
+            output,
+            "\
+direction: down
+serde_derive: {shape: diamond}
+app: \"My App\"
+app -> serde_derive: \"build\" {style.stroke-dash: 3}
+"
+        );
+    }
+
+    #[test]
+    fn subgraphs_as_containers() {
+        let graph = DepGraph {
+            nodes: IndexMap::from([("top".into(), NodeInfo::new("top"))]),
+            subgraphs: vec![DepGraph {
+                id: Some("cluster_backend".into()),
+                attrs: IndexMap::from([("label".into(), "Backend".into())]),
+                nodes: IndexMap::from([
+                    ("api".into(), NodeInfo::new("API Server")),
+                    ("db.main".into(), NodeInfo::new("db.main")),
+                ]),
+                edges: vec![Edge {
+                    from: "api".into(),
+                    to: "db.main".into(),
+                    ..Default::default()
+                }],
+                subgraphs: vec![DepGraph {
+                    id: Some("cache".into()),
+                    nodes: IndexMap::from([("redis".into(), NodeInfo::new("redis"))]),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            edges: vec![
+                Edge {
+                    from: "top".into(),
+                    to: "api".into(),
+                    ..Default::default()
+                },
+                Edge {
+                    from: "top".into(),
+                    to: "redis".into(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let output = emit_to_string(&graph);
+        assert_eq!(
+            output,
+            "\
+cluster_backend: \"Backend\" {
+  cache: {
+    redis
+  }
+  api: \"API Server\"
+  \"db.main\"
+}
+top
+top -> cluster_backend.api
+top -> cluster_backend.cache.redis
+cluster_backend.api -> cluster_backend.\"db.main\"
+"
+        );
+    }
+
+    #[test]
+    fn anonymous_sibling_subgraphs() {
+        let anonymous = |id: &str| DepGraph {
+            nodes: IndexMap::from([(id.into(), NodeInfo::new(id))]),
+            ..Default::default()
+        };
+        let graph = DepGraph {
+            subgraphs: vec![anonymous("x"), anonymous("y")],
+            edges: vec![Edge {
+                from: "x".into(),
+                to: "y".into(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let output = emit_to_string(&graph);
+        assert_eq!(
+            output,
+            "\
+sg1: {
+  x
+}
+sg2: {
+  y
+}
+sg1.x -> sg2.y
+"
+        );
+    }
+
+    #[test]
+    fn anonymous_subgraph_skips_sibling_keys() {
+        // digraph { subgraph { a } subgraph sg1 { b } }
+        let graph = DepGraph {
+            subgraphs: vec![
+                DepGraph {
+                    nodes: IndexMap::from([("a".into(), NodeInfo::new("a"))]),
+                    ..Default::default()
+                },
+                DepGraph {
+                    id: Some("sg1".into()),
+                    nodes: IndexMap::from([("b".into(), NodeInfo::new("b"))]),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let output = emit_to_string(&graph);
+        assert_eq!(
+            output,
+            "\
+sg2: {
+  a
+}
+sg1: {
+  b
+}
+"
+        );
+    }
+}
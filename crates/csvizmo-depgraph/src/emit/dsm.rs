@@ -0,0 +1,429 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::io::Write;
+
+use indexmap::IndexMap;
+use petgraph::algo::tarjan_scc;
+use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::visit::EdgeRef;
+
+use crate::{DepGraph, FlatGraphView};
+
+/// A subgraph, by its index in the pre-order list of subgraphs.
+struct Cluster<'a> {
+    graph: &'a DepGraph,
+    /// The enclosing subgraph, or `None` for the top level.
+    parent: Option<usize>,
+    /// Whether the subgraph, or one of its own subgraphs, owns any nodes.
+    nonempty: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Item {
+    Node(usize),
+    Cluster(usize),
+}
+
+/// A contiguous run of rows belonging to a subgraph.
+struct Band {
+    cluster: usize,
+    start: usize,
+    end: usize,
+    depth: usize,
+}
+
+/// Collect subgraphs in pre-order, and the innermost subgraph owning each node.
+///
+/// A node listed in several subgraphs belongs to the last one, matching [`DepGraph::all_nodes`].
+fn collect<'a>(
+    graph: &'a DepGraph,
+    parent: Option<usize>,
+    clusters: &mut Vec<Cluster<'a>>,
+    owners: &mut IndexMap<&'a str, Option<usize>>,
+) {
+    for id in graph.nodes.keys() {
+        owners.insert(id.as_str(), parent);
+    }
+    for sg in &graph.subgraphs {
+        clusters.push(Cluster {
+            graph: sg,
+            parent,
+            nonempty: false,
+        });
+        collect(sg, Some(clusters.len() - 1), clusters, owners);
+    }
+}
+
+struct Partitioner<'a> {
+    view: FlatGraphView<'a>,
+    clusters: Vec<Cluster<'a>>,
+    /// The owning subgraph of each node, by its index in the flat view.
+    owners: Vec<Option<usize>>,
+    rows: Vec<usize>,
+    bands: Vec<Band>,
+}
+
+impl<'a> Partitioner<'a> {
+    /// Order the nodes of a graph for a DSM.
+    fn new(graph: &'a DepGraph) -> Self {
+        let view = FlatGraphView::new(graph);
+        let mut clusters = Vec::new();
+        let mut owners = IndexMap::new();
+        collect(graph, None, &mut clusters, &mut owners);
+        let owners: Vec<Option<usize>> = view.idx_to_id.iter().map(|id| owners[id]).collect();
+        for &owner in &owners {
+            let mut cluster = owner;
+            while let Some(i) = cluster {
+                clusters[i].nonempty = true;
+                cluster = clusters[i].parent;
+            }
+        }
+
+        let mut partitioner = Self {
+            view,
+            clusters,
+            owners,
+            rows: Vec::new(),
+            bands: Vec::new(),
+        };
+        partitioner.partition(None, 0);
+        // Outer bands before inner ones that start on the same row
+        partitioner.bands.sort_by_key(|b| (b.start, b.depth));
+        partitioner
+    }
+
+    /// The item that represents a node at the given level, if the node is inside it.
+    fn item(&self, node: usize, level: Option<usize>) -> Option<Item> {
+        let mut owner = self.owners[node];
+        if owner == level {
+            return Some(Item::Node(node));
+        }
+        while let Some(i) = owner {
+            if self.clusters[i].parent == level {
+                return Some(Item::Cluster(i));
+            }
+            owner = self.clusters[i].parent;
+        }
+        None
+    }
+
+    /// Order the nodes and subgraphs directly inside `level`, and then recurse into subgraphs.
+    ///
+    /// Subgraphs are treated as single items, so that they stay contiguous. Items are grouped
+    /// into strongly connected components, and the components are sorted with dependencies
+    /// first, breaking ties by the original order.
+    fn partition(&mut self, level: Option<usize>, depth: usize) {
+        let mut items: Vec<Item> = (0..self.owners.len())
+            .filter(|&node| self.owners[node] == level)
+            .map(Item::Node)
+            .collect();
+        items.extend(
+            (0..self.clusters.len())
+                .filter(|&i| self.clusters[i].parent == level && self.clusters[i].nonempty)
+                .map(Item::Cluster),
+        );
+        let index: HashMap<Item, usize> = items.iter().enumerate().map(|(i, &x)| (x, i)).collect();
+
+        let mut pg = DiGraph::<(), ()>::new();
+        for _ in &items {
+            pg.add_node(());
+        }
+        for edge in self.view.pg.edge_references() {
+            let from = self.item(edge.source().index(), level);
+            let to = self.item(edge.target().index(), level);
+            if let (Some(from), Some(to)) = (from, to)
+                && from != to
+            {
+                pg.add_edge(NodeIndex::new(index[&from]), NodeIndex::new(index[&to]), ());
+            }
+        }
+
+        let mut sccs = tarjan_scc(&pg);
+        for scc in &mut sccs {
+            scc.sort();
+        }
+        let mut component = vec![0; items.len()];
+        for (c, scc) in sccs.iter().enumerate() {
+            for node in scc {
+                component[node.index()] = c;
+            }
+        }
+
+        // Kahn's algorithm on the condensation, placing a component once all of its
+        // dependencies have been placed
+        let mut pending = vec![0usize; sccs.len()];
+        let mut dependents = vec![Vec::new(); sccs.len()];
+        for edge in pg.edge_references() {
+            let (from, to) = (
+                component[edge.source().index()],
+                component[edge.target().index()],
+            );
+            if from != to {
+                pending[from] += 1;
+                dependents[to].push(from);
+            }
+        }
+        let mut ready: BinaryHeap<_> = (0..sccs.len())
+            .filter(|&c| pending[c] == 0)
+            .map(|c| Reverse((sccs[c][0].index(), c)))
+            .collect();
+        while let Some(Reverse((_, c))) = ready.pop() {
+            for i in 0..sccs[c].len() {
+                match items[sccs[c][i].index()] {
+                    Item::Node(node) => self.rows.push(node),
+                    Item::Cluster(cluster) => {
+                        let start = self.rows.len();
+                        self.partition(Some(cluster), depth + 1);
+                        self.bands.push(Band {
+                            cluster,
+                            start,
+                            end: self.rows.len(),
+                            depth,
+                        });
+                    }
+                }
+            }
+            for &dependent in &dependents[c] {
+                pending[dependent] -= 1;
+                if pending[dependent] == 0 {
+                    ready.push(Reverse((sccs[dependent][0].index(), dependent)));
+                }
+            }
+        }
+    }
+}
+
+/// Emit a [`DepGraph`] as a text Dependency Structure Matrix.
+///
+/// Each node gets a row and a column, and a mark in row `r` and column `c` counts the edges from
+/// `r` to `c` (`+` for ten or more), so a row lists what a node depends on and a column what
+/// depends on it. Rows are partitioned with dependencies first, so an acyclic graph only has marks
+/// below the diagonal. Strongly connected components are kept together, so cycles show up as blocks
+/// on the diagonal with marks above it.
+///
+/// Subgraphs are kept contiguous, and drawn as nested bands to the left of the rows. Preserves
+/// node and subgraph labels. Everything else is silently dropped: graph attrs, node attrs, edge
+/// labels, edge attrs. Edges to nodes that aren't declared are dropped too.
+pub fn emit(graph: &DepGraph, writer: &mut dyn Write) -> eyre::Result<()> {
+    let Partitioner {
+        view,
+        clusters,
+        rows,
+        bands,
+        ..
+    } = Partitioner::new(graph);
+    if rows.is_empty() {
+        return Ok(());
+    }
+
+    let all_nodes = graph.all_nodes();
+    let position: HashMap<usize, usize> = rows.iter().enumerate().map(|(r, &n)| (n, r)).collect();
+    let mut counts: Vec<HashMap<usize, usize>> = vec![HashMap::new(); rows.len()];
+    for edge in view.pg.edge_references() {
+        let r = position[&edge.source().index()];
+        let c = position[&edge.target().index()];
+        *counts[r].entry(c).or_default() += 1;
+    }
+
+    let labels: Vec<&str> = rows
+        .iter()
+        .map(|&n| all_nodes[view.idx_to_id[n]].label.as_str())
+        .collect();
+    let label_width = labels.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    let number_width = rows.len().to_string().len();
+    let depths = bands.iter().map(|b| b.depth + 1).max().unwrap_or(0);
+    // The band columns left of a row, or of the header of the band at depth `header`
+    let prefix = |r: usize, header: Option<usize>| {
+        let mut prefix = String::new();
+        for depth in 0..depths {
+            let band = bands
+                .iter()
+                .find(|b| b.depth == depth && b.start <= r && r < b.end);
+            match band {
+                Some(_) if header == Some(depth) => break,
+                Some(b) if header.is_none() && r + 1 == b.end => prefix.push_str("└ "),
+                Some(_) => prefix.push_str("│ "),
+                None => prefix.push_str("  "),
+            }
+        }
+        prefix
+    };
+
+    let indent = 2 * depths + number_width + label_width + 3;
+    for digit in (0..number_width).rev() {
+        let mut line = " ".repeat(indent);
+        for c in 1..=rows.len() {
+            let place = 10usize.pow(digit as u32);
+            if c >= place {
+                line.push_str(&format!(" {}", c / place % 10));
+            } else {
+                line.push_str("  ");
+            }
+        }
+        writeln!(writer, "{}", line.trim_end())?;
+    }
+
+    for (r, label) in labels.iter().enumerate() {
+        for band in bands.iter().filter(|b| b.start == r) {
+            let sg = clusters[band.cluster].graph;
+            let name = sg.attrs.get("label").or(sg.id.as_ref());
+            let line = format!(
+                "{}┌─ {}",
+                prefix(r, Some(band.depth)),
+                name.map_or("", |n| n.as_str())
+            );
+            writeln!(writer, "{}", line.trim_end())?;
+        }
+        let mut line = format!(
+            "{}{:>number_width$} {label:<label_width$} │",
+            prefix(r, None),
+            r + 1
+        );
+        let mut marks = vec!['·'; rows.len()];
+        for (&c, &n) in &counts[r] {
+            marks[c] = if n < 10 {
+                char::from_digit(n as u32, 10).unwrap()
+            } else {
+                '+'
+            };
+        }
+        marks[r] = '■';
+        for mark in marks {
+            line.push(' ');
+            line.push(mark);
+        }
+        writeln!(writer, "{line}")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emit::fixtures::sample_graph;
+    use crate::{Edge, NodeInfo};
+
+    fn emit_to_string(graph: &DepGraph) -> String {
+        let mut buf = Vec::new();
+        emit(graph, &mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    fn edge(from: &str, to: &str) -> Edge {
+        Edge {
+            from: from.into(),
+            to: to.into(),
+            ..Default::default()
+        }
+    }
+
+    fn nodes(ids: &[&str]) -> IndexMap<String, NodeInfo> {
+        ids.iter()
+            .map(|id| (id.to_string(), NodeInfo::new(*id)))
+            .collect()
+    }
+
+    #[test]
+    fn empty_graph() {
+        assert_eq!(emit_to_string(&DepGraph::default()), "");
+    }
+
+    #[test]
+    fn dependencies_first() {
+        // a -> b -> c, a -> c
+        assert_eq!(
+            emit_to_string(&sample_graph()),
+            "          1 2 3
+1 c     │ ■ · ·
+2 bravo │ 1 ■ ·
+3 alpha │ 1 1 ■
+"
+        );
+    }
+
+    #[test]
+    fn cycles_are_blocks() {
+        // a -> b -> c -> b, c -> d, with the cycle listed after d
+        let graph = DepGraph {
+            nodes: nodes(&["a", "d", "b", "c"]),
+            edges: vec![
+                edge("a", "b"),
+                edge("b", "c"),
+                edge("c", "b"),
+                edge("c", "d"),
+                edge("c", "d"),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            emit_to_string(&graph),
+            "      1 2 3 4
+1 d │ ■ · · ·
+2 b │ · ■ 1 ·
+3 c │ 2 1 ■ ·
+4 a │ · 1 · ■
+"
+        );
+    }
+
+    #[test]
+    fn nested_bands() {
+        let graph = DepGraph {
+            nodes: nodes(&["app"]),
+            edges: vec![
+                edge("app", "core"),
+                edge("app", "util"),
+                edge("core", "util"),
+                edge("util", "log"),
+            ],
+            subgraphs: vec![DepGraph {
+                id: Some("libs".into()),
+                attrs: IndexMap::from([("label".into(), "Libraries".into())]),
+                nodes: nodes(&["core", "util"]),
+                subgraphs: vec![
+                    DepGraph {
+                        id: Some("empty".into()),
+                        ..Default::default()
+                    },
+                    DepGraph {
+                        id: Some("leaf".into()),
+                        nodes: nodes(&["log"]),
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert_eq!(
+            emit_to_string(&graph),
+            "             1 2 3 4
+┌─ Libraries
+│ ┌─ leaf
+│ └ 1 log  │ ■ · · ·
+│   2 util │ 1 ■ · ·
+└   3 core │ · 1 ■ ·
+    4 app  │ · 1 1 ■
+"
+        );
+    }
+
+    #[test]
+    fn wide_numbers() {
+        let ids: Vec<String> = (0..12).map(|i| format!("n{i}")).collect();
+        let ids: Vec<&str> = ids.iter().map(|s| s.as_str()).collect();
+        let graph = DepGraph {
+            nodes: nodes(&ids),
+            ..Default::default()
+        };
+        let output = emit_to_string(&graph);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], format!("{}1 1 1", " ".repeat(27)));
+        assert_eq!(
+            lines[1],
+            format!("{}1 2 3 4 5 6 7 8 9 0 1 2", " ".repeat(9))
+        );
+        assert_eq!(lines[2], " 1 n0  │ ■ · · · · · · · · · · ·");
+        assert_eq!(lines[13], "12 n11 │ · · · · · · · · · · · ■");
+    }
+}
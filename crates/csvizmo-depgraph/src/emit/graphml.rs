@@ -0,0 +1,375 @@
+use std::io::Write;
+
+use indexmap::{IndexMap, IndexSet};
+
+use crate::{DepGraph, Edge, NodeInfo};
+
+/// Escape a string for use in XML text content or a double-quoted attribute value.
+pub(crate) fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// GraphML `<key>` declarations, mapping attribute names to key IDs for each domain.
+#[derive(Default)]
+struct Keys {
+    graph: IndexMap<String, String>,
+    node: IndexMap<String, String>,
+    edge: IndexMap<String, String>,
+}
+
+impl Keys {
+    /// Collect every attribute name used anywhere in the graph and assign `d0`, `d1`, ... IDs.
+    ///
+    /// `label` and `type` are always declared first for nodes, and `label` first for edges, so
+    /// that tools like yEd and Gephi pick them up as the display label.
+    fn collect(graph: &DepGraph) -> Self {
+        let mut graph_names = IndexSet::new();
+        let mut node_names = IndexSet::from(["label".to_string()]);
+        let mut edge_names = IndexSet::new();
+        collect_names(graph, &mut graph_names, &mut node_names, &mut edge_names);
+
+        let mut keys = Self::default();
+        let mut next_id = 0;
+        let mut assign = |names: IndexSet<String>, map: &mut IndexMap<String, String>| {
+            for name in names {
+                map.insert(name, format!("d{next_id}"));
+                next_id += 1;
+            }
+        };
+        assign(graph_names, &mut keys.graph);
+        assign(node_names, &mut keys.node);
+        assign(edge_names, &mut keys.edge);
+        keys
+    }
+}
+
+fn collect_names(
+    graph: &DepGraph,
+    graph_names: &mut IndexSet<String>,
+    node_names: &mut IndexSet<String>,
+    edge_names: &mut IndexSet<String>,
+) {
+    graph_names.extend(graph.attrs.keys().cloned());
+    for info in graph.nodes.values() {
+        if info.node_type.is_some() {
+            node_names.insert("type".to_string());
+        }
+        node_names.extend(info.attrs.keys().cloned());
+    }
+    for edge in &graph.edges {
+        if edge.label.is_some() {
+            edge_names.insert("label".to_string());
+        }
+        edge_names.extend(edge.attrs.keys().cloned());
+    }
+    for sg in &graph.subgraphs {
+        collect_names(sg, graph_names, node_names, edge_names);
+    }
+}
+
+/// Emit a [`DepGraph`] as a GraphML document.
+///
+/// All graph features are preserved:
+/// - Graph, node, and edge attrs become `<data>` elements with matching `<key>` declarations.
+/// - Node labels and types use the `label` and `type` keys; edge labels use `label`.
+/// - Subgraphs are emitted as nested `<graph>` elements inside a container `<node>`, which is
+///   how yEd represents group nodes.
+///
+/// Every key is declared with `attr.type="string"`, since attribute values are untyped strings.
+pub fn emit(graph: &DepGraph, writer: &mut dyn Write) -> eyre::Result<()> {
+    let keys = Keys::collect(graph);
+
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:schemaLocation="http://graphml.graphdrawing.org/xmlns http://graphml.graphdrawing.org/xmlns/1.0/graphml.xsd">"#
+    )?;
+    for (domain, map) in [
+        ("graph", &keys.graph),
+        ("node", &keys.node),
+        ("edge", &keys.edge),
+    ] {
+        for (name, id) in map {
+            writeln!(
+                writer,
+                r#"  <key id="{id}" for="{domain}" attr.name="{}" attr.type="string"/>"#,
+                escape(name)
+            )?;
+        }
+    }
+
+    let mut anon_count = 0;
+    emit_graph(graph, &keys, writer, 1, &mut anon_count)?;
+
+    writeln!(writer, "</graphml>")?;
+    Ok(())
+}
+
+/// Emit a `<graph>` element (root or nested) with its data, subgraphs, nodes, and edges.
+fn emit_graph(
+    graph: &DepGraph,
+    keys: &Keys,
+    writer: &mut dyn Write,
+    depth: usize,
+    anon_count: &mut usize,
+) -> eyre::Result<()> {
+    let indent = "  ".repeat(depth);
+    match &graph.id {
+        Some(id) => writeln!(
+            writer,
+            r#"{indent}<graph id="{}" edgedefault="directed">"#,
+            escape(id)
+        )?,
+        None => writeln!(writer, r#"{indent}<graph edgedefault="directed">"#)?,
+    }
+
+    let inner = "  ".repeat(depth + 1);
+    for (k, v) in &graph.attrs {
+        emit_data(&keys.graph[k], v, writer, &inner)?;
+    }
+
+    for sg in &graph.subgraphs {
+        // GraphML nests graphs inside nodes. The container node needs a document-unique ID,
+        // so anonymous subgraphs get a generated one. The parser only looks at the nested
+        // graph's ID, so the generated name never leaks back into the DepGraph.
+        let container_id = match &sg.id {
+            Some(id) => format!("{id}::"),
+            None => {
+                *anon_count += 1;
+                format!("subgraph{anon_count}::")
+            }
+        };
+        writeln!(writer, r#"{inner}<node id="{}">"#, escape(&container_id))?;
+        emit_graph(sg, keys, writer, depth + 2, anon_count)?;
+        writeln!(writer, "{inner}</node>")?;
+    }
+
+    for (id, info) in &graph.nodes {
+        emit_node(id, info, keys, writer, &inner)?;
+    }
+
+    for edge in &graph.edges {
+        emit_edge(edge, keys, writer, &inner)?;
+    }
+
+    writeln!(writer, "{indent}</graph>")?;
+    Ok(())
+}
+
+fn emit_node(
+    id: &str,
+    info: &NodeInfo,
+    keys: &Keys,
+    writer: &mut dyn Write,
+    indent: &str,
+) -> eyre::Result<()> {
+    writeln!(writer, r#"{indent}<node id="{}">"#, escape(id))?;
+    let inner = format!("{indent}  ");
+    emit_data(&keys.node["label"], &info.label, writer, &inner)?;
+    if let Some(node_type) = &info.node_type {
+        emit_data(&keys.node["type"], node_type, writer, &inner)?;
+    }
+    for (k, v) in &info.attrs {
+        emit_data(&keys.node[k], v, writer, &inner)?;
+    }
+    writeln!(writer, "{indent}</node>")?;
+    Ok(())
+}
+
+fn emit_edge(edge: &Edge, keys: &Keys, writer: &mut dyn Write, indent: &str) -> eyre::Result<()> {
+    let from = escape(&edge.from);
+    let to = escape(&edge.to);
+    if edge.label.is_none() && edge.attrs.is_empty() {
+        writeln!(writer, r#"{indent}<edge source="{from}" target="{to}"/>"#)?;
+        return Ok(());
+    }
+
+    writeln!(writer, r#"{indent}<edge source="{from}" target="{to}">"#)?;
+    let inner = format!("{indent}  ");
+    if let Some(label) = &edge.label {
+        emit_data(&keys.edge["label"], label, writer, &inner)?;
+    }
+    for (k, v) in &edge.attrs {
+        emit_data(&keys.edge[k], v, writer, &inner)?;
+    }
+    writeln!(writer, "{indent}</edge>")?;
+    Ok(())
+}
+
+fn emit_data(key: &str, value: &str, writer: &mut dyn Write, indent: &str) -> eyre::Result<()> {
+    writeln!(
+        writer,
+        r#"{indent}<data key="{key}">{}</data>"#,
+        escape(value)
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emit::fixtures::sample_graph;
+
+    const HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:schemaLocation="http://graphml.graphdrawing.org/xmlns http://graphml.graphdrawing.org/xmlns/1.0/graphml.xsd">
+"#;
+
+    fn emit_to_string(graph: &DepGraph) -> String {
+        let mut buf = Vec::new();
+        emit(graph, &mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn escape_special_chars() {
+        assert_eq!(escape("a & b"), "a &amp; b");
+        assert_eq!(escape("<tag>"), "&lt;tag&gt;");
+        assert_eq!(escape(r#"say "hi""#), "say &quot;hi&quot;");
+        assert_eq!(escape("it's"), "it&apos;s");
+    }
+
+    #[test]
+    fn empty_graph() {
+        let output = emit_to_string(&DepGraph::default());
+        let expected = format!(
+            "{HEADER}  <key id=\"d0\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>
+  <graph edgedefault=\"directed\">
+  </graph>
+</graphml>
+"
+        );
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn sample() {
+        let output = emit_to_string(&sample_graph());
+        let expected = format!(
+            "{HEADER}  <key id=\"d0\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>
+  <key id=\"d1\" for=\"edge\" attr.name=\"label\" attr.type=\"string\"/>
+  <graph edgedefault=\"directed\">
+    <node id=\"a\">
+      <data key=\"d0\">alpha</data>
+    </node>
+    <node id=\"b\">
+      <data key=\"d0\">bravo</data>
+    </node>
+    <node id=\"c\">
+      <data key=\"d0\">c</data>
+    </node>
+    <edge source=\"a\" target=\"b\">
+      <data key=\"d1\">depends</data>
+    </edge>
+    <edge source=\"b\" target=\"c\"/>
+    <edge source=\"a\" target=\"c\"/>
+  </graph>
+</graphml>
+"
+        );
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn attrs_and_types() {
+        let graph = DepGraph {
+            id: Some("deps".into()),
+            attrs: IndexMap::from([("rankdir".into(), "LR".into())]),
+            nodes: IndexMap::from([(
+                "a".into(),
+                NodeInfo {
+                    label: "A & co".into(),
+                    node_type: Some("lib".into()),
+                    attrs: IndexMap::from([("version".into(), "1.0".into())]),
+                },
+            )]),
+            edges: vec![Edge {
+                from: "a".into(),
+                to: "a".into(),
+                label: None,
+                attrs: IndexMap::from([("kind".into(), "dev".into())]),
+            }],
+            ..Default::default()
+        };
+        let output = emit_to_string(&graph);
+        let expected = format!(
+            "{HEADER}  <key id=\"d0\" for=\"graph\" attr.name=\"rankdir\" attr.type=\"string\"/>
+  <key id=\"d1\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>
+  <key id=\"d2\" for=\"node\" attr.name=\"type\" attr.type=\"string\"/>
+  <key id=\"d3\" for=\"node\" attr.name=\"version\" attr.type=\"string\"/>
+  <key id=\"d4\" for=\"edge\" attr.name=\"kind\" attr.type=\"string\"/>
+  <graph id=\"deps\" edgedefault=\"directed\">
+    <data key=\"d0\">LR</data>
+    <node id=\"a\">
+      <data key=\"d1\">A &amp; co</data>
+      <data key=\"d2\">lib</data>
+      <data key=\"d3\">1.0</data>
+    </node>
+    <edge source=\"a\" target=\"a\">
+      <data key=\"d4\">dev</data>
+    </edge>
+  </graph>
+</graphml>
+"
+        );
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn nested_subgraphs() {
+        let graph = DepGraph {
+            subgraphs: vec![DepGraph {
+                id: Some("outer".into()),
+                attrs: IndexMap::from([("label".into(), "Outer".into())]),
+                nodes: IndexMap::from([("a".into(), NodeInfo::new("a"))]),
+                subgraphs: vec![DepGraph {
+                    nodes: IndexMap::from([("b".into(), NodeInfo::new("b"))]),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            edges: vec![Edge {
+                from: "a".into(),
+                to: "b".into(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let output = emit_to_string(&graph);
+        let expected = format!(
+            "{HEADER}  <key id=\"d0\" for=\"graph\" attr.name=\"label\" attr.type=\"string\"/>
+  <key id=\"d1\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>
+  <graph edgedefault=\"directed\">
+    <node id=\"outer::\">
+      <graph id=\"outer\" edgedefault=\"directed\">
+        <data key=\"d0\">Outer</data>
+        <node id=\"subgraph1::\">
+          <graph edgedefault=\"directed\">
+            <node id=\"b\">
+              <data key=\"d1\">b</data>
+            </node>
+          </graph>
+        </node>
+        <node id=\"a\">
+          <data key=\"d1\">a</data>
+        </node>
+      </graph>
+    </node>
+    <edge source=\"a\" target=\"b\"/>
+  </graph>
+</graphml>
+"
+        );
+        assert_eq!(output, expected);
+    }
+}
@@ -0,0 +1,141 @@
+use std::io::Write;
+
+use crate::DepGraph;
+
+const STYLE: &str = include_str!("html/viewer.css");
+const SCRIPT: &str = include_str!("html/viewer.js");
+
+/// Serialize the graph as compact JGF that's safe to embed in a `<script>` element.
+///
+/// `<` only appears inside JSON strings, where it can be written as a `\u003c` escape, so the
+/// data can never close the element or open a comment.
+fn graph_data(graph: &DepGraph) -> eyre::Result<String> {
+    let json = serde_json::to_string(&super::json::document(graph))?;
+    Ok(json.replace('<', "\\u003c"))
+}
+
+/// Emit a [`DepGraph`] as a self-contained interactive HTML viewer.
+///
+/// The graph is embedded as the same JGF document written by the JSON emitter, along with a
+/// small inlined renderer. The page fetches nothing, so it can be opened offline or attached to
+/// a CI run. The viewer supports pan/zoom, searching node labels, highlighting a node's
+/// transitive dependencies and dependents, and collapsing subgraphs into single nodes.
+///
+/// Node and edge `shape`, `style`, `color`, `fillcolor`, `fontcolor`, and `penwidth` attrs are
+/// honored, as set by `apply_default_styles()` or `graphdiff annotate`. The `rankdir` graph attr
+/// picks a top-down or left-to-right layout.
+pub fn emit(graph: &DepGraph, writer: &mut dyn Write) -> eyre::Result<()> {
+    let title = super::graphml::escape(graph.id.as_deref().unwrap_or("Dependency graph"));
+    let data = graph_data(graph)?;
+    write!(
+        writer,
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title}</title>
+<style>
+{STYLE}</style>
+</head>
+<body>
+<aside id="sidebar">
+<h1>{title}</h1>
+<p id="stats"></p>
+<input id="search" type="search" placeholder="Search labels (/)" autocomplete="off">
+<p id="search-status"></p>
+<div class="buttons">
+<button id="fit">Fit</button>
+<button id="expand-all">Expand all</button>
+<button id="collapse-all">Collapse all</button>
+</div>
+<section id="details" hidden></section>
+<section id="clusters" hidden>
+<h2>Subgraphs</h2>
+<div id="cluster-tree"></div>
+</section>
+<p class="help">Scroll to zoom and drag to pan. Click a node to highlight its dependencies and
+dependents, and Escape to clear. Click a subgraph's label to collapse it, and double-click the
+collapsed node to expand it again.</p>
+</aside>
+<main>
+<svg id="canvas"></svg>
+</main>
+<script id="graph-data" type="application/json">{data}</script>
+<script>
+{SCRIPT}</script>
+</body>
+</html>
+"#
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexMap;
+
+    use super::*;
+    use crate::NodeInfo;
+    use crate::emit::fixtures::sample_graph;
+
+    fn emit_to_string(graph: &DepGraph) -> String {
+        let mut buf = Vec::new();
+        emit(graph, &mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    /// The contents of the embedded graph data element.
+    fn embedded_data(output: &str) -> &str {
+        let start = r#"<script id="graph-data" type="application/json">"#;
+        let start = output.find(start).unwrap() + start.len();
+        let end = start + output[start..].find("</script>").unwrap();
+        &output[start..end]
+    }
+
+    #[test]
+    fn self_contained() {
+        let output = emit_to_string(&sample_graph());
+        assert!(output.starts_with("<!DOCTYPE html>\n"));
+        assert!(!output.contains("<link"));
+        assert!(!output.contains("<script src"));
+        assert!(!output.contains("import("));
+        assert!(!output.contains("fetch("));
+        assert_eq!(output.matches("<script").count(), 2);
+    }
+
+    #[test]
+    fn embeds_jgf() {
+        let graph = sample_graph();
+        let output = emit_to_string(&graph);
+        let data = embedded_data(&output);
+        let mut expected = Vec::new();
+        super::super::json::emit(&graph, &mut expected).unwrap();
+        let expected: serde_json::Value = serde_json::from_slice(&expected).unwrap();
+        let actual: serde_json::Value = serde_json::from_str(data).unwrap();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn escapes_markup() {
+        let graph = DepGraph {
+            id: Some("<deps> & co".into()),
+            nodes: IndexMap::from([(
+                "a".into(),
+                NodeInfo::new("</script><script>alert(1)</script><!--"),
+            )]),
+            ..Default::default()
+        };
+        let output = emit_to_string(&graph);
+        assert!(output.contains("<title>&lt;deps&gt; &amp; co</title>"));
+        assert!(!output.contains("alert(1)</script>"));
+
+        let data = embedded_data(&output);
+        assert!(!data.contains('<'));
+        let actual: serde_json::Value = serde_json::from_str(data).unwrap();
+        assert_eq!(
+            actual["graph"]["nodes"]["a"]["label"],
+            "</script><script>alert(1)</script><!--"
+        );
+    }
+}
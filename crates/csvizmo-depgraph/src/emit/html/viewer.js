@@ -0,0 +1,850 @@
+// Interactive viewer for graphs emitted by `depconv --output-format html`.
+//
+// Reads the JGF document embedded in the page, lays it out in layers, and renders it as SVG.
+// Everything the viewer needs is inlined into the page, so it works offline.
+"use strict";
+
+(function () {
+  const SVG_NS = "http://www.w3.org/2000/svg";
+  const FONT = "12px sans-serif";
+  const LINE_HEIGHT = 15;
+  const NODE_GAP = 24;
+  const RANK_GAP = 64;
+  const CLUSTER_PAD = 12;
+  const CLUSTER_LABEL = 18;
+
+  const doc = JSON.parse(document.getElementById("graph-data").textContent).graph;
+
+  // The flattened graph: every node with the index of its innermost subgraph, the subgraphs as
+  // a tree of clusters, and every edge.
+  const nodes = new Map();
+  const clusters = [];
+  const edges = [];
+
+  function stringAttrs(metadata) {
+    const attrs = {};
+    for (const [key, value] of Object.entries(metadata || {})) {
+      attrs[key] = typeof value === "string" ? value : JSON.stringify(value);
+    }
+    return attrs;
+  }
+
+  function load(graph, cluster) {
+    for (const [id, node] of Object.entries(graph.nodes || {})) {
+      if (nodes.has(id)) continue;
+      const attrs = stringAttrs(node.metadata);
+      const type = attrs.type;
+      delete attrs.type;
+      nodes.set(id, { id, label: node.label ?? id, type, attrs, cluster });
+    }
+    for (const edge of graph.edges || []) {
+      edges.push({
+        from: edge.source,
+        to: edge.target,
+        label: edge.label,
+        attrs: stringAttrs(edge.metadata),
+      });
+    }
+    for (const subgraph of graph.subgraphs || []) {
+      const attrs = stringAttrs(subgraph.metadata);
+      const index = clusters.length;
+      clusters.push({
+        index,
+        label: attrs.label || subgraph.id || `subgraph ${index + 1}`,
+        attrs,
+        parent: cluster,
+        children: [],
+        size: 0,
+      });
+      if (cluster !== null) clusters[cluster].children.push(index);
+      load(subgraph, index);
+    }
+  }
+
+  load(doc, null);
+  for (const edge of edges) {
+    for (const id of [edge.from, edge.to]) {
+      if (!nodes.has(id)) nodes.set(id, { id, label: id, type: undefined, attrs: {}, cluster: null });
+    }
+  }
+  for (const node of nodes.values()) {
+    for (let c = node.cluster; c !== null; c = clusters[c].parent) clusters[c].size++;
+  }
+
+  const graphAttrs = stringAttrs(doc.metadata);
+  const leftToRight = ["LR", "RL"].includes(graphAttrs.rankdir || graphAttrs.direction);
+
+  // Viewer state
+  const collapsed = new Set();
+  let view = null;
+  let selected = null;
+  let matches = [];
+  let matchIndex = -1;
+  const transform = { x: 0, y: 0, k: 1 };
+
+  const svg = document.getElementById("canvas");
+  const search = document.getElementById("search");
+  const searchStatus = document.getElementById("search-status");
+  const details = document.getElementById("details");
+  const measureContext = document.createElement("canvas").getContext("2d");
+  measureContext.font = FONT;
+
+  function el(tag, attrs, parent) {
+    const element = document.createElementNS(SVG_NS, tag);
+    for (const [key, value] of Object.entries(attrs)) {
+      if (value !== undefined && value !== null) element.setAttribute(key, value);
+    }
+    if (parent) parent.appendChild(element);
+    return element;
+  }
+
+  // DOT attrs
+
+  // Convert a DOT color to CSS. Handles X11 grayNN, HSV triples, and color lists, whose first
+  // color is used. Named colors are passed through, and mostly overlap with CSS.
+  function cssColor(color) {
+    if (!color) return undefined;
+    color = color.split(":")[0].split(";")[0].trim();
+    const gray = /^gr[ae]y(\d{1,3})$/i.exec(color);
+    if (gray) {
+      const v = Math.round(Math.min(100, Number(gray[1])) * 2.55);
+      return `rgb(${v},${v},${v})`;
+    }
+    const hsv = /^([\d.]+)[ ,]+([\d.]+)[ ,]+([\d.]+)$/.exec(color);
+    if (hsv) {
+      const [h, s, v] = hsv.slice(1).map(Number);
+      const l = v * (1 - s / 2);
+      const sl = l === 0 || l === 1 ? 0 : (v - l) / Math.min(l, 1 - l);
+      return `hsl(${h * 360},${sl * 100}%,${l * 100}%)`;
+    }
+    return color;
+  }
+
+  function styleFlags(attrs) {
+    return new Set(
+      (attrs.style || "")
+        .split(",")
+        .map((s) => s.trim().replace(/\(.*$/, ""))
+        .filter(Boolean),
+    );
+  }
+
+  function strokeAttrs(attrs, flags, defaultColor) {
+    const stroke = {
+      stroke: cssColor(attrs.color) || defaultColor,
+      "stroke-width": attrs.penwidth || (flags.has("bold") ? 2 : 1),
+    };
+    if (flags.has("dashed")) stroke["stroke-dasharray"] = "5,3";
+    if (flags.has("dotted")) stroke["stroke-dasharray"] = "1,3";
+    return stroke;
+  }
+
+  function shapeOf(vnode) {
+    return vnode.attrs.shape || "ellipse";
+  }
+
+  // The visible graph
+
+  // Replace the nodes of collapsed subgraphs with one node per outermost collapsed subgraph, and
+  // merge the edges that now share endpoints.
+  function visibleGraph() {
+    const byId = new Map();
+    const repOf = new Map();
+    for (const node of nodes.values()) {
+      let outermost = null;
+      for (let c = node.cluster; c !== null; c = clusters[c].parent) {
+        if (collapsed.has(c)) outermost = c;
+      }
+      const vid = outermost === null ? `n:${node.id}` : `c:${outermost}`;
+      repOf.set(node.id, vid);
+      if (byId.has(vid)) continue;
+      if (outermost === null) {
+        byId.set(vid, { vid, node, label: node.label, attrs: node.attrs, cluster: node.cluster });
+      } else {
+        const cluster = clusters[outermost];
+        byId.set(vid, {
+          vid,
+          clusterIndex: outermost,
+          label: `${cluster.label} (${cluster.size})`,
+          attrs: { shape: "box3d", color: cluster.attrs.color },
+          cluster: cluster.parent,
+        });
+      }
+    }
+
+    const vedges = [];
+    const merged = new Set();
+    for (const edge of edges) {
+      const from = repOf.get(edge.from);
+      const to = repOf.get(edge.to);
+      if (from.startsWith("c:") || to.startsWith("c:")) {
+        const key = `${from}\u0000${to}`;
+        if (from === to || merged.has(key)) continue;
+        merged.add(key);
+        vedges.push({ from, to, label: undefined, attrs: {} });
+      } else {
+        vedges.push({ from, to, label: edge.label, attrs: edge.attrs });
+      }
+    }
+
+    const succ = new Map();
+    const pred = new Map();
+    for (const vid of byId.keys()) {
+      succ.set(vid, []);
+      pred.set(vid, []);
+    }
+    for (const edge of vedges) {
+      succ.get(edge.from).push(edge.to);
+      pred.get(edge.to).push(edge.from);
+    }
+    return { vnodes: [...byId.values()], vedges, byId, repOf, succ, pred };
+  }
+
+  // Layout
+
+  function measure(vnode) {
+    const lines = String(vnode.label).split(/\n|\\n|\\l|\\r/);
+    const textWidth = Math.max(...lines.map((line) => measureContext.measureText(line).width));
+    let w = textWidth + 20;
+    let h = lines.length * LINE_HEIGHT + 12;
+    switch (shapeOf(vnode)) {
+      case "ellipse":
+      case "oval":
+        w *= 1.25;
+        h += 4;
+        break;
+      case "circle":
+      case "doublecircle":
+        w = h = Math.max(w, h);
+        break;
+      case "diamond":
+        w *= 1.6;
+        h *= 1.6;
+        break;
+      case "hexagon":
+        w += 20;
+        break;
+      case "note":
+      case "cylinder":
+        h += 6;
+        break;
+    }
+    vnode.lines = lines;
+    vnode.w = Math.max(w, 30);
+    vnode.h = h;
+  }
+
+  // A layered layout: break cycles by ignoring DFS back edges, put each node on the layer of its
+  // longest path from a root, order the layers with barycenter sweeps, keep subgraphs together,
+  // and wrap layers that would be too wide to read.
+  function layout(vnodes, vedges) {
+    const n = vnodes.length;
+    const index = new Map(vnodes.map((v, i) => [v.vid, i]));
+    const succ = Array.from({ length: n }, () => []);
+    const pred = Array.from({ length: n }, () => []);
+    for (const edge of vedges) {
+      const a = index.get(edge.from);
+      const b = index.get(edge.to);
+      if (a === b) continue;
+      succ[a].push(b);
+      pred[b].push(a);
+    }
+
+    const state = new Uint8Array(n);
+    const discovered = new Int32Array(n);
+    const finished = [];
+    const back = new Set();
+    let discoveries = 0;
+    const starts = [...Array(n).keys()].sort((a, b) => (pred[a].length > 0) - (pred[b].length > 0));
+    for (const start of starts) {
+      if (state[start]) continue;
+      state[start] = 1;
+      discovered[start] = discoveries++;
+      const stack = [[start, 0]];
+      while (stack.length) {
+        const top = stack[stack.length - 1];
+        const v = top[0];
+        if (top[1] < succ[v].length) {
+          const w = succ[v][top[1]++];
+          if (state[w] === 1) {
+            back.add(v * n + w);
+          } else if (state[w] === 0) {
+            state[w] = 1;
+            discovered[w] = discoveries++;
+            stack.push([w, 0]);
+          }
+        } else {
+          state[v] = 2;
+          finished.push(v);
+          stack.pop();
+        }
+      }
+    }
+
+    const layer = new Int32Array(n);
+    for (let i = finished.length - 1; i >= 0; i--) {
+      const v = finished[i];
+      for (const w of succ[v]) {
+        if (!back.has(v * n + w)) layer[w] = Math.max(layer[w], layer[v] + 1);
+      }
+    }
+
+    const ranks = [];
+    for (let v = 0; v < n; v++) (ranks[layer[v]] ||= []).push(v);
+    for (const rank of ranks) rank.sort((a, b) => discovered[a] - discovered[b]);
+
+    const pos = new Float64Array(n);
+    const place = (rank) => rank.forEach((v, i) => (pos[v] = i - (rank.length - 1) / 2));
+    ranks.forEach(place);
+    const bary = new Float64Array(n);
+    for (let sweep = 0; sweep < 8; sweep++) {
+      const down = sweep % 2 === 0;
+      for (const rank of down ? ranks : [...ranks].reverse()) {
+        for (const v of rank) {
+          const adjacent = down ? pred[v] : succ[v];
+          bary[v] = adjacent.length
+            ? adjacent.reduce((sum, w) => sum + pos[w], 0) / adjacent.length
+            : pos[v];
+        }
+        rank.sort((a, b) => bary[a] - bary[b]);
+        place(rank);
+      }
+    }
+
+    // Group each layer by subgraph, ordering the groups by their mean position
+    for (const rank of ranks) {
+      const chains = new Map();
+      const sums = new Map();
+      for (const v of rank) {
+        const chain = [];
+        for (let c = vnodes[v].cluster; c !== null; c = clusters[c].parent) chain.unshift(c);
+        chains.set(v, chain);
+        for (const c of chain) {
+          const sum = sums.get(c) || [0, 0];
+          sum[0] += pos[v];
+          sum[1]++;
+          sums.set(c, sum);
+        }
+      }
+      const key = (v) => chains.get(v).map((c) => sums.get(c)[0] / sums.get(c)[1]).concat(pos[v]);
+      const keys = new Map(rank.map((v) => [v, key(v)]));
+      rank.sort((a, b) => {
+        const ka = keys.get(a);
+        const kb = keys.get(b);
+        for (let i = 0; i < Math.min(ka.length, kb.length); i++) {
+          if (ka[i] !== kb[i]) return ka[i] - kb[i];
+        }
+        return ka.length - kb.length;
+      });
+    }
+
+    const maxRow = Math.max(16, Math.ceil(Math.sqrt(n) * 1.5));
+    let offset = 0;
+    for (const rank of ranks) {
+      for (let start = 0; start < rank.length; start += maxRow) {
+        const row = rank.slice(start, start + maxRow).map((v) => vnodes[v]);
+        const depth = Math.max(...row.map((v) => (leftToRight ? v.w : v.h)));
+        const sizes = row.map((v) => (leftToRight ? v.h : v.w));
+        let along = -(sizes.reduce((a, b) => a + b, 0) + NODE_GAP * (row.length - 1)) / 2;
+        row.forEach((v, i) => {
+          const across = offset + depth / 2;
+          const middle = along + sizes[i] / 2;
+          v.x = leftToRight ? across : middle;
+          v.y = leftToRight ? middle : across;
+          along += sizes[i] + NODE_GAP;
+        });
+        offset += depth + RANK_GAP;
+      }
+    }
+  }
+
+  // The bounding box of each expanded subgraph with a visible node, innermost first
+  function clusterBoxes(vnodes) {
+    const boxes = new Map();
+    const grow = (c, x0, y0, x1, y1) => {
+      const box = boxes.get(c);
+      if (box) {
+        box.x0 = Math.min(box.x0, x0);
+        box.y0 = Math.min(box.y0, y0);
+        box.x1 = Math.max(box.x1, x1);
+        box.y1 = Math.max(box.y1, y1);
+      } else {
+        boxes.set(c, { c, x0, y0, x1, y1 });
+      }
+    };
+    for (const v of vnodes) {
+      if (v.cluster !== null) {
+        grow(v.cluster, v.x - v.w / 2, v.y - v.h / 2, v.x + v.w / 2, v.y + v.h / 2);
+      }
+    }
+    const depth = (c) => (clusters[c].parent === null ? 0 : 1 + depth(clusters[c].parent));
+    const sorted = [...boxes.keys()].sort((a, b) => depth(b) - depth(a));
+    for (let i = 0; i < sorted.length; i++) {
+      const box = boxes.get(sorted[i]);
+      box.x0 -= CLUSTER_PAD;
+      box.x1 += CLUSTER_PAD;
+      box.y0 -= CLUSTER_PAD + CLUSTER_LABEL;
+      box.y1 += CLUSTER_PAD;
+      const parent = clusters[box.c].parent;
+      if (parent !== null) {
+        grow(parent, box.x0, box.y0, box.x1, box.y1);
+        if (!sorted.includes(parent)) sorted.push(parent);
+      }
+    }
+    return sorted.map((c) => boxes.get(c));
+  }
+
+  // Rendering
+
+  const markers = new Map();
+  let defs = null;
+
+  function marker(color) {
+    let id = markers.get(color);
+    if (!id) {
+      id = `arrow${markers.size}`;
+      markers.set(color, id);
+      const m = el(
+        "marker",
+        {
+          id,
+          viewBox: "0 0 10 10",
+          refX: 10,
+          refY: 5,
+          markerWidth: 9,
+          markerHeight: 9,
+          markerUnits: "userSpaceOnUse",
+          orient: "auto",
+        },
+        defs,
+      );
+      el("path", { d: "M0,0 L10,5 L0,10 z", fill: color }, m);
+    }
+    return id;
+  }
+
+  function drawShape(v, g, attrs, rounded) {
+    const hw = v.w / 2;
+    const hh = v.h / 2;
+    const shape = shapeOf(v);
+    switch (shape) {
+      case "box":
+      case "rect":
+      case "rectangle":
+      case "square":
+      case "record":
+      case "Mrecord":
+      case "component":
+      case "tab":
+      case "folder":
+        return el("rect", { x: -hw, y: -hh, width: v.w, height: v.h, rx: shape === "Mrecord" || rounded ? 6 : 0, ...attrs }, g);
+      case "box3d":
+        el("rect", { x: -hw + 4, y: -hh - 4, width: v.w, height: v.h, ...attrs }, g);
+        return el("rect", { x: -hw, y: -hh, width: v.w, height: v.h, ...attrs }, g);
+      case "circle":
+        return el("circle", { r: hw, ...attrs }, g);
+      case "doublecircle":
+        el("circle", { r: hw, ...attrs }, g);
+        return el("circle", { r: hw - 4, ...attrs }, g);
+      case "diamond":
+        return el("polygon", { points: `0,${-hh} ${hw},0 0,${hh} ${-hw},0`, ...attrs }, g);
+      case "hexagon": {
+        const k = Math.min(12, hw / 3);
+        const points = `${-hw + k},${-hh} ${hw - k},${-hh} ${hw},0 ${hw - k},${hh} ${-hw + k},${hh} ${-hw},0`;
+        return el("polygon", { points, ...attrs }, g);
+      }
+      case "note": {
+        const f = 8;
+        const d = `M${-hw},${-hh} H${hw - f} L${hw},${-hh + f} V${hh} H${-hw} Z M${hw - f},${-hh} V${-hh + f} H${hw}`;
+        return el("path", { d, ...attrs }, g);
+      }
+      case "cylinder": {
+        const r = 5;
+        const top = -hh + r;
+        const d =
+          `M${-hw},${top} A${hw},${r} 0 0 1 ${hw},${top} V${hh - r} A${hw},${r} 0 0 1 ${-hw},${hh - r} Z ` +
+          `M${-hw},${top} A${hw},${r} 0 0 0 ${hw},${top}`;
+        return el("path", { d, ...attrs }, g);
+      }
+      case "plaintext":
+      case "plain":
+      case "none":
+        return el("rect", { x: -hw, y: -hh, width: v.w, height: v.h, ...attrs, fill: "transparent", stroke: "none" }, g);
+      default:
+        return el("ellipse", { rx: hw, ry: hh, ...attrs }, g);
+    }
+  }
+
+  function drawNode(v, parent) {
+    const flags = styleFlags(v.attrs);
+    const g = el("g", { class: "node", transform: `translate(${v.x},${v.y})` }, parent);
+    g.vnode = v;
+    v.element = g;
+    if (flags.has("invis")) g.style.display = "none";
+
+    let fill = "#fff";
+    if (flags.has("filled")) {
+      fill = cssColor(v.attrs.fillcolor) || cssColor(v.attrs.color) || "lightgrey";
+    }
+    const attrs = { class: "shape", fill, ...strokeAttrs(v.attrs, flags, "#333") };
+    drawShape(v, g, attrs, flags.has("rounded"));
+
+    const text = el("text", { class: "label", fill: cssColor(v.attrs.fontcolor) || "#111" }, g);
+    v.lines.forEach((line, i) => {
+      const y = (i - (v.lines.length - 1) / 2) * LINE_HEIGHT;
+      el("tspan", { x: 0, y, dy: "0.35em" }, text).textContent = line;
+    });
+    const title = v.node ? [v.node.id, v.node.type].filter(Boolean).join("\n") : "Double-click to expand";
+    el("title", {}, g).textContent = title;
+  }
+
+  function edgePath(a, b) {
+    if (a === b) {
+      const x = a.x + a.w / 2;
+      return `M${x},${a.y - 6} C${x + 30},${a.y - 30} ${x + 30},${a.y + 30} ${x},${a.y + 6}`;
+    }
+    if (leftToRight) {
+      const x1 = a.x + a.w / 2;
+      const x2 = b.x - b.w / 2;
+      const d = Math.max(40, Math.abs(x2 - x1) / 2);
+      return `M${x1},${a.y} C${x1 + d},${a.y} ${x2 - d},${b.y} ${x2},${b.y}`;
+    }
+    const y1 = a.y + a.h / 2;
+    const y2 = b.y - b.h / 2;
+    const d = Math.max(40, Math.abs(y2 - y1) / 2);
+    return `M${a.x},${y1} C${a.x},${y1 + d} ${b.x},${y2 - d} ${b.x},${y2}`;
+  }
+
+  function drawEdge(edge, parent) {
+    const flags = styleFlags(edge.attrs);
+    if (flags.has("invis")) return;
+    const a = view.byId.get(edge.from);
+    const b = view.byId.get(edge.to);
+    const g = el("g", { class: "edge" }, parent);
+    edge.element = g;
+    const stroke = strokeAttrs(edge.attrs, flags, "#666");
+    const path = el("path", { d: edgePath(a, b), fill: "none", ...stroke }, g);
+    if (edge.attrs.arrowhead !== "none") {
+      path.setAttribute("marker-end", `url(#${marker(stroke.stroke)})`);
+    }
+    if (edge.label) {
+      const x = (a.x + b.x) / 2;
+      const y = (a.y + b.y) / 2;
+      const fill = cssColor(edge.attrs.fontcolor) || "#444";
+      el("text", { class: "edge-label", x, y, fill }, g).textContent = edge.label;
+    }
+  }
+
+  function drawCluster(box, parent) {
+    const cluster = clusters[box.c];
+    const flags = styleFlags(cluster.attrs);
+    const fill = flags.has("filled")
+      ? cssColor(cluster.attrs.fillcolor) || cssColor(cluster.attrs.color) || "lightgrey"
+      : "rgba(0,0,0,0.025)";
+    const g = el("g", { class: "cluster" }, parent);
+    el(
+      "rect",
+      {
+        x: box.x0,
+        y: box.y0,
+        width: box.x1 - box.x0,
+        height: box.y1 - box.y0,
+        rx: 4,
+        fill,
+        ...strokeAttrs(cluster.attrs, flags, "#aaa"),
+      },
+      g,
+    );
+    const fontcolor = cssColor(cluster.attrs.fontcolor) || "#555";
+    const label = el("text", { class: "cluster-label", x: box.x0 + 6, y: box.y0 + 13, fill: fontcolor }, g);
+    label.textContent = `▾ ${cluster.label}`;
+    label.clusterIndex = box.c;
+    el("title", {}, label).textContent = "Click to collapse";
+  }
+
+  function render() {
+    view = visibleGraph();
+    view.vnodes.forEach(measure);
+    layout(view.vnodes, view.vedges);
+    const boxes = clusterBoxes(view.vnodes);
+
+    svg.replaceChildren();
+    markers.clear();
+    defs = el("defs", {}, svg);
+    const viewport = el("g", { id: "viewport" }, svg);
+    const clusterLayer = el("g", {}, viewport);
+    const edgeLayer = el("g", {}, viewport);
+    const nodeLayer = el("g", {}, viewport);
+    for (const box of [...boxes].reverse()) drawCluster(box, clusterLayer);
+    for (const edge of view.vedges) drawEdge(edge, edgeLayer);
+    for (const v of view.vnodes) drawNode(v, nodeLayer);
+
+    const extents = view.vnodes
+      .map((v) => ({ x0: v.x - v.w / 2, y0: v.y - v.h / 2, x1: v.x + v.w / 2, y1: v.y + v.h / 2 }))
+      .concat(boxes);
+    view.bounds = extents.reduce(
+      (b, e) => ({
+        x0: Math.min(b.x0, e.x0),
+        y0: Math.min(b.y0, e.y0),
+        x1: Math.max(b.x1, e.x1),
+        y1: Math.max(b.y1, e.y1),
+      }),
+      extents.length ? { ...extents[0] } : { x0: 0, y0: 0, x1: 0, y1: 0 },
+    );
+
+    if (selected !== null && !view.byId.has(selected)) selected = null;
+    applyTransform();
+    highlight();
+    updateMatches();
+    renderClusterTree();
+  }
+
+  // Pan and zoom
+
+  function applyTransform() {
+    const viewport = document.getElementById("viewport");
+    viewport.setAttribute("transform", `translate(${transform.x},${transform.y}) scale(${transform.k})`);
+  }
+
+  function fit() {
+    const rect = svg.getBoundingClientRect();
+    const b = view.bounds;
+    const w = b.x1 - b.x0 + 40;
+    const h = b.y1 - b.y0 + 40;
+    transform.k = Math.min(rect.width / w, rect.height / h, 1.5);
+    transform.x = rect.width / 2 - ((b.x0 + b.x1) / 2) * transform.k;
+    transform.y = rect.height / 2 - ((b.y0 + b.y1) / 2) * transform.k;
+    applyTransform();
+  }
+
+  function centerOn(v) {
+    const rect = svg.getBoundingClientRect();
+    transform.k = Math.max(transform.k, 1);
+    transform.x = rect.width / 2 - v.x * transform.k;
+    transform.y = rect.height / 2 - v.y * transform.k;
+    applyTransform();
+  }
+
+  svg.addEventListener(
+    "wheel",
+    (event) => {
+      event.preventDefault();
+      const rect = svg.getBoundingClientRect();
+      const px = event.clientX - rect.left;
+      const py = event.clientY - rect.top;
+      const k = Math.min(8, Math.max(0.01, transform.k * Math.exp(-event.deltaY * 0.0015)));
+      transform.x = px - ((px - transform.x) * k) / transform.k;
+      transform.y = py - ((py - transform.y) * k) / transform.k;
+      transform.k = k;
+      applyTransform();
+    },
+    { passive: false },
+  );
+
+  let drag = null;
+  svg.addEventListener("pointerdown", (event) => {
+    if (event.button !== 0) return;
+    drag = { x: event.clientX, y: event.clientY, tx: transform.x, ty: transform.y, target: event.target, moved: false };
+  });
+  svg.addEventListener("pointermove", (event) => {
+    if (!drag) return;
+    const dx = event.clientX - drag.x;
+    const dy = event.clientY - drag.y;
+    if (!drag.moved && Math.abs(dx) + Math.abs(dy) > 3) {
+      drag.moved = true;
+      svg.setPointerCapture(event.pointerId);
+      svg.classList.add("panning");
+    }
+    if (drag.moved) {
+      transform.x = drag.tx + dx;
+      transform.y = drag.ty + dy;
+      applyTransform();
+    }
+  });
+  svg.addEventListener("pointerup", () => {
+    if (drag && !drag.moved) click(drag.target);
+    drag = null;
+    svg.classList.remove("panning");
+  });
+  svg.addEventListener("dblclick", (event) => {
+    const node = event.target.closest(".node");
+    if (node && node.vnode.clusterIndex !== undefined) {
+      collapsed.delete(node.vnode.clusterIndex);
+      render();
+    }
+  });
+
+  function click(target) {
+    const label = target.closest(".cluster-label");
+    if (label) {
+      collapsed.add(label.clusterIndex);
+      render();
+      return;
+    }
+    const node = target.closest(".node");
+    select(node && node.vnode.vid !== selected ? node.vnode.vid : null);
+  }
+
+  // Highlighting
+
+  function reachable(start, adjacency) {
+    const seen = new Set();
+    const stack = [start];
+    while (stack.length) {
+      for (const w of adjacency.get(stack.pop())) {
+        if (!seen.has(w)) {
+          seen.add(w);
+          stack.push(w);
+        }
+      }
+    }
+    return seen;
+  }
+
+  function select(vid) {
+    selected = vid;
+    highlight();
+  }
+
+  function highlight() {
+    const deps = selected === null ? new Set() : reachable(selected, view.succ);
+    const rdeps = selected === null ? new Set() : reachable(selected, view.pred);
+    svg.classList.toggle("focus", selected !== null);
+    for (const v of view.vnodes) {
+      v.element.classList.toggle("selected", v.vid === selected);
+      v.element.classList.toggle("dep", deps.has(v.vid));
+      v.element.classList.toggle("rdep", rdeps.has(v.vid));
+    }
+    for (const edge of view.vedges) {
+      if (!edge.element) continue;
+      const dep = (edge.from === selected || deps.has(edge.from)) && deps.has(edge.to);
+      const rdep = rdeps.has(edge.from) && (edge.to === selected || rdeps.has(edge.to));
+      edge.element.classList.toggle("dep", dep);
+      edge.element.classList.toggle("rdep", rdep);
+    }
+    showDetails(deps, rdeps);
+  }
+
+  function showDetails(deps, rdeps) {
+    details.replaceChildren();
+    details.hidden = selected === null;
+    if (selected === null) return;
+    const v = view.byId.get(selected);
+    const add = (tag, text, parent = details) => {
+      const element = document.createElement(tag);
+      if (text !== undefined) element.textContent = text;
+      parent.appendChild(element);
+      return element;
+    };
+
+    add("h2", v.node ? v.node.label : clusters[v.clusterIndex].label);
+    const list = add("dl");
+    const attrs = v.node ? { id: v.node.id, type: v.node.type, ...v.node.attrs } : clusters[v.clusterIndex].attrs;
+    for (const [key, value] of Object.entries(attrs)) {
+      if (value === undefined) continue;
+      add("dt", key, list);
+      add("dd", value, list);
+    }
+
+    const neighbors = (title, direct, all) => {
+      add("h3", `${title}: ${direct.length} direct, ${all.size} total`);
+      const ul = add("ul");
+      for (const vid of [...new Set(direct)]) {
+        const button = add("button", view.byId.get(vid).label, add("li", undefined, ul));
+        button.addEventListener("click", () => {
+          select(vid);
+          centerOn(view.byId.get(vid));
+        });
+      }
+    };
+    neighbors("Dependencies", view.succ.get(selected), deps);
+    neighbors("Dependents", view.pred.get(selected), rdeps);
+  }
+
+  // Search
+
+  function updateMatches() {
+    const query = search.value.trim().toLowerCase();
+    const vids = new Set();
+    let count = 0;
+    if (query) {
+      for (const node of nodes.values()) {
+        if (node.label.toLowerCase().includes(query) || node.id.toLowerCase().includes(query)) {
+          count++;
+          vids.add(view.repOf.get(node.id));
+        }
+      }
+    }
+    matches = view.vnodes.filter((v) => vids.has(v.vid));
+    matchIndex = -1;
+    for (const v of view.vnodes) v.element.classList.toggle("match", vids.has(v.vid));
+    searchStatus.textContent = query ? `${count} matching node${count === 1 ? "" : "s"}` : "";
+  }
+
+  search.addEventListener("input", updateMatches);
+  search.addEventListener("keydown", (event) => {
+    if (event.key === "Enter" && matches.length) {
+      matchIndex = (matchIndex + 1) % matches.length;
+      select(matches[matchIndex].vid);
+      centerOn(matches[matchIndex]);
+    }
+  });
+  document.addEventListener("keydown", (event) => {
+    if (event.key === "Escape") {
+      search.value = "";
+      updateMatches();
+      select(null);
+    } else if (event.key === "/" && document.activeElement !== search) {
+      event.preventDefault();
+      search.focus();
+    }
+  });
+
+  // Subgraphs
+
+  function renderClusterTree() {
+    const section = document.getElementById("clusters");
+    section.hidden = clusters.length === 0;
+    const build = (indices, parent) => {
+      const ul = document.createElement("ul");
+      for (const c of indices) {
+        const li = document.createElement("li");
+        const label = document.createElement("label");
+        const checkbox = document.createElement("input");
+        checkbox.type = "checkbox";
+        checkbox.checked = !collapsed.has(c);
+        checkbox.addEventListener("change", () => {
+          if (checkbox.checked) collapsed.delete(c);
+          else collapsed.add(c);
+          render();
+        });
+        label.append(checkbox, ` ${clusters[c].label} (${clusters[c].size})`);
+        li.appendChild(label);
+        if (clusters[c].children.length) build(clusters[c].children, li);
+        ul.appendChild(li);
+      }
+      parent.appendChild(ul);
+    };
+    const tree = document.getElementById("cluster-tree");
+    tree.replaceChildren();
+    build(
+      clusters.filter((c) => c.parent === null).map((c) => c.index),
+      tree,
+    );
+  }
+
+  document.getElementById("fit").addEventListener("click", fit);
+  document.getElementById("expand-all").addEventListener("click", () => {
+    collapsed.clear();
+    render();
+  });
+  document.getElementById("collapse-all").addEventListener("click", () => {
+    collapsed.clear();
+    for (const cluster of clusters) if (cluster.parent === null) collapsed.add(cluster.index);
+    render();
+  });
+
+  document.getElementById("stats").textContent = `${nodes.size} nodes, ${edges.length} edges`;
+  render();
+  fit();
+})();
@@ -0,0 +1,218 @@
+use std::io::Write;
+
+use indexmap::IndexMap;
+use serde_json::Value;
+
+use crate::parse::json::{Document, Graph, JgfEdge, JgfNode, Nodes};
+use crate::{DepGraph, NodeInfo};
+
+fn convert_attrs(attrs: &IndexMap<String, String>) -> IndexMap<String, Value> {
+    attrs
+        .iter()
+        .map(|(k, v)| (k.clone(), Value::String(v.clone())))
+        .collect()
+}
+
+fn convert_node(info: &NodeInfo) -> JgfNode {
+    let mut metadata = IndexMap::new();
+    if let Some(node_type) = &info.node_type {
+        metadata.insert("type".to_string(), Value::String(node_type.clone()));
+    }
+    metadata.extend(convert_attrs(&info.attrs));
+    JgfNode {
+        label: Some(info.label.clone()),
+        metadata,
+    }
+}
+
+fn convert_graph(graph: &DepGraph, directed: Option<bool>) -> Graph {
+    Graph {
+        id: graph.id.clone(),
+        directed,
+        label: None,
+        metadata: convert_attrs(&graph.attrs),
+        nodes: Nodes::Map(
+            graph
+                .nodes
+                .iter()
+                .map(|(id, info)| (id.clone(), convert_node(info)))
+                .collect(),
+        ),
+        edges: graph
+            .edges
+            .iter()
+            .map(|e| JgfEdge {
+                source: e.from.clone(),
+                target: e.to.clone(),
+                relation: None,
+                label: e.label.clone(),
+                metadata: convert_attrs(&e.attrs),
+            })
+            .collect(),
+        subgraphs: graph
+            .subgraphs
+            .iter()
+            .map(|sg| convert_graph(sg, None))
+            .collect(),
+    }
+}
+
+/// Convert a [`DepGraph`] to the JGF document written by [`emit`].
+pub(crate) fn document(graph: &DepGraph) -> Document {
+    Document {
+        graph: convert_graph(graph, Some(true)),
+    }
+}
+
+/// Emit a [`DepGraph`] as a JSON Graph Format (JGF v2) document.
+///
+/// All graph features are preserved:
+/// - Graph, node, and edge attrs are emitted as `metadata` objects with string values.
+/// - Node types are emitted as `metadata.type`, matching the DOT `type` attr.
+/// - Subgraphs are emitted recursively under a non-standard `subgraphs` key on each graph
+///   object. Other JGF consumers will only see the top-level nodes and edges.
+pub fn emit(graph: &DepGraph, writer: &mut dyn Write) -> eyre::Result<()> {
+    serde_json::to_writer_pretty(&mut *writer, &document(graph))?;
+    writeln!(writer)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Edge;
+    use crate::emit::fixtures::sample_graph;
+
+    fn emit_to_string(graph: &DepGraph) -> String {
+        let mut buf = Vec::new();
+        emit(graph, &mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn empty_graph() {
+        let output = emit_to_string(&DepGraph::default());
+        assert_eq!(
+            output,
+            r#"{
+  "graph": {
+    "directed": true,
+    "nodes": {},
+    "edges": []
+  }
+}
+"#
+        );
+    }
+
+    #[test]
+    fn sample() {
+        let output = emit_to_string(&sample_graph());
+        assert_eq!(
+            output,
+            r#"{
+  "graph": {
+    "directed": true,
+    "nodes": {
+      "a": {
+        "label": "alpha"
+      },
+      "b": {
+        "label": "bravo"
+      },
+      "c": {
+        "label": "c"
+      }
+    },
+    "edges": [
+      {
+        "source": "a",
+        "target": "b",
+        "label": "depends"
+      },
+      {
+        "source": "b",
+        "target": "c"
+      },
+      {
+        "source": "a",
+        "target": "c"
+      }
+    ]
+  }
+}
+"#
+        );
+    }
+
+    #[test]
+    fn types_attrs_and_subgraphs() {
+        let graph = DepGraph {
+            id: Some("deps".into()),
+            attrs: IndexMap::from([("rankdir".into(), "LR".into())]),
+            nodes: IndexMap::from([(
+                "a".into(),
+                NodeInfo {
+                    label: "A \"quoted\"".into(),
+                    node_type: Some("lib".into()),
+                    attrs: IndexMap::from([("version".into(), "1.0".into())]),
+                },
+            )]),
+            edges: vec![Edge {
+                from: "a".into(),
+                to: "b".into(),
+                label: None,
+                attrs: IndexMap::from([("kind".into(), "dev".into())]),
+            }],
+            subgraphs: vec![DepGraph {
+                id: Some("inner".into()),
+                nodes: IndexMap::from([("b".into(), NodeInfo::new("b"))]),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let output = emit_to_string(&graph);
+        assert_eq!(
+            output,
+            r#"{
+  "graph": {
+    "id": "deps",
+    "directed": true,
+    "metadata": {
+      "rankdir": "LR"
+    },
+    "nodes": {
+      "a": {
+        "label": "A \"quoted\"",
+        "metadata": {
+          "type": "lib",
+          "version": "1.0"
+        }
+      }
+    },
+    "edges": [
+      {
+        "source": "a",
+        "target": "b",
+        "metadata": {
+          "kind": "dev"
+        }
+      }
+    ],
+    "subgraphs": [
+      {
+        "id": "inner",
+        "nodes": {
+          "b": {
+            "label": "b"
+          }
+        },
+        "edges": []
+      }
+    ]
+  }
+}
+"#
+        );
+    }
+}
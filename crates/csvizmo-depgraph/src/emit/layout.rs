@@ -0,0 +1,1187 @@
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+
+use indexmap::{IndexMap, IndexSet};
+
+use crate::DepGraph;
+
+/// Gap between adjacent nodes in a layer.
+const NODE_SEP: f64 = 24.0;
+/// Gap next to the dummy nodes that long edges are routed through.
+const DUMMY_SEP: f64 = 10.0;
+/// Gap between adjacent layers, before making room for subgraph boxes.
+const RANK_SEP: f64 = 48.0;
+/// Padding between a subgraph's box and its contents.
+const CLUSTER_PAD: f64 = 12.0;
+/// Room for a subgraph's label along the top of its box.
+pub(crate) const CLUSTER_LABEL: f64 = 16.0;
+/// Number of barycenter sweeps when ordering the layers.
+const ORDER_SWEEPS: usize = 24;
+/// Number of sweeps pulling nodes towards their neighbors when assigning coordinates.
+const POSITION_SWEEPS: usize = 16;
+
+/// The direction that edges flow in, from a `rankdir` (or `direction`) graph attr.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Direction {
+    TopBottom,
+    BottomTop,
+    LeftRight,
+    RightLeft,
+}
+
+impl Direction {
+    pub(crate) fn of(graph: &DepGraph) -> Self {
+        let rankdir = graph.attrs.get("rankdir").or(graph.attrs.get("direction"));
+        match rankdir.map(|d| d.as_str()) {
+            Some("LR") => Self::LeftRight,
+            Some("RL") => Self::RightLeft,
+            Some("BT") => Self::BottomTop,
+            _ => Self::TopBottom,
+        }
+    }
+
+    /// Whether layers are columns rather than rows.
+    pub(crate) fn is_horizontal(self) -> bool {
+        matches!(self, Self::LeftRight | Self::RightLeft)
+    }
+}
+
+/// A node's center and size.
+pub(crate) struct NodeLayout<'a> {
+    pub id: &'a str,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// The route of an edge, from its source to its target.
+pub(crate) struct EdgeLayout {
+    /// The index of the edge in [`DepGraph::all_edges`].
+    pub edge: usize,
+    /// The points the edge passes through. Consecutive points are either joined by a straight
+    /// segment across a layer, or by a curve between layers.
+    pub points: Vec<(f64, f64)>,
+}
+
+/// A subgraph's box, from its top left corner.
+pub(crate) struct ClusterLayout<'a> {
+    pub graph: &'a DepGraph,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// The coordinates of every node, edge, and non-empty subgraph of a [`DepGraph`].
+pub(crate) struct Layout<'a> {
+    pub direction: Direction,
+    pub width: f64,
+    pub height: f64,
+    pub nodes: Vec<NodeLayout<'a>>,
+    pub edges: Vec<EdgeLayout>,
+    /// Subgraphs, outermost first.
+    pub clusters: Vec<ClusterLayout<'a>>,
+}
+
+struct Cluster<'a> {
+    graph: &'a DepGraph,
+    parent: Option<usize>,
+    /// The first and last layer with a node in this cluster, if it has any.
+    span: Option<(usize, usize)>,
+    /// Size along the layers, including padding. Filled in when the cluster is positioned.
+    width: f64,
+    /// The left edge, relative to the parent's left edge until every level is positioned.
+    left: f64,
+}
+
+/// Walk the subgraphs in pre-order, recording the innermost subgraph that declares each node.
+fn collect<'a>(
+    graph: &'a DepGraph,
+    parent: Option<usize>,
+    clusters: &mut Vec<Cluster<'a>>,
+    members: &mut IndexMap<&'a str, Option<usize>>,
+) {
+    for id in graph.nodes.keys() {
+        members.insert(id, parent);
+    }
+    for sg in &graph.subgraphs {
+        let index = clusters.len();
+        clusters.push(Cluster {
+            graph: sg,
+            parent,
+            span: None,
+            width: 0.0,
+            left: 0.0,
+        });
+        collect(sg, Some(index), clusters, members);
+    }
+}
+
+/// Find the edges that close a cycle, with a DFS from the roots.
+fn back_edges(n: usize, edges: &[(usize, usize)]) -> Vec<bool> {
+    let mut out = vec![Vec::new(); n];
+    let mut has_pred = vec![false; n];
+    for (i, &(a, b)) in edges.iter().enumerate() {
+        out[a].push((b, i));
+        has_pred[b] = true;
+    }
+
+    // 0 is unvisited, 1 is on the DFS stack, and 2 is finished
+    let mut state = vec![0u8; n];
+    let mut back = vec![false; edges.len()];
+    let roots = (0..n).filter(|&v| !has_pred[v]);
+    for start in roots.chain(0..n) {
+        if state[start] != 0 {
+            continue;
+        }
+        state[start] = 1;
+        let mut stack = vec![(start, 0)];
+        while let Some(top) = stack.last_mut() {
+            let (v, next) = *top;
+            if let Some(&(w, i)) = out[v].get(next) {
+                top.1 += 1;
+                match state[w] {
+                    0 => {
+                        state[w] = 1;
+                        stack.push((w, 0));
+                    }
+                    1 => back[i] = true,
+                    _ => {}
+                }
+            } else {
+                state[v] = 2;
+                stack.pop();
+            }
+        }
+    }
+    back
+}
+
+/// Put each node of a DAG on a layer below all of its predecessors.
+///
+/// Nodes start on the layer of their longest path from a root, and are then pulled down to just
+/// above their closest successor, so that short edges stay short.
+fn assign_layers(n: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    let mut succ = vec![Vec::new(); n];
+    let mut indegree = vec![0; n];
+    for &(a, b) in edges {
+        succ[a].push(b);
+        indegree[b] += 1;
+    }
+    let mut queue: VecDeque<usize> = (0..n).filter(|&v| indegree[v] == 0).collect();
+    let mut order = Vec::with_capacity(n);
+    while let Some(v) = queue.pop_front() {
+        order.push(v);
+        for &w in &succ[v] {
+            indegree[w] -= 1;
+            if indegree[w] == 0 {
+                queue.push_back(w);
+            }
+        }
+    }
+
+    let mut layer = vec![0; n];
+    for &v in &order {
+        for &w in &succ[v] {
+            layer[w] = layer[w].max(layer[v] + 1);
+        }
+    }
+    for &v in order.iter().rev() {
+        if let Some(closest) = succ[v].iter().map(|&w| layer[w]).min() {
+            layer[v] = closest - 1;
+        }
+    }
+    layer
+}
+
+/// The sort key of a node within its layer: the mean position of each subgraph it's nested in,
+/// outermost first, and then its own position.
+///
+/// Sorting by these keys keeps every subgraph contiguous within a layer. The second element of
+/// each pair breaks ties between subgraphs, so that sibling subgraphs are always in the same order.
+type Key = Vec<(f64, usize)>;
+
+fn compare_keys(a: &Key, b: &Key) -> Ordering {
+    for (x, y) in a.iter().zip(b) {
+        let order = x.0.total_cmp(&y.0).then(x.1.cmp(&y.1));
+        if order != Ordering::Equal {
+            return order;
+        }
+    }
+    a.len().cmp(&b.len())
+}
+
+/// An item within the layers of a subgraph: one of its own nodes, or a nested subgraph.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Item {
+    Node(usize),
+    Cluster(usize),
+}
+
+/// A proper layered graph, where every edge spans exactly one layer.
+struct Layered<'a> {
+    clusters: Vec<Cluster<'a>>,
+    /// The innermost cluster of each node. Real nodes come first, followed by dummy nodes.
+    cluster: Vec<Option<usize>>,
+    real: usize,
+    /// The size of each node along its layer, and across it.
+    breadth: Vec<f64>,
+    depth: Vec<f64>,
+    layer: Vec<usize>,
+    succ: Vec<Vec<usize>>,
+    pred: Vec<Vec<usize>>,
+    layers: Vec<Vec<usize>>,
+    /// The centered position of each node within its layer.
+    pos: Vec<f64>,
+    /// The sum of the positions of the nodes within each cluster, including nested clusters.
+    sum: Vec<f64>,
+    count: Vec<usize>,
+    /// The position of each node along its layer, relative to the left edge of the outermost
+    /// cluster positioned so far.
+    offset: Vec<f64>,
+}
+
+impl Layered<'_> {
+    fn add_node(&mut self, cluster: Option<usize>, size: (f64, f64), layer: usize) -> usize {
+        self.cluster.push(cluster);
+        self.breadth.push(size.0);
+        self.depth.push(size.1);
+        self.layer.push(layer);
+        self.succ.push(Vec::new());
+        self.pred.push(Vec::new());
+        self.layer.len() - 1
+    }
+
+    fn add_edge(&mut self, a: usize, b: usize) {
+        self.succ[a].push(b);
+        self.pred[b].push(a);
+    }
+
+    fn is_dummy(&self, v: usize) -> bool {
+        v >= self.real
+    }
+
+    fn ancestors(&self, mut cluster: Option<usize>) -> Vec<usize> {
+        let mut ancestors = Vec::new();
+        while let Some(c) = cluster {
+            ancestors.push(c);
+            cluster = self.clusters[c].parent;
+        }
+        ancestors
+    }
+
+    /// The innermost cluster containing both nodes.
+    fn common_cluster(&self, a: usize, b: usize) -> Option<usize> {
+        let ancestors = self.ancestors(self.cluster[a]);
+        let mut cluster = self.cluster[b];
+        while let Some(c) = cluster {
+            if ancestors.contains(&c) {
+                return Some(c);
+            }
+            cluster = self.clusters[c].parent;
+        }
+        None
+    }
+
+    /// The item containing a node at the level of the given cluster, or the root if `None`.
+    fn item_at(&self, v: usize, level: Option<usize>) -> Option<Item> {
+        let mut item = Item::Node(v);
+        let mut cluster = self.cluster[v];
+        loop {
+            if cluster == level {
+                return Some(item);
+            }
+            let c = cluster?;
+            item = Item::Cluster(c);
+            cluster = self.clusters[c].parent;
+        }
+    }
+
+    fn mean(&self, c: usize) -> f64 {
+        self.sum[c] / self.count[c] as f64
+    }
+
+    fn key(&self, v: usize, value: f64) -> Key {
+        let mut key: Key = self
+            .ancestors(self.cluster[v])
+            .into_iter()
+            .rev()
+            .map(|c| (self.mean(c), c))
+            .collect();
+        key.push((value, usize::MAX));
+        key
+    }
+
+    /// Move a node's contribution to the cluster sums.
+    fn shift(&mut self, v: usize, delta: f64) {
+        for c in self.ancestors(self.cluster[v]) {
+            self.sum[c] += delta;
+        }
+    }
+
+    fn place(&mut self, l: usize) {
+        let center = (self.layers[l].len() as f64 - 1.0) / 2.0;
+        for i in 0..self.layers[l].len() {
+            let v = self.layers[l][i];
+            let pos = i as f64 - center;
+            self.shift(v, pos - self.pos[v]);
+            self.pos[v] = pos;
+        }
+    }
+
+    /// Sort a layer by the barycenter of each node's neighbors in the previous layer, or the
+    /// next layer when sweeping up.
+    fn reorder(&mut self, l: usize, down: bool) {
+        let layer = std::mem::take(&mut self.layers[l]);
+        let mut values = Vec::with_capacity(layer.len());
+        for &v in &layer {
+            let adjacent = if down { &self.pred[v] } else { &self.succ[v] };
+            let value = if adjacent.is_empty() {
+                self.pos[v]
+            } else {
+                adjacent.iter().map(|&w| self.pos[w]).sum::<f64>() / adjacent.len() as f64
+            };
+            values.push(value);
+        }
+        for (&v, &value) in layer.iter().zip(&values) {
+            self.shift(v, value - self.pos[v]);
+            self.pos[v] = value;
+        }
+        let mut keyed: Vec<(Key, usize)> = layer
+            .iter()
+            .zip(&values)
+            .map(|(&v, &value)| (self.key(v, value), v))
+            .collect();
+        keyed.sort_by(|a, b| compare_keys(&a.0, &b.0));
+        self.layers[l] = keyed.into_iter().map(|(_, v)| v).collect();
+        self.place(l);
+    }
+
+    /// Count the edge crossings between every pair of adjacent layers.
+    fn crossings(&self) -> usize {
+        let mut index = vec![0; self.layer.len()];
+        for layer in &self.layers {
+            for (i, &v) in layer.iter().enumerate() {
+                index[v] = i;
+            }
+        }
+
+        let mut crossings = 0;
+        for l in 1..self.layers.len() {
+            let mut ends: Vec<(usize, usize)> = self.layers[l - 1]
+                .iter()
+                .flat_map(|&v| {
+                    let index = &index;
+                    self.succ[v].iter().map(move |&w| (index[v], index[w]))
+                })
+                .collect();
+            ends.sort_unstable();
+
+            // Count the inversions of the lower ends with a Fenwick tree
+            let mut tree = vec![0usize; self.layers[l].len() + 1];
+            for (seen, &(_, lower)) in ends.iter().enumerate() {
+                let mut at_most = 0;
+                let mut i = lower + 1;
+                while i > 0 {
+                    at_most += tree[i];
+                    i &= i - 1;
+                }
+                crossings += seen - at_most;
+                let mut i = lower + 1;
+                while i < tree.len() {
+                    tree[i] += 1;
+                    i += i & i.wrapping_neg();
+                }
+            }
+        }
+        crossings
+    }
+
+    /// Order the nodes within each layer to reduce edge crossings.
+    fn order(&mut self) {
+        // Start from the order of a DFS from the roots, so that subtrees start out together
+        let n = self.layer.len();
+        let mut discovered = vec![usize::MAX; n];
+        let mut discoveries = 0;
+        let mut starts: Vec<usize> = (0..n).collect();
+        starts.sort_by_key(|&v| self.layer[v]);
+        for start in starts {
+            if discovered[start] != usize::MAX {
+                continue;
+            }
+            let mut stack = vec![start];
+            while let Some(v) = stack.pop() {
+                if discovered[v] != usize::MAX {
+                    continue;
+                }
+                discovered[v] = discoveries;
+                discoveries += 1;
+                stack.extend(self.succ[v].iter().rev());
+            }
+        }
+
+        self.layers = vec![Vec::new(); self.layer.iter().max().map_or(0, |&l| l + 1)];
+        for v in 0..n {
+            self.layers[self.layer[v]].push(v);
+        }
+        for l in 0..self.layers.len() {
+            self.layers[l].sort_by_key(|&v| discovered[v]);
+            self.place(l);
+        }
+
+        let mut best = self.layers.clone();
+        let mut fewest = self.crossings();
+        for sweep in 0..ORDER_SWEEPS {
+            if fewest == 0 {
+                break;
+            }
+            if sweep % 2 == 0 {
+                for l in 1..self.layers.len() {
+                    self.reorder(l, true);
+                }
+            } else {
+                for l in (0..self.layers.len().saturating_sub(1)).rev() {
+                    self.reorder(l, false);
+                }
+            }
+            let crossings = self.crossings();
+            if crossings < fewest {
+                fewest = crossings;
+                best = self.layers.clone();
+            }
+        }
+        self.layers = best;
+        for l in 0..self.layers.len() {
+            self.place(l);
+        }
+
+        // Each layer was sorted against the cluster means at the time, which drift during a
+        // sweep. Sort them all against the final means, so that sibling clusters are in the same
+        // order in every layer.
+        for l in 0..self.layers.len() {
+            let mut keyed: Vec<(Key, usize)> = self.layers[l]
+                .iter()
+                .map(|&v| (self.key(v, self.pos[v]), v))
+                .collect();
+            keyed.sort_by(|a, b| compare_keys(&a.0, &b.0));
+            self.layers[l] = keyed.into_iter().map(|(_, v)| v).collect();
+        }
+    }
+
+    fn item_key(&self, item: Item) -> (f64, usize) {
+        match item {
+            Item::Node(v) => (self.pos[v], usize::MAX),
+            Item::Cluster(c) => (self.mean(c), c),
+        }
+    }
+
+    fn item_breadth(&self, item: Item) -> f64 {
+        match item {
+            Item::Node(v) => self.breadth[v],
+            Item::Cluster(c) => self.clusters[c].width,
+        }
+    }
+
+    /// The offset of a node from the center of the item containing it.
+    fn offset_in(&self, v: usize, item: Item) -> f64 {
+        match item {
+            Item::Node(_) => 0.0,
+            Item::Cluster(c) => self.offset[v] - self.clusters[c].width / 2.0,
+        }
+    }
+
+    /// Assign coordinates along the layers to the items of a cluster, or the root.
+    ///
+    /// Nested clusters have already been positioned, and are placed as rigid blocks that span
+    /// every layer they have a node in. The items are packed to the left, and then repeatedly
+    /// pulled towards the median of their neighbors without overlapping.
+    fn position(&mut self, level: Option<usize>, members: &[usize], pad: (f64, f64)) {
+        let (first, last) = match level {
+            Some(c) => self.clusters[c]
+                .span
+                .expect("positioned clusters have nodes"),
+            None => (0, self.layers.len() - 1),
+        };
+
+        let mut items = IndexSet::new();
+        let mut rows = Vec::new();
+        for l in first..=last {
+            let mut row: Vec<Item> = Vec::new();
+            for &v in &self.layers[l] {
+                if let Some(item) = self.item_at(v, level)
+                    && row.last() != Some(&item)
+                {
+                    row.push(item);
+                }
+            }
+            // Clusters span every layer between their first and last, even those without any of
+            // their nodes, so they're boxes rather than ragged shapes
+            let spanning = (0..self.clusters.len()).filter(|&c| {
+                self.clusters[c].parent == level
+                    && self.clusters[c]
+                        .span
+                        .is_some_and(|(lo, hi)| lo <= l && l <= hi)
+            });
+            for c in spanning {
+                let item = Item::Cluster(c);
+                if !row.contains(&item) {
+                    let key = self.item_key(item);
+                    let at = row
+                        .iter()
+                        .position(|&other| {
+                            let other = self.item_key(other);
+                            other.0.total_cmp(&key.0).then(other.1.cmp(&key.1)) == Ordering::Greater
+                        })
+                        .unwrap_or(row.len());
+                    row.insert(at, item);
+                }
+            }
+            rows.push(
+                row.into_iter()
+                    .map(|item| items.insert_full(item).0)
+                    .collect::<Vec<usize>>(),
+            );
+        }
+
+        // Separation constraints between adjacent items
+        let m = items.len();
+        let mut left = vec![Vec::new(); m];
+        let mut right = vec![Vec::new(); m];
+        for row in &rows {
+            for pair in row.windows(2) {
+                let (a, b) = (items[pair[0]], items[pair[1]]);
+                let dummy = |item: Item| matches!(item, Item::Node(v) if self.is_dummy(v));
+                let gap = if dummy(a) || dummy(b) {
+                    DUMMY_SEP
+                } else {
+                    NODE_SEP
+                };
+                let sep = (self.item_breadth(a) + self.item_breadth(b)) / 2.0 + gap;
+                right[pair[0]].push((pair[1], sep));
+                left[pair[1]].push((pair[0], sep));
+            }
+        }
+
+        // The targets each item is pulled towards: a neighbor's position plus an offset
+        let mut targets = vec![Vec::new(); m];
+        for &v in members {
+            let Some(item) = self.item_at(v, level) else {
+                continue;
+            };
+            let from = items.get_index_of(&item).expect("every member is in a row");
+            for &w in self.pred[v].iter().chain(&self.succ[v]) {
+                if let Some(other) = self.item_at(w, level)
+                    && other != item
+                {
+                    let to = items
+                        .get_index_of(&other)
+                        .expect("every member is in a row");
+                    let delta = self.offset_in(w, other) - self.offset_in(v, item);
+                    targets[from].push((to, delta));
+                }
+            }
+        }
+
+        // Pack to the left, in topological order of the constraints
+        let mut x = vec![0.0; m];
+        let mut indegree: Vec<usize> = left.iter().map(|l| l.len()).collect();
+        let mut queue: VecDeque<usize> = (0..m).filter(|&i| indegree[i] == 0).collect();
+        while let Some(i) = queue.pop_front() {
+            for &(j, sep) in &right[i] {
+                x[j] = f64::max(x[j], x[i] + sep);
+                indegree[j] -= 1;
+                if indegree[j] == 0 {
+                    queue.push_back(j);
+                }
+            }
+        }
+
+        for sweep in 0..POSITION_SWEEPS {
+            let mut order: Vec<usize> = (0..m).collect();
+            order.sort_by(|&a, &b| x[a].total_cmp(&x[b]));
+            if sweep % 2 == 1 {
+                order.reverse();
+            }
+            for i in order {
+                if targets[i].is_empty() {
+                    continue;
+                }
+                let mut wanted: Vec<f64> = targets[i].iter().map(|&(j, d)| x[j] + d).collect();
+                wanted.sort_by(f64::total_cmp);
+                let mid = wanted.len() / 2;
+                let median = if wanted.len().is_multiple_of(2) {
+                    (wanted[mid - 1] + wanted[mid]) / 2.0
+                } else {
+                    wanted[mid]
+                };
+                let lo = left[i]
+                    .iter()
+                    .map(|&(j, sep)| x[j] + sep)
+                    .fold(f64::NEG_INFINITY, f64::max);
+                let hi = right[i]
+                    .iter()
+                    .map(|&(j, sep)| x[j] - sep)
+                    .fold(f64::INFINITY, f64::min);
+                if lo <= hi {
+                    x[i] = median.clamp(lo, hi);
+                }
+            }
+        }
+
+        // Shift the items to start after the padding, and size the cluster to fit them
+        let extent = |i: usize| {
+            let half = self.item_breadth(items[i]) / 2.0;
+            (x[i] - half, x[i] + half)
+        };
+        let min = (0..m).map(|i| extent(i).0).fold(f64::INFINITY, f64::min);
+        let max = (0..m)
+            .map(|i| extent(i).1)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let (min, max) = if m == 0 { (0.0, 0.0) } else { (min, max) };
+        let shift = pad.0 - min;
+        if let Some(c) = level {
+            self.clusters[c].width = (max - min) + pad.0 + pad.1;
+        }
+
+        for (i, &item) in items.iter().enumerate() {
+            let center = x[i] + shift;
+            match item {
+                Item::Node(v) => self.offset[v] = center,
+                Item::Cluster(c) => self.clusters[c].left = center - self.clusters[c].width / 2.0,
+            }
+        }
+        for &v in members {
+            if let Some(Item::Cluster(c)) = self.item_at(v, level) {
+                self.offset[v] += self.clusters[c].left;
+            }
+        }
+    }
+}
+
+/// Lay out a [`DepGraph`] in layers, with the Sugiyama method.
+///
+/// Cycles are broken by reversing the back edges of a DFS, nodes are assigned to layers, long
+/// edges are split into chains of dummy nodes, the layers are ordered with barycenter sweeps to
+/// reduce crossings, and nodes are pulled towards their neighbors to straighten edges. Subgraphs
+/// are kept contiguous and positioned as blocks, so that each gets a box that contains only its
+/// own nodes.
+///
+/// `node_size` gives the width and height of a node by ID, and `label_width` the width of a
+/// subgraph's label.
+pub(crate) fn layout<'a>(
+    graph: &'a DepGraph,
+    node_size: &dyn Fn(&str) -> (f64, f64),
+    label_width: &dyn Fn(&DepGraph) -> f64,
+) -> Layout<'a> {
+    let direction = Direction::of(graph);
+    let horizontal = direction.is_horizontal();
+
+    let mut clusters = Vec::new();
+    let mut members = IndexMap::new();
+    collect(graph, None, &mut clusters, &mut members);
+    let all_edges = graph.all_edges();
+    for edge in all_edges {
+        for id in [&edge.from, &edge.to] {
+            if !members.contains_key(id.as_str()) {
+                members.insert(id.as_str(), None);
+            }
+        }
+    }
+
+    let real = members.len();
+    let mut edges = Vec::new();
+    let mut loops = Vec::new();
+    for (i, edge) in all_edges.iter().enumerate() {
+        let a = members.get_index_of(edge.from.as_str()).unwrap();
+        let b = members.get_index_of(edge.to.as_str()).unwrap();
+        if a == b {
+            loops.push((i, a));
+        } else {
+            edges.push((i, a, b));
+        }
+    }
+
+    let pairs: Vec<(usize, usize)> = edges.iter().map(|&(_, a, b)| (a, b)).collect();
+    let back = back_edges(real, &pairs);
+    let dag: Vec<(usize, usize)> = pairs
+        .iter()
+        .zip(&back)
+        .map(|(&(a, b), &back)| if back { (b, a) } else { (a, b) })
+        .collect();
+    let layer = assign_layers(real, &dag);
+
+    let cluster_count = clusters.len();
+    let mut g = Layered {
+        clusters,
+        cluster: Vec::new(),
+        real,
+        breadth: Vec::new(),
+        depth: Vec::new(),
+        layer: Vec::new(),
+        succ: Vec::new(),
+        pred: Vec::new(),
+        layers: Vec::new(),
+        pos: Vec::new(),
+        sum: vec![0.0; cluster_count],
+        count: vec![0; cluster_count],
+        offset: Vec::new(),
+    };
+    for (v, (id, cluster)) in members.iter().enumerate() {
+        let (width, height) = node_size(id);
+        let size = if horizontal {
+            (height, width)
+        } else {
+            (width, height)
+        };
+        g.add_node(*cluster, size, layer[v]);
+    }
+
+    // Split long edges into chains of dummy nodes, one per layer
+    let mut chains = Vec::with_capacity(dag.len());
+    for &(a, b) in &dag {
+        let cluster = g.common_cluster(a, b);
+        let mut chain = vec![a];
+        for l in g.layer[a] + 1..g.layer[b] {
+            let d = g.add_node(cluster, (0.0, 0.0), l);
+            g.add_edge(*chain.last().unwrap(), d);
+            chain.push(d);
+        }
+        g.add_edge(*chain.last().unwrap(), b);
+        chain.push(b);
+        chains.push(chain);
+    }
+
+    let n = g.layer.len();
+    g.pos = vec![0.0; n];
+    g.offset = vec![0.0; n];
+    let mut level_members = vec![Vec::new(); cluster_count + 1];
+    for v in 0..n {
+        level_members[cluster_count].push(v);
+        for c in g.ancestors(g.cluster[v]) {
+            g.count[c] += 1;
+            level_members[c].push(v);
+            let l = g.layer[v];
+            let span = g.clusters[c].span.get_or_insert((l, l));
+            *span = (span.0.min(l), span.1.max(l));
+        }
+    }
+
+    g.order();
+
+    // The label goes along the top of each box, which is the start or end of the layers, or
+    // the start of each layer when they're columns.
+    let label_rank_start = if direction == Direction::TopBottom {
+        CLUSTER_LABEL
+    } else {
+        0.0
+    };
+    let label_rank_end = if direction == Direction::BottomTop {
+        CLUSTER_LABEL
+    } else {
+        0.0
+    };
+    let lead = if horizontal {
+        CLUSTER_PAD + CLUSTER_LABEL
+    } else {
+        CLUSTER_PAD
+    };
+
+    // Position the innermost clusters first, since they're rigid blocks in their parents. Pre-order
+    // indices make this a reverse iteration.
+    for c in (0..cluster_count).rev() {
+        if g.clusters[c].span.is_some() {
+            let min_width = if horizontal {
+                0.0
+            } else {
+                label_width(g.clusters[c].graph)
+            };
+            g.position(Some(c), &level_members[c], (lead, CLUSTER_PAD));
+            g.clusters[c].width = g.clusters[c].width.max(min_width + lead + CLUSTER_PAD);
+        }
+    }
+    if n > 0 {
+        g.position(None, &level_members[cluster_count], (0.0, 0.0));
+    }
+    for c in 0..cluster_count {
+        if let Some(parent) = g.clusters[c].parent {
+            g.clusters[c].left += g.clusters[parent].left;
+        }
+    }
+
+    // How many nested boxes start or end on the first or last layer of each cluster
+    let mut starting = vec![0usize; cluster_count];
+    let mut ending = vec![0usize; cluster_count];
+    for c in (0..cluster_count).rev() {
+        let Some((first, last)) = g.clusters[c].span else {
+            continue;
+        };
+        starting[c] += 1;
+        ending[c] += 1;
+        if let Some(p) = g.clusters[c].parent {
+            let (parent_first, parent_last) = g.clusters[p].span.unwrap();
+            if parent_first == first {
+                starting[p] = starting[p].max(starting[c]);
+            }
+            if parent_last == last {
+                ending[p] = ending[p].max(ending[c]);
+            }
+        }
+    }
+    let layer_count = g.layers.len();
+    let mut boxes_starting = vec![0; layer_count];
+    let mut boxes_ending = vec![0; layer_count];
+    for c in 0..cluster_count {
+        if let Some((first, last)) = g.clusters[c].span {
+            boxes_starting[first] = boxes_starting[first].max(starting[c]);
+            boxes_ending[last] = boxes_ending[last].max(ending[c]);
+        }
+    }
+
+    // Coordinates across the layers
+    let start_room = CLUSTER_PAD + label_rank_start;
+    let end_room = CLUSTER_PAD + label_rank_end;
+    let layer_depth: Vec<f64> = g
+        .layers
+        .iter()
+        .map(|layer| layer.iter().map(|&v| g.depth[v]).fold(0.0, f64::max))
+        .collect();
+    let mut centers = Vec::with_capacity(layer_count);
+    let mut rank = boxes_starting
+        .first()
+        .map_or(0.0, |&b| b as f64 * start_room);
+    for l in 0..layer_count {
+        centers.push(rank + layer_depth[l] / 2.0);
+        rank += layer_depth[l];
+        if l + 1 < layer_count {
+            rank += RANK_SEP
+                + boxes_ending[l] as f64 * end_room
+                + boxes_starting[l + 1] as f64 * start_room;
+        }
+    }
+    let rank_extent = rank + boxes_ending.last().map_or(0.0, |&b| b as f64 * end_room);
+    let order_extent = (0..n)
+        .map(|v| g.offset[v] + g.breadth[v] / 2.0)
+        .chain(
+            g.clusters
+                .iter()
+                .filter(|c| c.span.is_some())
+                .map(|c| c.left + c.width),
+        )
+        .fold(0.0, f64::max);
+
+    let point = |order: f64, rank: f64| match direction {
+        Direction::TopBottom => (order, rank),
+        Direction::BottomTop => (order, rank_extent - rank),
+        Direction::LeftRight => (rank, order),
+        Direction::RightLeft => (rank_extent - rank, order),
+    };
+    let (width, height) = if horizontal {
+        (rank_extent, order_extent)
+    } else {
+        (order_extent, rank_extent)
+    };
+
+    let nodes = members
+        .keys()
+        .enumerate()
+        .map(|(v, id)| {
+            let (x, y) = point(g.offset[v], centers[g.layer[v]]);
+            let (width, height) = if horizontal {
+                (g.depth[v], g.breadth[v])
+            } else {
+                (g.breadth[v], g.depth[v])
+            };
+            NodeLayout {
+                id,
+                x,
+                y,
+                width,
+                height,
+            }
+        })
+        .collect();
+
+    let mut routes = Vec::with_capacity(all_edges.len());
+    for ((&(i, _, _), chain), &back) in edges.iter().zip(&chains).zip(&back) {
+        let (a, b) = (chain[0], chain[chain.len() - 1]);
+        let mut points = vec![point(g.offset[a], centers[g.layer[a]] + g.depth[a] / 2.0)];
+        for &d in &chain[1..chain.len() - 1] {
+            let l = g.layer[d];
+            let half = layer_depth[l] / 2.0;
+            points.push(point(g.offset[d], centers[l] - half));
+            if half > 0.0 {
+                points.push(point(g.offset[d], centers[l] + half));
+            }
+        }
+        points.push(point(g.offset[b], centers[g.layer[b]] - g.depth[b] / 2.0));
+        if back {
+            points.reverse();
+        }
+        routes.push(EdgeLayout { edge: i, points });
+    }
+    for (i, v) in loops {
+        let side = g.offset[v] + g.breadth[v] / 2.0;
+        let center = centers[g.layer[v]];
+        let quarter = g.depth[v] / 4.0;
+        let points = vec![
+            point(side, center - quarter),
+            point(side + NODE_SEP, center),
+            point(side, center + quarter),
+        ];
+        routes.push(EdgeLayout { edge: i, points });
+    }
+    routes.sort_by_key(|route| route.edge);
+
+    let clusters = g
+        .clusters
+        .iter()
+        .enumerate()
+        .filter_map(|(c, cluster)| {
+            let (first, last) = cluster.span?;
+            let top = centers[first] - layer_depth[first] / 2.0 - starting[c] as f64 * start_room;
+            let bottom = centers[last] + layer_depth[last] / 2.0 + ending[c] as f64 * end_room;
+            let (x0, y0) = point(cluster.left, top);
+            let (x1, y1) = point(cluster.left + cluster.width, bottom);
+            Some(ClusterLayout {
+                graph: cluster.graph,
+                x: x0.min(x1),
+                y: y0.min(y1),
+                width: (x1 - x0).abs(),
+                height: (y1 - y0).abs(),
+            })
+        })
+        .collect();
+
+    Layout {
+        direction,
+        width,
+        height,
+        nodes,
+        edges: routes,
+        clusters,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexMap;
+
+    use super::*;
+    use crate::parse::{InputFormat, parse};
+    use crate::{Edge, NodeInfo};
+
+    fn edge(from: &str, to: &str) -> Edge {
+        Edge {
+            from: from.into(),
+            to: to.into(),
+            ..Default::default()
+        }
+    }
+
+    fn graph(nodes: &[&str], edges: &[(&str, &str)]) -> DepGraph {
+        DepGraph {
+            nodes: nodes
+                .iter()
+                .map(|n| (n.to_string(), NodeInfo::new(*n)))
+                .collect(),
+            edges: edges.iter().map(|(a, b)| edge(a, b)).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn lay_out(graph: &DepGraph) -> Layout<'_> {
+        layout(graph, &|id| (10.0 * id.len() as f64 + 20.0, 36.0), &|_| {
+            40.0
+        })
+    }
+
+    fn node<'a>(layout: &'a Layout, id: &str) -> &'a NodeLayout<'a> {
+        layout.nodes.iter().find(|n| n.id == id).unwrap()
+    }
+
+    /// A rectangle as (left, top, right, bottom).
+    fn bounds(n: &NodeLayout) -> (f64, f64, f64, f64) {
+        (
+            n.x - n.width / 2.0,
+            n.y - n.height / 2.0,
+            n.x + n.width / 2.0,
+            n.y + n.height / 2.0,
+        )
+    }
+
+    fn overlaps(a: (f64, f64, f64, f64), b: (f64, f64, f64, f64)) -> bool {
+        a.0 < b.2 && b.0 < a.2 && a.1 < b.3 && b.1 < a.3
+    }
+
+    /// Check that nodes don't overlap, and that each subgraph's box contains exactly its own
+    /// nodes.
+    fn assert_well_formed(layout: &Layout) {
+        for (i, a) in layout.nodes.iter().enumerate() {
+            for b in &layout.nodes[i + 1..] {
+                assert!(
+                    !overlaps(bounds(a), bounds(b)),
+                    "{} overlaps {}",
+                    a.id,
+                    b.id
+                );
+            }
+        }
+        for cluster in &layout.clusters {
+            let rect = (
+                cluster.x,
+                cluster.y,
+                cluster.x + cluster.width,
+                cluster.y + cluster.height,
+            );
+            let members = cluster.graph.all_nodes();
+            for n in &layout.nodes {
+                let (l, t, r, b) = bounds(n);
+                if members.contains_key(n.id) {
+                    assert!(
+                        rect.0 <= l && rect.1 <= t && r <= rect.2 && b <= rect.3,
+                        "{} is outside {:?}",
+                        n.id,
+                        cluster.graph.id
+                    );
+                } else {
+                    assert!(
+                        !overlaps(rect, (l, t, r, b)),
+                        "{} is inside {:?}",
+                        n.id,
+                        cluster.graph.id
+                    );
+                }
+            }
+            assert!(rect.2 <= layout.width && rect.3 <= layout.height);
+        }
+    }
+
+    #[test]
+    fn empty() {
+        let graph = DepGraph::default();
+        let layout = lay_out(&graph);
+        assert!(layout.nodes.is_empty());
+        assert_eq!((layout.width, layout.height), (0.0, 0.0));
+    }
+
+    #[test]
+    fn chain() {
+        let graph = graph(&["a", "b", "c"], &[("a", "b"), ("b", "c")]);
+        let layout = lay_out(&graph);
+        let (a, b, c) = (node(&layout, "a"), node(&layout, "b"), node(&layout, "c"));
+        assert_eq!(a.x, b.x);
+        assert_eq!(b.x, c.x);
+        assert!(a.y < b.y && b.y < c.y);
+        for e in &layout.edges {
+            assert_eq!(e.points.len(), 2);
+        }
+    }
+
+    #[test]
+    fn left_right() {
+        let mut graph = graph(&["a", "b"], &[("a", "b")]);
+        graph.attrs.insert("rankdir".into(), "LR".into());
+        let layout = lay_out(&graph);
+        assert_eq!(layout.direction, Direction::LeftRight);
+        let (a, b) = (node(&layout, "a"), node(&layout, "b"));
+        assert!(a.x < b.x);
+        assert_eq!(a.y, b.y);
+    }
+
+    #[test]
+    fn long_edges_bend_around_nodes() {
+        let graph = graph(&["a", "b", "c"], &[("a", "b"), ("b", "c"), ("a", "c")]);
+        let layout = lay_out(&graph);
+        let long = layout
+            .edges
+            .iter()
+            .find(|e| e.edge == 2)
+            .expect("a -> c is laid out");
+        assert!(long.points.len() > 2);
+        assert_well_formed(&layout);
+    }
+
+    #[test]
+    fn cycles() {
+        let graph = graph(
+            &["a", "b", "c"],
+            &[("a", "b"), ("b", "c"), ("c", "a"), ("c", "c")],
+        );
+        let layout = lay_out(&graph);
+        assert_eq!(layout.edges.len(), 4);
+        let back = &layout.edges.iter().find(|e| e.edge == 2).unwrap().points;
+        // The reversed edge still starts at its source, below its target
+        assert!(back.first().unwrap().1 > back.last().unwrap().1);
+        let (a, c) = (node(&layout, "a"), node(&layout, "c"));
+        assert!(a.y < c.y);
+        assert_well_formed(&layout);
+    }
+
+    #[test]
+    fn implicit_nodes() {
+        let graph = DepGraph {
+            edges: vec![edge("a", "b")],
+            ..Default::default()
+        };
+        let layout = lay_out(&graph);
+        assert_eq!(layout.nodes.len(), 2);
+        assert!(node(&layout, "a").y < node(&layout, "b").y);
+    }
+
+    #[test]
+    fn nested_subgraphs() {
+        let sg = |id: &str, nodes: &[&str], subgraphs: Vec<DepGraph>| DepGraph {
+            id: Some(id.into()),
+            nodes: nodes
+                .iter()
+                .map(|n| (n.to_string(), NodeInfo::new(*n)))
+                .collect::<IndexMap<_, _>>(),
+            subgraphs,
+            ..Default::default()
+        };
+        let mut graph = graph(
+            &["root", "leaf"],
+            &[
+                ("root", "a1"),
+                ("root", "b1"),
+                ("a1", "a2"),
+                ("a2", "inner1"),
+                ("inner1", "inner2"),
+                ("b1", "a2"),
+                ("b1", "leaf"),
+                ("inner2", "leaf"),
+                ("root", "c1"),
+                ("c1", "inner2"),
+            ],
+        );
+        graph.subgraphs = vec![
+            sg(
+                "A",
+                &["a1", "a2"],
+                vec![sg("inner", &["inner1", "inner2"], vec![])],
+            ),
+            sg("B", &["b1"], vec![]),
+        ];
+        let layout = lay_out(&graph);
+        assert_eq!(layout.clusters.len(), 3);
+        assert_well_formed(&layout);
+
+        for rankdir in ["LR", "BT", "RL"] {
+            graph.attrs.insert("rankdir".into(), rankdir.into());
+            assert_well_formed(&lay_out(&graph));
+        }
+    }
+
+    #[test]
+    fn plantuml_architecture() {
+        let graph = parse(
+            InputFormat::PlantUml,
+            include_str!("../../../../data/depconv/architecture.puml"),
+        )
+        .unwrap();
+        let layout = lay_out(&graph);
+        assert!(!layout.clusters.is_empty());
+        assert_well_formed(&layout);
+    }
+}
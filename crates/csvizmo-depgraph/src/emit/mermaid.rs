@@ -1,5 +1,6 @@
 use std::io::Write;
 
+use super::svg::svg_color;
 use crate::DepGraph;
 
 /// Sanitize a node ID to be a valid Mermaid identifier.
@@ -86,6 +87,31 @@ fn node_shape(info: &crate::NodeInfo, label: &str) -> String {
     format!("[\"{label}\"]")
 }
 
+/// Build the CSS properties for a `style` or `linkStyle` line from DOT color attrs.
+///
+/// Maps `color` to the stroke, `fillcolor` to the fill (only for `style=filled`, like DOT),
+/// `fontcolor` to the text color, and `penwidth` to the stroke width. Commas inside values are
+/// escaped, since Mermaid splits properties on them.
+fn css_style(attrs: &indexmap::IndexMap<String, String>) -> Option<String> {
+    let color = |c: &str| svg_color(c).replace(',', "\\,");
+    let mut props = Vec::new();
+    if let Some(c) = attrs.get("color") {
+        props.push(format!("stroke:{}", color(c)));
+    }
+    if let Some(c) = attrs.get("fillcolor")
+        && attrs.get("style").is_some_and(|s| s.contains("filled"))
+    {
+        props.push(format!("fill:{}", color(c)));
+    }
+    if let Some(c) = attrs.get("fontcolor") {
+        props.push(format!("color:{}", color(c)));
+    }
+    if let Some(w) = attrs.get("penwidth").and_then(|w| w.parse::<f64>().ok()) {
+        props.push(format!("stroke-width:{w}px"));
+    }
+    (!props.is_empty()).then(|| props.join(","))
+}
+
 /// Emit a [`DepGraph`] as a Mermaid flowchart.
 ///
 /// Preserves:
@@ -94,6 +120,7 @@ fn node_shape(info: &crate::NodeInfo, label: &str) -> String {
 /// - Node types as shape hints (lossy mapping to Mermaid shapes)
 /// - Edge labels
 /// - Subgraphs as nested `subgraph ... end` blocks
+/// - Node and edge colors and pen widths, as `style` and `linkStyle` lines
 ///
 /// Drops:
 /// - Graph-level attrs (except rankdir for direction)
@@ -108,17 +135,34 @@ pub fn emit(graph: &DepGraph, writer: &mut dyn Write) -> eyre::Result<()> {
         .unwrap_or("LR");
 
     writeln!(writer, "flowchart {direction}")?;
-    emit_body(graph, writer, 1)?;
+    // linkStyle refers to edges by their index in the whole flowchart, so they're collected
+    // while emitting and written at the end.
+    let mut links = Links::default();
+    emit_body(graph, writer, 1, &mut links)?;
+    for (index, style) in &links.styles {
+        writeln!(writer, "    linkStyle {index} {style}")?;
+    }
     Ok(())
 }
 
+#[derive(Default)]
+struct Links {
+    count: usize,
+    styles: Vec<(usize, String)>,
+}
+
 /// Emit the body of a graph or subgraph: subgraphs, nodes, edges.
-fn emit_body(graph: &DepGraph, writer: &mut dyn Write, depth: usize) -> eyre::Result<()> {
+fn emit_body(
+    graph: &DepGraph,
+    writer: &mut dyn Write,
+    depth: usize,
+    links: &mut Links,
+) -> eyre::Result<()> {
     let indent = "    ".repeat(depth);
 
     // Emit subgraphs before nodes/edges.
     for sg in &graph.subgraphs {
-        emit_subgraph(sg, writer, depth)?;
+        emit_subgraph(sg, writer, depth, links)?;
     }
 
     // Emit nodes (only if they have labels or types that affect shape).
@@ -130,17 +174,25 @@ fn emit_body(graph: &DepGraph, writer: &mut dyn Write, depth: usize) -> eyre::Re
         let escaped = escape_label(label);
         let shape = node_shape(info, &escaped);
         writeln!(writer, "{indent}{sanitized}{shape}")?;
+        if let Some(style) = css_style(&info.attrs) {
+            writeln!(writer, "{indent}style {sanitized} {style}")?;
+        }
     }
 
     // Emit edges.
     for edge in &graph.edges {
-        emit_edge(edge, writer, depth)?;
+        emit_edge(edge, writer, depth, links)?;
     }
 
     Ok(())
 }
 
-fn emit_edge(edge: &crate::Edge, writer: &mut dyn Write, depth: usize) -> eyre::Result<()> {
+fn emit_edge(
+    edge: &crate::Edge,
+    writer: &mut dyn Write,
+    depth: usize,
+    links: &mut Links,
+) -> eyre::Result<()> {
     let indent = "    ".repeat(depth);
     let from = sanitize_id(&edge.from);
     let to = sanitize_id(&edge.to);
@@ -151,11 +203,20 @@ fn emit_edge(edge: &crate::Edge, writer: &mut dyn Write, depth: usize) -> eyre::
     } else {
         writeln!(writer, "{indent}{from} --> {to}")?;
     }
+    if let Some(style) = css_style(&edge.attrs) {
+        links.styles.push((links.count, style));
+    }
+    links.count += 1;
 
     Ok(())
 }
 
-fn emit_subgraph(sg: &DepGraph, writer: &mut dyn Write, depth: usize) -> eyre::Result<()> {
+fn emit_subgraph(
+    sg: &DepGraph,
+    writer: &mut dyn Write,
+    depth: usize,
+    links: &mut Links,
+) -> eyre::Result<()> {
     let indent = "    ".repeat(depth);
 
     if let Some(id) = &sg.id {
@@ -166,7 +227,7 @@ fn emit_subgraph(sg: &DepGraph, writer: &mut dyn Write, depth: usize) -> eyre::R
         writeln!(writer, "{indent}subgraph sg{depth}")?;
     }
 
-    emit_body(sg, writer, depth + 1)?;
+    emit_body(sg, writer, depth + 1, links)?;
 
     writeln!(writer, "{indent}end")?;
     Ok(())
@@ -439,7 +500,7 @@ flowchart LR
                 node_type: None,
                 attrs: IndexMap::from([
                     ("shape".into(), "box".into()),
-                    ("color".into(), "red".into()),
+                    ("version".into(), "1.0.0".into()),
                 ]),
             },
         );
@@ -451,7 +512,67 @@ flowchart LR
         // Mermaid doesn't support arbitrary attrs in basic syntax, so they're dropped
         assert!(output.contains("a[\"Alpha\"]"));
         assert!(!output.contains("shape"));
-        assert!(!output.contains("color"));
+        assert!(!output.contains("version"));
+    }
+
+    #[test]
+    fn colors_styled() {
+        let graph = DepGraph {
+            nodes: IndexMap::from([
+                (
+                    "a".into(),
+                    NodeInfo {
+                        label: "a".into(),
+                        node_type: None,
+                        attrs: IndexMap::from([
+                            ("color".into(), "red".into()),
+                            ("penwidth".into(), "2".into()),
+                        ]),
+                    },
+                ),
+                (
+                    "b".into(),
+                    NodeInfo {
+                        label: "b".into(),
+                        node_type: None,
+                        attrs: IndexMap::from([
+                            ("style".into(), "filled".into()),
+                            ("fillcolor".into(), "gray90".into()),
+                        ]),
+                    },
+                ),
+            ]),
+            edges: vec![
+                Edge {
+                    from: "a".into(),
+                    to: "b".into(),
+                    ..Default::default()
+                },
+                Edge {
+                    from: "b".into(),
+                    to: "a".into(),
+                    attrs: IndexMap::from([
+                        ("color".into(), "#ff0000".into()),
+                        ("penwidth".into(), "2.5".into()),
+                    ]),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let output = emit_to_string(&graph);
+        assert_eq!(
+            output,
+            r#"flowchart LR
+    a["a"]
+    style a stroke:red,stroke-width:2px
+    b["b"]
+    style b fill:rgb(229\,229\,229)
+    a --> b
+    b --> a
+    linkStyle 1 stroke:#ff0000,stroke-width:2.5px
+"#
+        );
     }
 
     #[test]
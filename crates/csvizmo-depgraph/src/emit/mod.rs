@@ -1,7 +1,17 @@
+mod csv;
+mod cyclonedx;
+mod d2;
 mod depfile;
 pub(crate) mod dot;
+mod dsm;
+mod graphml;
+mod html;
+mod json;
+mod layout;
 mod mermaid;
 mod pathlist;
+mod plantuml;
+mod svg;
 mod tgf;
 mod tree;
 mod walk;
@@ -16,8 +26,21 @@ use crate::DepGraph;
 #[derive(Clone, Copy, Debug, ValueEnum)]
 pub enum OutputFormat {
     Dot,
+    #[value(name = "graphml")]
+    GraphMl,
+    Json,
+    #[value(name = "cyclonedx")]
+    CycloneDx,
     Mermaid,
+    #[value(name = "plantuml")]
+    PlantUml,
+    D2,
     Tgf,
+    Csv,
+    CsvNodes,
+    Html,
+    Svg,
+    Dsm,
     Depfile,
     Tree,
     Pathlist,
@@ -33,8 +56,15 @@ impl TryFrom<&Path> for OutputFormat {
             .ok_or_else(|| eyre::eyre!("no file extension: {}", path.display()))?;
         match ext {
             "dot" | "gv" => Ok(Self::Dot),
+            "graphml" => Ok(Self::GraphMl),
+            "json" | "jgf" => Ok(Self::Json),
             "mmd" | "mermaid" => Ok(Self::Mermaid),
+            "puml" | "plantuml" => Ok(Self::PlantUml),
+            "d2" => Ok(Self::D2),
             "tgf" => Ok(Self::Tgf),
+            "csv" => Ok(Self::Csv),
+            "html" | "htm" => Ok(Self::Html),
+            "svg" => Ok(Self::Svg),
             "d" => Ok(Self::Depfile),
             _ => eyre::bail!("unrecognized dependency graph file extension: .{ext}"),
         }
@@ -73,14 +103,24 @@ pub fn resolve_output_format(
 /// Not every format can represent all graph features. The table below
 /// summarises what each emitter preserves:
 ///
-/// | Format   | Graph attrs | Node label | Node attrs | Edge label | Edge attrs |
-/// |----------|-------------|------------|------------|------------|------------|
-/// | DOT      | yes         | yes        | yes        | yes        | yes        |
-/// | Mermaid  | direction   | yes        | shapes     | yes        | dropped    |
-/// | TGF      | dropped     | yes        | dropped    | yes        | dropped    |
-/// | Tree     | dropped     | yes        | dropped    | dropped    | dropped    |
-/// | Pathlist | dropped     | yes        | dropped    | dropped    | dropped    |
-/// | Depfile  | dropped     | dropped    | dropped    | dropped    | dropped    |
+/// | Format    | Graph attrs | Node label | Node attrs     | Edge label | Edge attrs |
+/// |-----------|-------------|------------|----------------|------------|------------|
+/// | DOT       | yes         | yes        | yes            | yes        | yes        |
+/// | GraphML   | yes         | yes        | yes            | yes        | yes        |
+/// | JSON      | yes         | yes        | yes            | yes        | yes        |
+/// | CycloneDX | dropped     | yes        | SBOM attrs     | dropped    | dropped    |
+/// | Mermaid   | direction   | yes        | shapes, styles | yes        | styles     |
+/// | PlantUML  | direction   | yes        | shapes         | yes        | style      |
+/// | D2        | direction   | yes        | shapes         | yes        | style      |
+/// | TGF       | dropped     | yes        | dropped        | yes        | dropped    |
+/// | CSV       | dropped     | dropped    | dropped        | yes        | yes        |
+/// | CSV nodes | dropped     | yes        | yes            | dropped    | dropped    |
+/// | HTML      | yes         | yes        | yes            | yes        | yes        |
+/// | SVG       | direction   | yes        | styles         | yes        | styles     |
+/// | DSM       | dropped     | yes        | dropped        | dropped    | dropped    |
+/// | Tree      | dropped     | yes        | dropped        | dropped    | dropped    |
+/// | Pathlist  | dropped     | yes        | dropped        | dropped    | dropped    |
+/// | Depfile   | dropped     | dropped    | dropped        | dropped    | dropped    |
 ///
 /// Features marked "dropped" are silently discarded. Converting from a
 /// rich format (e.g. DOT) to a lossy one (e.g. Depfile) is intentionally
@@ -89,8 +129,18 @@ pub fn resolve_output_format(
 pub fn emit(format: OutputFormat, graph: &DepGraph, writer: &mut dyn Write) -> eyre::Result<()> {
     match format {
         OutputFormat::Dot => dot::emit(graph, writer),
+        OutputFormat::GraphMl => graphml::emit(graph, writer),
+        OutputFormat::Json => json::emit(graph, writer),
+        OutputFormat::CycloneDx => cyclonedx::emit(graph, writer),
         OutputFormat::Mermaid => mermaid::emit(graph, writer),
+        OutputFormat::PlantUml => plantuml::emit(graph, writer),
+        OutputFormat::D2 => d2::emit(graph, writer),
         OutputFormat::Tgf => tgf::emit(graph, writer),
+        OutputFormat::Csv => csv::emit(graph, writer),
+        OutputFormat::CsvNodes => csv::emit_nodes(graph, writer),
+        OutputFormat::Html => html::emit(graph, writer),
+        OutputFormat::Svg => svg::emit(graph, writer),
+        OutputFormat::Dsm => dsm::emit(graph, writer),
         OutputFormat::Depfile => depfile::emit(graph, writer),
         OutputFormat::Pathlist => pathlist::emit(graph, writer),
         OutputFormat::Tree => tree::emit(graph, writer),
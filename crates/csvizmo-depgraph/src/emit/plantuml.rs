@@ -0,0 +1,377 @@
+use std::collections::HashSet;
+use std::io::Write;
+
+use crate::{DepGraph, NodeInfo};
+
+/// Sanitize a node ID to be a valid PlantUML alias.
+///
+/// PlantUML aliases support alphanumeric and underscore characters. Spaces are
+/// replaced with underscores, and other characters with `_XX` hex encoding.
+fn sanitize_id(id: &str) -> String {
+    let mut result = String::new();
+    for c in id.chars() {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            result.push(c);
+        } else if c == ' ' {
+            result.push('_');
+        } else {
+            for b in c.to_string().as_bytes() {
+                result.push_str(&format!("_{b:02x}"));
+            }
+        }
+    }
+    if result.is_empty() || result.starts_with(|c: char| c.is_ascii_digit()) {
+        result.insert(0, '_');
+    }
+    result
+}
+
+/// Escape a label for use inside a quoted PlantUML string.
+///
+/// PlantUML strings have no escape for `"`, so it's written as a Unicode
+/// escape, and newlines as PlantUML's `\n` line break.
+fn escape_label(s: &str) -> String {
+    s.replace('"', "<U+0022>").replace('\n', "\\n")
+}
+
+/// Map a DOT shape attribute to a PlantUML component diagram element.
+///
+/// Returns None if the shape doesn't have a good PlantUML equivalent.
+fn dot_shape_to_plantuml(shape: &str) -> Option<&'static str> {
+    match shape {
+        "box" => Some("node"),
+        "circle" => Some("interface"),
+        "ellipse" => Some("component"),
+        "diamond" => Some("card"),
+        "hexagon" => Some("hexagon"),
+        "note" => Some("file"),
+        "cylinder" => Some("database"),
+        "folder" => Some("folder"),
+        _ => None,
+    }
+}
+
+/// Choose the element keyword for a node, defaulting to `component`.
+///
+/// Like the Mermaid emitter, this relies on `apply_default_styles()` having
+/// mapped semantic node types to DOT `shape` attrs.
+fn node_element(info: &NodeInfo) -> &'static str {
+    info.attrs
+        .get("shape")
+        .and_then(|shape| dot_shape_to_plantuml(shape))
+        .unwrap_or("component")
+}
+
+/// Emit a [`DepGraph`] as a PlantUML component diagram.
+///
+/// Preserves:
+/// - Left-to-right layout from a `rankdir` (or `direction`) attr of `LR`
+/// - Node labels
+/// - Node types as element kinds (lossy mapping from DOT shapes)
+/// - Edge labels, and dashed or dotted edge styles as `..>` arrows
+/// - Subgraphs as nested `package` blocks, labeled by their `label` attr or ID, and aliased by
+///   their ID where it doesn't clash with another alias
+///
+/// Drops:
+/// - Other graph-level attrs
+/// - Arbitrary node and edge attrs
+pub fn emit(graph: &DepGraph, writer: &mut dyn Write) -> eyre::Result<()> {
+    writeln!(writer, "@startuml")?;
+    let direction = graph.attrs.get("rankdir").or(graph.attrs.get("direction"));
+    if direction.is_some_and(|d| d == "LR") {
+        writeln!(writer, "left to right direction")?;
+    }
+
+    // Package aliases can't reuse a node's alias, since PlantUML would merge them
+    let mut aliases: HashSet<String> = graph.all_nodes().keys().map(|id| sanitize_id(id)).collect();
+    emit_nodes(graph, writer, &mut aliases, 0)?;
+
+    // Edges go after every declaration, so that an edge to a node in a package
+    // doesn't implicitly declare the node outside of it.
+    for edge in graph.all_edges() {
+        let from = sanitize_id(&edge.from);
+        let to = sanitize_id(&edge.to);
+        let arrow = match edge.attrs.get("style").map(|s| s.as_str()) {
+            Some("dashed" | "dotted") => "..>",
+            _ => "-->",
+        };
+        match &edge.label {
+            Some(label) => {
+                let escaped = escape_label(label);
+                writeln!(writer, "{from} {arrow} {to} : {escaped}")?;
+            }
+            None => writeln!(writer, "{from} {arrow} {to}")?,
+        }
+    }
+
+    writeln!(writer, "@enduml")?;
+    Ok(())
+}
+
+/// A unique alias for a package: its sanitized ID, with a numeric suffix if that's already taken,
+/// or `sg1`, `sg2`, etc. for an anonymous subgraph.
+fn package_alias(sg: &DepGraph, aliases: &mut HashSet<String>) -> String {
+    let (base, separator, start) = match &sg.id {
+        Some(id) => {
+            let alias = sanitize_id(id);
+            if aliases.insert(alias.clone()) {
+                return alias;
+            }
+            (alias, "_", 2)
+        }
+        None => ("sg".to_string(), "", 1),
+    };
+    let mut n = start;
+    loop {
+        let alias = format!("{base}{separator}{n}");
+        if aliases.insert(alias.clone()) {
+            return alias;
+        }
+        n += 1;
+    }
+}
+
+/// Emit the node declarations of a graph or subgraph, with subgraphs as packages.
+///
+/// Every package gets an alias from `aliases`, since packages with the same name are merged.
+fn emit_nodes(
+    graph: &DepGraph,
+    writer: &mut dyn Write,
+    aliases: &mut HashSet<String>,
+    depth: usize,
+) -> eyre::Result<()> {
+    let indent = "  ".repeat(depth);
+
+    for sg in &graph.subgraphs {
+        let alias = package_alias(sg, aliases);
+        let label = match sg.attrs.get("label").or(sg.id.as_ref()) {
+            Some(label) => escape_label(label),
+            None => alias.clone(),
+        };
+        writeln!(writer, "{indent}package \"{label}\" as {alias} {{")?;
+        emit_nodes(sg, writer, aliases, depth + 1)?;
+        writeln!(writer, "{indent}}}")?;
+    }
+
+    for (id, info) in &graph.nodes {
+        let element = node_element(info);
+        let escaped = escape_label(&info.label);
+        let sanitized = sanitize_id(id);
+        writeln!(writer, "{indent}{element} \"{escaped}\" as {sanitized}")?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexMap;
+
+    use super::*;
+    use crate::Edge;
+    use crate::emit::fixtures::sample_graph;
+
+    fn emit_to_string(graph: &DepGraph) -> String {
+        let mut buf = Vec::new();
+        emit(graph, &mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn sanitize() {
+        assert_eq!(sanitize_id("foo_bar"), "foo_bar");
+        assert_eq!(sanitize_id("myapp v0.1.0"), "myapp_v0_2e1_2e0");
+        assert_eq!(sanitize_id("crate::parse"), "crate_3a_3aparse");
+        assert_eq!(sanitize_id("123abc"), "_123abc");
+        assert_eq!(sanitize_id(""), "_");
+    }
+
+    #[test]
+    fn escape_label_quotes() {
+        assert_eq!(escape_label("say \"hi\""), "say <U+0022>hi<U+0022>");
+    }
+
+    #[test]
+    fn empty_graph() {
+        let output = emit_to_string(&DepGraph::default());
+        assert_eq!(output, "@startuml\n@enduml\n");
+    }
+
+    #[test]
+    fn sample() {
+        let output = emit_to_string(&sample_graph());
+        assert_eq!(
+            output,
+            "\
+@startuml
+component \"alpha\" as a
+component \"bravo\" as b
+component \"c\" as c
+a --> b : depends
+b --> c
+a --> c
+@enduml
+"
+        );
+    }
+
+    #[test]
+    fn shapes_and_styles() {
+        let graph = DepGraph {
+            attrs: IndexMap::from([("rankdir".into(), "LR".into())]),
+            nodes: IndexMap::from([
+                (
+                    "app".into(),
+                    NodeInfo {
+                        label: "app".into(),
+                        node_type: Some("bin".into()),
+                        attrs: IndexMap::from([("shape".into(), "box".into())]),
+                    },
+                ),
+                (
+                    "db".into(),
+                    NodeInfo {
+                        label: "db".into(),
+                        node_type: None,
+                        attrs: IndexMap::from([("shape".into(), "cylinder".into())]),
+                    },
+                ),
+                (
+                    "odd".into(),
+                    NodeInfo {
+                        label: "odd".into(),
+                        node_type: None,
+                        attrs: IndexMap::from([("shape".into(), "trapezium".into())]),
+                    },
+                ),
+            ]),
+            edges: vec![Edge {
+                from: "app".into(),
+                to: "db".into(),
+                attrs: IndexMap::from([("style".into(), "dashed".into())]),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let output = emit_to_string(&graph);
+        assert_eq!(
+            output,
+            "\
+@startuml
+left to right direction
+node \"app\" as app
+database \"db\" as db
+component \"odd\" as odd
+app ..> db
+@enduml
+"
+        );
+    }
+
+    #[test]
+    fn subgraphs_as_packages() {
+        let graph = DepGraph {
+            nodes: IndexMap::from([("top".into(), NodeInfo::new("top"))]),
+            subgraphs: vec![DepGraph {
+                id: Some("cluster_backend".into()),
+                attrs: IndexMap::from([("label".into(), "Backend".into())]),
+                nodes: IndexMap::from([
+                    ("api".into(), NodeInfo::new("API Server")),
+                    ("db".into(), NodeInfo::new("Database")),
+                ]),
+                edges: vec![Edge {
+                    from: "api".into(),
+                    to: "db".into(),
+                    ..Default::default()
+                }],
+                subgraphs: vec![DepGraph {
+                    id: Some("cache".into()),
+                    nodes: IndexMap::from([("redis".into(), NodeInfo::new("redis"))]),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            edges: vec![Edge {
+                from: "top".into(),
+                to: "api".into(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let output = emit_to_string(&graph);
+        assert_eq!(
+            output,
+            "\
+@startuml
+package \"Backend\" as cluster_backend {
+  package \"cache\" as cache {
+    component \"redis\" as redis
+  }
+  component \"API Server\" as api
+  component \"Database\" as db
+}
+component \"top\" as top
+top --> api
+api --> db
+@enduml
+"
+        );
+    }
+
+    #[test]
+    fn package_aliases_unique() {
+        // Sibling packages with the same label, one of them with the same ID as a node
+        let package = |id: &str, node: &str| DepGraph {
+            id: Some(id.into()),
+            attrs: IndexMap::from([("label".into(), "lib".into())]),
+            nodes: IndexMap::from([(node.into(), NodeInfo::new(node))]),
+            ..Default::default()
+        };
+        let graph = DepGraph {
+            nodes: IndexMap::from([("core".into(), NodeInfo::new("core"))]),
+            subgraphs: vec![package("core", "x"), package("util", "y")],
+            ..Default::default()
+        };
+        let output = emit_to_string(&graph);
+        assert_eq!(
+            output,
+            "\
+@startuml
+package \"lib\" as core_2 {
+  component \"x\" as x
+}
+package \"lib\" as util {
+  component \"y\" as y
+}
+component \"core\" as core
+@enduml
+"
+        );
+    }
+
+    #[test]
+    fn anonymous_sibling_subgraphs() {
+        let anonymous = |id: &str| DepGraph {
+            nodes: IndexMap::from([(id.into(), NodeInfo::new(id))]),
+            ..Default::default()
+        };
+        let graph = DepGraph {
+            subgraphs: vec![anonymous("x"), anonymous("y")],
+            ..Default::default()
+        };
+        let output = emit_to_string(&graph);
+        assert_eq!(
+            output,
+            "\
+@startuml
+package \"sg1\" as sg1 {
+  component \"x\" as x
+}
+package \"sg2\" as sg2 {
+  component \"y\" as y
+}
+@enduml
+"
+        );
+    }
+}
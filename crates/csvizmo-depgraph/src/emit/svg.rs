@@ -0,0 +1,607 @@
+use std::io::Write;
+
+use indexmap::{IndexMap, IndexSet};
+
+use super::graphml::escape;
+use super::layout::{self, CLUSTER_LABEL, Direction};
+use crate::{DepGraph, NodeInfo};
+
+const FONT_SIZE: f64 = 14.0;
+const SMALL_FONT_SIZE: f64 = 12.0;
+/// Average character width of the default sans-serif font, since there's no font to measure.
+const CHAR_WIDTH: f64 = 0.55;
+const LINE_HEIGHT: f64 = 17.0;
+const MARGIN: f64 = 8.0;
+
+/// Format a coordinate with at most one decimal place.
+fn num(x: f64) -> String {
+    let s = format!("{x:.1}");
+    match s.strip_suffix(".0") {
+        Some("-0") => "0".to_string(),
+        Some(s) => s.to_string(),
+        None => s,
+    }
+}
+
+fn text_width(text: &str, font_size: f64) -> f64 {
+    text.chars().count() as f64 * font_size * CHAR_WIDTH
+}
+
+fn lines(label: &str) -> Vec<&str> {
+    label.split('\n').collect()
+}
+
+/// Convert a DOT color to an SVG color.
+///
+/// Handles X11 `grayNN`, which isn't a CSS color, HSV triples, and color lists, whose first
+/// color is used. Named colors are passed through, and mostly overlap with CSS.
+pub(crate) fn svg_color(color: &str) -> String {
+    let color = color.split([':', ';']).next().unwrap_or_default().trim();
+    let gray = color
+        .strip_prefix("gray")
+        .or_else(|| color.strip_prefix("grey"))
+        .and_then(|level| level.parse::<u8>().ok());
+    if let Some(level) = gray {
+        let v = (f64::from(level.min(100)) * 2.55).round();
+        return format!("rgb({v},{v},{v})");
+    }
+    let hsv: Vec<f64> = color
+        .split([' ', ','])
+        .filter(|s| !s.is_empty())
+        .map_while(|s| s.parse().ok())
+        .collect();
+    if hsv.len() == 3 && !color.starts_with('#') {
+        let (h, s, v) = (hsv[0], hsv[1], hsv[2]);
+        let l = v * (1.0 - s / 2.0);
+        let sl = if l == 0.0 || l == 1.0 {
+            0.0
+        } else {
+            (v - l) / l.min(1.0 - l)
+        };
+        return format!(
+            "hsl({},{}%,{}%)",
+            num(h * 360.0),
+            num(sl * 100.0),
+            num(l * 100.0)
+        );
+    }
+    color.to_string()
+}
+
+fn style_flags(attrs: &IndexMap<String, String>) -> IndexSet<&str> {
+    attrs
+        .get("style")
+        .map(|s| s.split(',').map(|f| f.trim()).collect())
+        .unwrap_or_default()
+}
+
+/// The `stroke` attributes of an outline or edge.
+fn stroke(attrs: &IndexMap<String, String>, flags: &IndexSet<&str>, default: &str) -> String {
+    let color = attrs
+        .get("color")
+        .map_or_else(|| default.to_string(), |c| svg_color(c));
+    let width = match attrs.get("penwidth") {
+        Some(width) => escape(width),
+        None if flags.contains("bold") => "2".to_string(),
+        None => "1".to_string(),
+    };
+    let mut stroke = format!(r#"stroke="{}" stroke-width="{width}""#, escape(&color));
+    if flags.contains("dashed") {
+        stroke.push_str(r#" stroke-dasharray="5,2""#);
+    } else if flags.contains("dotted") {
+        stroke.push_str(r#" stroke-dasharray="1,3""#);
+    }
+    stroke
+}
+
+/// The `fill` attribute of a node or subgraph, which is only filled with a `filled` style.
+fn fill(attrs: &IndexMap<String, String>, flags: &IndexSet<&str>) -> String {
+    let color = if flags.contains("filled") {
+        let color = attrs.get("fillcolor").or(attrs.get("color"));
+        color.map_or_else(|| "lightgrey".to_string(), |c| svg_color(c))
+    } else {
+        "none".to_string()
+    };
+    format!(r#"fill="{}""#, escape(&color))
+}
+
+fn shape(info: &NodeInfo) -> &str {
+    info.attrs.get("shape").map_or("ellipse", |s| s.as_str())
+}
+
+/// The size of a node, from its label and shape, with the same minimum size as GraphViz.
+fn node_size(info: &NodeInfo) -> (f64, f64) {
+    let lines = lines(&info.label);
+    let text = lines
+        .iter()
+        .map(|line| text_width(line, FONT_SIZE))
+        .fold(0.0, f64::max);
+    let mut width = text + 16.0;
+    let mut height = lines.len() as f64 * LINE_HEIGHT + 10.0;
+    match shape(info) {
+        "circle" | "doublecircle" => {
+            width = width.max(height);
+            height = width;
+        }
+        "diamond" => {
+            width *= 1.6;
+            height *= 1.6;
+        }
+        "hexagon" => width += 20.0,
+        "note" | "cylinder" | "box3d" => height += 8.0,
+        "box" | "rect" | "rectangle" | "square" | "plaintext" | "plain" | "none" => {}
+        // Everything else is drawn as an ellipse
+        _ => {
+            width *= 1.3;
+            height += 4.0;
+        }
+    }
+    (width.max(54.0), height.max(36.0))
+}
+
+/// Draw a node's outline, centered on the origin.
+fn outline(info: &NodeInfo, width: f64, height: f64, style: &str) -> String {
+    let (w, h) = (width / 2.0, height / 2.0);
+    let rect = |x: f64, y: f64, rx: f64| {
+        let rx = if rx > 0.0 {
+            format!(r#" rx="{}""#, num(rx))
+        } else {
+            String::new()
+        };
+        format!(
+            r#"<rect x="{}" y="{}" width="{}" height="{}"{rx} {style}/>"#,
+            num(x - w),
+            num(y - h),
+            num(width),
+            num(height)
+        )
+    };
+    let polygon = |points: &[(f64, f64)]| {
+        let points: Vec<String> = points
+            .iter()
+            .map(|&(x, y)| format!("{},{}", num(x), num(y)))
+            .collect();
+        format!(r#"<polygon points="{}" {style}/>"#, points.join(" "))
+    };
+    let flags = style_flags(&info.attrs);
+    match shape(info) {
+        "box" | "rect" | "rectangle" | "square" | "component" | "tab" | "folder" | "record" => {
+            rect(0.0, 0.0, if flags.contains("rounded") { 6.0 } else { 0.0 })
+        }
+        "Mrecord" => rect(0.0, 0.0, 6.0),
+        "box3d" => format!("{}{}", rect(4.0, -4.0, 0.0), rect(0.0, 0.0, 0.0)),
+        "plaintext" | "plain" | "none" => String::new(),
+        "circle" => format!(r#"<circle r="{}" {style}/>"#, num(w)),
+        "doublecircle" => format!(
+            r#"<circle r="{}" {style}/><circle r="{}" {style}/>"#,
+            num(w),
+            num(w - 4.0)
+        ),
+        "diamond" => polygon(&[(0.0, -h), (w, 0.0), (0.0, h), (-w, 0.0)]),
+        "hexagon" => {
+            let k = (w / 3.0).min(12.0);
+            polygon(&[
+                (k - w, -h),
+                (w - k, -h),
+                (w, 0.0),
+                (w - k, h),
+                (k - w, h),
+                (-w, 0.0),
+            ])
+        }
+        "note" => {
+            let f = 8.0;
+            format!(
+                r#"<path d="M{},{} H{} L{},{} V{} H{} Z M{},{} V{} H{}" {style}/>"#,
+                num(-w),
+                num(-h),
+                num(w - f),
+                num(w),
+                num(f - h),
+                num(h),
+                num(-w),
+                num(w - f),
+                num(-h),
+                num(f - h),
+                num(w)
+            )
+        }
+        "cylinder" => {
+            let r = 6.0;
+            let top = r - h;
+            format!(
+                r#"<path d="M{l},{top} A{w},{r} 0 0 1 {rt},{top} V{bottom} A{w},{r} 0 0 1 {l},{bottom} Z M{l},{top} A{w},{r} 0 0 0 {rt},{top}" {style}/>"#,
+                l = num(-w),
+                rt = num(w),
+                w = num(w),
+                r = num(r),
+                top = num(top),
+                bottom = num(h - r),
+            )
+        }
+        _ => format!(r#"<ellipse rx="{}" ry="{}" {style}/>"#, num(w), num(h)),
+    }
+}
+
+/// Write centered, possibly multi-line, text.
+fn text(x: f64, y: f64, label: &str, attrs: &str) -> String {
+    let lines = lines(label);
+    let first = y - (lines.len() as f64 - 1.0) * LINE_HEIGHT / 2.0;
+    let mut text = format!(
+        r#"<text x="{}" y="{}" text-anchor="middle" dominant-baseline="central"{attrs}>"#,
+        num(x),
+        num(first)
+    );
+    for (i, line) in lines.iter().enumerate() {
+        if i == 0 {
+            text.push_str(&escape(line));
+        } else {
+            text.push_str(&format!(
+                r#"<tspan x="{}" dy="{}">{}</tspan>"#,
+                num(x),
+                num(LINE_HEIGHT),
+                escape(line)
+            ));
+        }
+    }
+    text.push_str("</text>");
+    text
+}
+
+/// Build an SVG path through the points of an edge, curving between layers.
+fn path(points: &[(f64, f64)], direction: Direction, is_loop: bool) -> String {
+    let mut d = String::new();
+    if let [(x0, y0), ..] = points {
+        d.push_str(&format!("M{},{}", num(*x0), num(*y0)));
+    }
+    if is_loop && let [(x0, y0), (x1, y1), (x2, y2)] = points {
+        let (c1, c2) = if direction.is_horizontal() {
+            ((*x0, *y1), (*x2, *y1))
+        } else {
+            ((*x1, *y0), (*x1, *y2))
+        };
+        d.push_str(&format!(
+            " C{},{} {},{} {},{}",
+            num(c1.0),
+            num(c1.1),
+            num(c2.0),
+            num(c2.1),
+            num(*x2),
+            num(*y2)
+        ));
+        return d;
+    }
+    for pair in points.windows(2) {
+        let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+        let straight = if direction.is_horizontal() {
+            y0 == y1
+        } else {
+            x0 == x1
+        };
+        if straight {
+            d.push_str(&format!(" L{},{}", num(x1), num(y1)));
+        } else {
+            let (c1, c2) = if direction.is_horizontal() {
+                let mid = (x0 + x1) / 2.0;
+                ((mid, y0), (mid, y1))
+            } else {
+                let mid = (y0 + y1) / 2.0;
+                ((x0, mid), (x1, mid))
+            };
+            d.push_str(&format!(
+                " C{},{} {},{} {},{}",
+                num(c1.0),
+                num(c1.1),
+                num(c2.0),
+                num(c2.1),
+                num(x1),
+                num(y1)
+            ));
+        }
+    }
+    d
+}
+
+/// Emit a [`DepGraph`] as an SVG image, laid out in layers without GraphViz.
+///
+/// Nodes are drawn with their DOT `shape`, `style`, `color`, `fillcolor`, `fontcolor`, and
+/// `penwidth` attrs, as set by `apply_default_styles()` or `graphdiff annotate`. Edges use their
+/// `style`, `color`, `fontcolor`, `penwidth`, and `arrowhead=none` attrs, and subgraphs are drawn
+/// as labeled boxes. The `rankdir` graph attr sets the direction of the layers.
+///
+/// There's no font to measure text with, so labels are sized from their number of characters.
+pub fn emit(graph: &DepGraph, writer: &mut dyn Write) -> eyre::Result<()> {
+    let nodes = graph.all_nodes();
+    let edges = graph.all_edges();
+    let implicit: IndexMap<&str, NodeInfo> = edges
+        .iter()
+        .flat_map(|e| [e.from.as_str(), e.to.as_str()])
+        .filter(|id| !nodes.contains_key(*id))
+        .map(|id| (id, NodeInfo::new(id)))
+        .collect();
+    let info = |id: &str| nodes.get(id).or_else(|| implicit.get(id));
+    let cluster_label = |sg: &DepGraph| {
+        sg.attrs
+            .get("label")
+            .or(sg.id.as_ref())
+            .cloned()
+            .unwrap_or_default()
+    };
+
+    let layout = layout::layout(
+        graph,
+        &|id| info(id).map_or((54.0, 36.0), node_size),
+        &|sg| text_width(&cluster_label(sg), SMALL_FONT_SIZE),
+    );
+    let width = num(layout.width + 2.0 * MARGIN);
+    let height = num(layout.height + 2.0 * MARGIN);
+
+    writeln!(
+        writer,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="sans-serif" font-size="{FONT_SIZE}">"#
+    )?;
+    if let Some(id) = &graph.id {
+        writeln!(writer, "<title>{}</title>", escape(id))?;
+    }
+
+    // One arrowhead per edge color, since markers can't inherit the stroke of their path
+    let colors: IndexSet<String> = edges
+        .iter()
+        .map(|e| {
+            e.attrs
+                .get("color")
+                .map_or("black".to_string(), |c| svg_color(c))
+        })
+        .collect();
+    writeln!(writer, "<defs>")?;
+    for (i, color) in colors.iter().enumerate() {
+        writeln!(
+            writer,
+            r#"<marker id="arrow{i}" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="10" markerHeight="10" markerUnits="userSpaceOnUse" orient="auto"><path d="M0,0 L10,5 L0,10 z" fill="{}"/></marker>"#,
+            escape(color)
+        )?;
+    }
+    writeln!(writer, "</defs>")?;
+    writeln!(writer, r#"<rect width="100%" height="100%" fill="white"/>"#)?;
+    writeln!(writer, r#"<g transform="translate({MARGIN} {MARGIN})">"#)?;
+
+    for cluster in &layout.clusters {
+        let attrs = &cluster.graph.attrs;
+        let flags = style_flags(attrs);
+        if flags.contains("invis") {
+            continue;
+        }
+        let rx = if flags.contains("rounded") {
+            r#" rx="8""#
+        } else {
+            ""
+        };
+        writeln!(writer, r#"<g class="cluster">"#)?;
+        if let Some(id) = &cluster.graph.id {
+            writeln!(writer, "<title>{}</title>", escape(id))?;
+        }
+        writeln!(
+            writer,
+            r#"<rect x="{}" y="{}" width="{}" height="{}"{rx} {} {}/>"#,
+            num(cluster.x),
+            num(cluster.y),
+            num(cluster.width),
+            num(cluster.height),
+            fill(attrs, &flags),
+            stroke(attrs, &flags, "black"),
+        )?;
+        let label = cluster_label(cluster.graph);
+        if !label.is_empty() {
+            let fontcolor = attrs
+                .get("fontcolor")
+                .map_or("black".to_string(), |c| svg_color(c));
+            writeln!(
+                writer,
+                r#"<text x="{}" y="{}" font-size="{SMALL_FONT_SIZE}" fill="{}">{}</text>"#,
+                num(cluster.x + 8.0),
+                num(cluster.y + CLUSTER_LABEL - 2.0),
+                escape(&fontcolor),
+                escape(&label)
+            )?;
+        }
+        writeln!(writer, "</g>")?;
+    }
+
+    for route in &layout.edges {
+        let edge = &edges[route.edge];
+        let flags = style_flags(&edge.attrs);
+        if flags.contains("invis") {
+            continue;
+        }
+        let color = edge
+            .attrs
+            .get("color")
+            .map_or("black".to_string(), |c| svg_color(c));
+        let marker = if edge.attrs.get("arrowhead").is_some_and(|a| a == "none") {
+            String::new()
+        } else {
+            let i = colors.get_index_of(&color).unwrap_or_default();
+            format!(r#" marker-end="url(#arrow{i})""#)
+        };
+        let d = path(&route.points, layout.direction, edge.from == edge.to);
+        writeln!(writer, r#"<g class="edge">"#)?;
+        writeln!(
+            writer,
+            "<title>{} -&gt; {}</title>",
+            escape(&edge.from),
+            escape(&edge.to)
+        )?;
+        writeln!(
+            writer,
+            r#"<path d="{d}" fill="none" {}{marker}/>"#,
+            stroke(&edge.attrs, &flags, "black")
+        )?;
+        if let Some(label) = &edge.label {
+            let mid = route.points.len() / 2;
+            let (x, y) = if route.points.len() % 2 == 0 {
+                let (a, b) = (route.points[mid - 1], route.points[mid]);
+                ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0)
+            } else {
+                route.points[mid]
+            };
+            let fontcolor = edge
+                .attrs
+                .get("fontcolor")
+                .map_or("black".to_string(), |c| svg_color(c));
+            writeln!(
+                writer,
+                r#"<text x="{}" y="{}" font-size="{SMALL_FONT_SIZE}" fill="{}" stroke="white" stroke-width="3" paint-order="stroke">{}</text>"#,
+                num(x + 4.0),
+                num(y),
+                escape(&fontcolor),
+                escape(label)
+            )?;
+        }
+        writeln!(writer, "</g>")?;
+    }
+
+    for node in &layout.nodes {
+        let Some(info) = info(node.id) else {
+            continue;
+        };
+        let flags = style_flags(&info.attrs);
+        if flags.contains("invis") {
+            continue;
+        }
+        let style = format!(
+            "{} {}",
+            fill(&info.attrs, &flags),
+            stroke(&info.attrs, &flags, "black")
+        );
+        let fontcolor = info
+            .attrs
+            .get("fontcolor")
+            .map_or("black".to_string(), |c| svg_color(c));
+        writeln!(
+            writer,
+            r#"<g class="node" transform="translate({} {})">"#,
+            num(node.x),
+            num(node.y)
+        )?;
+        writeln!(writer, "<title>{}</title>", escape(node.id))?;
+        let outline = outline(info, node.width, node.height, &style);
+        if !outline.is_empty() {
+            writeln!(writer, "{outline}")?;
+        }
+        let fill = format!(r#" fill="{}""#, escape(&fontcolor));
+        writeln!(writer, "{}", text(0.0, 0.0, &info.label, &fill))?;
+        writeln!(writer, "</g>")?;
+    }
+
+    writeln!(writer, "</g>")?;
+    writeln!(writer, "</svg>")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexMap;
+
+    use super::*;
+    use crate::Edge;
+    use crate::emit::fixtures::sample_graph;
+
+    fn emit_to_string(graph: &DepGraph) -> String {
+        let mut buf = Vec::new();
+        emit(graph, &mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn empty_graph() {
+        let output = emit_to_string(&DepGraph::default());
+        assert_eq!(
+            output,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="16" height="16" viewBox="0 0 16 16" font-family="sans-serif" font-size="14">
+<defs>
+</defs>
+<rect width="100%" height="100%" fill="white"/>
+<g transform="translate(8 8)">
+</g>
+</svg>
+"#
+        );
+    }
+
+    #[test]
+    fn sample() {
+        let output = emit_to_string(&sample_graph());
+        assert_eq!(output.matches(r#"<g class="node""#).count(), 3);
+        assert_eq!(output.matches(r#"<g class="edge">"#).count(), 3);
+        assert!(output.contains("<title>a -&gt; b</title>"));
+        assert!(output.contains(">alpha</text>"));
+        assert!(output.contains(">depends</text>"));
+    }
+
+    #[test]
+    fn styles() {
+        let graph = DepGraph {
+            nodes: IndexMap::from([
+                (
+                    "bin".into(),
+                    NodeInfo {
+                        label: "bin".into(),
+                        node_type: None,
+                        attrs: IndexMap::from([
+                            ("shape".into(), "box".into()),
+                            ("style".into(), "filled".into()),
+                            ("fillcolor".into(), "lightblue".into()),
+                        ]),
+                    },
+                ),
+                ("lib".into(), NodeInfo::new("lib")),
+                (
+                    "hidden".into(),
+                    NodeInfo {
+                        label: "hidden".into(),
+                        node_type: None,
+                        attrs: IndexMap::from([("style".into(), "invis".into())]),
+                    },
+                ),
+            ]),
+            edges: vec![Edge {
+                from: "bin".into(),
+                to: "lib".into(),
+                attrs: IndexMap::from([
+                    ("style".into(), "dashed".into()),
+                    ("color".into(), "gray60".into()),
+                ]),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let output = emit_to_string(&graph);
+        assert!(output.contains(r#"fill="lightblue" stroke="black""#));
+        assert!(output.contains("<ellipse "));
+        assert!(output.contains(
+            r#"stroke="rgb(153,153,153)" stroke-width="1" stroke-dasharray="5,2" marker-end="url(#arrow0)""#
+        ));
+        assert!(!output.contains("<title>hidden</title>"));
+    }
+
+    #[test]
+    fn colors() {
+        assert_eq!(svg_color("gray60"), "rgb(153,153,153)");
+        assert_eq!(svg_color("red:blue"), "red");
+        assert_eq!(svg_color("#ff0000"), "#ff0000");
+        assert_eq!(svg_color("0.0 1.0 1.0"), "hsl(0,100%,50%)");
+    }
+
+    #[test]
+    fn escapes_labels() {
+        let graph = DepGraph {
+            id: Some("a & b".into()),
+            nodes: IndexMap::from([("<a>".into(), NodeInfo::new("Vec<T>\n& co"))]),
+            ..Default::default()
+        };
+        let output = emit_to_string(&graph);
+        assert!(output.contains("<title>a &amp; b</title>"));
+        assert!(output.contains("<title>&lt;a&gt;</title>"));
+        assert!(output.contains(r#">Vec&lt;T&gt;<tspan x="0" dy="17">&amp; co</tspan></text>"#));
+    }
+}
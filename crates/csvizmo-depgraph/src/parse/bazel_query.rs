@@ -0,0 +1,297 @@
+use std::collections::HashMap;
+
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::{DepGraph, Edge, NodeInfo};
+
+/// One object in the stream: a target from `--output=streamed_jsonproto`, or the whole result
+/// from `--output=jsonproto`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Message {
+    QueryResult { target: Vec<Target> },
+    Target(Box<Target>),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Target {
+    #[serde(rename = "type")]
+    target_type: String,
+    rule: Option<Rule>,
+    source_file: Option<File>,
+    generated_file: Option<File>,
+    package_group: Option<File>,
+    environment_group: Option<File>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Rule {
+    name: String,
+    rule_class: String,
+    location: Option<String>,
+    #[serde(default)]
+    attribute: Vec<Attribute>,
+    #[serde(default)]
+    rule_input: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct File {
+    name: String,
+    location: Option<String>,
+    generating_rule: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Attribute {
+    name: String,
+    #[serde(rename = "type")]
+    attr_type: String,
+    #[serde(default)]
+    explicitly_specified: bool,
+    string_value: Option<String>,
+    #[serde(default)]
+    string_list_value: Vec<String>,
+    int_value: Option<serde_json::Number>,
+    boolean_value: Option<bool>,
+    tristate_value: Option<String>,
+}
+
+impl Attribute {
+    /// The attribute value as a string, for the attribute types that map onto a node attr.
+    ///
+    /// Dependency attributes (`LABEL`, `LABEL_LIST`) become edges instead, and outputs and
+    /// dictionaries are skipped.
+    fn value(&self) -> Option<String> {
+        match self.attr_type.as_str() {
+            "STRING" | "NODEP_LABEL" => self.string_value.clone(),
+            "STRING_LIST" | "NODEP_LABEL_LIST" | "DISTRIBUTION_SET" => {
+                Some(self.string_list_value.join(","))
+            }
+            "INTEGER" => self.int_value.as_ref().map(|n| n.to_string()),
+            "BOOLEAN" => self.boolean_value.map(|b| b.to_string()),
+            "TRISTATE" => self.tristate_value.clone(),
+            _ => None,
+        }
+    }
+
+    /// The labels of a dependency attribute.
+    fn labels(&self) -> &[String] {
+        match self.attr_type.as_str() {
+            "LABEL" => self.string_value.as_slice(),
+            "LABEL_LIST" => &self.string_list_value,
+            _ => &[],
+        }
+    }
+}
+
+fn set_node(graph: &mut DepGraph, id: &str, node_type: String, attrs: IndexMap<String, String>) {
+    // Inputs may be listed before their own targets, so replace placeholders in place
+    graph.nodes.insert(
+        id.to_string(),
+        NodeInfo {
+            label: id.to_string(),
+            node_type: Some(node_type),
+            attrs,
+        },
+    );
+}
+
+fn add_edge(graph: &mut DepGraph, from: &str, to: &str, label: Option<String>) {
+    graph
+        .nodes
+        .entry(to.to_string())
+        .or_insert_with(|| NodeInfo::new(to));
+    graph.edges.push(Edge {
+        from: from.to_string(),
+        to: to.to_string(),
+        label,
+        ..Default::default()
+    });
+}
+
+fn add_target(graph: &mut DepGraph, target: Target) -> eyre::Result<()> {
+    if let Some(rule) = target.rule {
+        let mut attrs = IndexMap::new();
+        if let Some(location) = rule.location {
+            attrs.insert("location".to_string(), location);
+        }
+        // Name the attribute that each dependency comes from
+        let mut dep_attrs: HashMap<&str, &str> = HashMap::new();
+        for attribute in &rule.attribute {
+            for label in attribute.labels() {
+                dep_attrs.entry(label).or_insert(&attribute.name);
+            }
+            if attribute.explicitly_specified
+                && attribute.name != "name"
+                && let Some(value) = attribute.value()
+            {
+                attrs.insert(attribute.name.clone(), value);
+            }
+        }
+        set_node(graph, &rule.name, rule.rule_class, attrs);
+        for input in &rule.rule_input {
+            let label = dep_attrs.get(input.as_str()).map(|a| a.to_string());
+            add_edge(graph, &rule.name, input, label);
+        }
+        return Ok(());
+    }
+
+    let (file, node_type) = match target.target_type.as_str() {
+        "SOURCE_FILE" => (target.source_file, "source_file"),
+        "GENERATED_FILE" => (target.generated_file, "generated_file"),
+        "PACKAGE_GROUP" => (target.package_group, "package_group"),
+        "ENVIRONMENT_GROUP" => (target.environment_group, "environment_group"),
+        other => eyre::bail!("unsupported bazel target type: {other:?}"),
+    };
+    let Some(file) = file else {
+        eyre::bail!("bazel {} target without a body", target.target_type);
+    };
+    let mut attrs = IndexMap::new();
+    if let Some(location) = file.location {
+        attrs.insert("location".to_string(), location);
+    }
+    set_node(graph, &file.name, node_type.to_string(), attrs);
+    if let Some(rule) = file.generating_rule {
+        add_edge(graph, &file.name, &rule, None);
+    }
+    Ok(())
+}
+
+/// Parse `bazel query --output=streamed_jsonproto` (or `--output=jsonproto`) into a [`DepGraph`].
+///
+/// Each target becomes a node keyed and labeled by its Bazel label. Rules get their rule class as
+/// the `node_type` (`cc_library`, `py_binary`, ...), and files and groups get `source_file`,
+/// `generated_file`, `package_group`, or `environment_group`. The `location` of the target and
+/// every explicitly specified, non-dependency attribute (`visibility`, `tags`, `copts`, ...) are
+/// stored in attrs, with lists comma-separated.
+///
+/// Each rule input becomes an edge, labeled with the attribute it comes from (`deps`, `srcs`,
+/// implicit attributes like `:cc_toolchain`, ...) when there is one. Generated files have an edge
+/// to their generating rule, like `bazel query --output=graph`. Inputs outside the query result
+/// become nodes without a type.
+pub fn parse(input: &str) -> eyre::Result<DepGraph> {
+    let mut graph = DepGraph::default();
+    for message in serde_json::Deserializer::from_str(input).into_iter::<Message>() {
+        match message? {
+            Message::QueryResult { target } => {
+                for target in target {
+                    add_target(&mut graph, target)?;
+                }
+            }
+            Message::Target(target) => add_target(&mut graph, *target)?,
+        }
+    }
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edge_tuples(graph: &DepGraph) -> Vec<(&str, &str, Option<&str>)> {
+        graph
+            .edges
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str(), e.label.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn empty_input() {
+        let graph = parse("").unwrap();
+        assert!(graph.nodes.is_empty());
+    }
+
+    #[test]
+    fn unsupported_target_type() {
+        assert!(parse(r#"{"type": "SOMETHING_NEW"}"#).is_err());
+    }
+
+    #[test]
+    fn jsonproto() {
+        let input = r#"{"target": [
+            {"type": "SOURCE_FILE", "sourceFile": {"name": "//lib:foo.cc"}},
+            {"type": "RULE", "rule": {"name": "//lib:foo", "ruleClass": "cc_library",
+             "ruleInput": ["//lib:foo.cc"]}}
+        ]}"#;
+        let graph = parse(input).unwrap();
+        assert_eq!(
+            graph.nodes["//lib:foo"].node_type.as_deref(),
+            Some("cc_library")
+        );
+        assert_eq!(
+            edge_tuples(&graph),
+            vec![("//lib:foo", "//lib:foo.cc", None)]
+        );
+    }
+
+    #[test]
+    fn fixture() {
+        let input = include_str!("../../../../data/depconv/bazel-query.jsonl");
+        let graph = parse(input).unwrap();
+
+        let ids: Vec<&str> = graph.nodes.keys().map(|s| s.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "//app:app",
+                "//app:main.cc",
+                "//lib:foo",
+                "@bazel_tools//tools/cpp:toolchain",
+                "//lib:foo.cc",
+                "//lib:version.h",
+                "//lib:gen_version",
+                "//lib:version.txt",
+            ]
+        );
+
+        let app = &graph.nodes["//app:app"];
+        assert_eq!(app.node_type.as_deref(), Some("cc_binary"));
+        assert_eq!(app.attrs["location"], "/src/app/BUILD:1:10");
+        assert_eq!(app.attrs["visibility"], "//visibility:public");
+        assert_eq!(app.attrs["tags"], "manual,no-remote");
+        assert_eq!(app.attrs["linkstatic"], "true");
+        // Not explicitly specified
+        assert!(!app.attrs.contains_key("testonly"));
+        // Dependencies are edges, not attrs
+        assert!(!app.attrs.contains_key("deps"));
+
+        assert_eq!(
+            graph.nodes["//lib:version.h"].node_type.as_deref(),
+            Some("generated_file")
+        );
+        assert_eq!(
+            graph.nodes["//app:main.cc"].node_type.as_deref(),
+            Some("source_file")
+        );
+        // Outside the query result
+        assert!(
+            graph.nodes["@bazel_tools//tools/cpp:toolchain"]
+                .node_type
+                .is_none()
+        );
+
+        assert_eq!(
+            edge_tuples(&graph),
+            vec![
+                ("//app:app", "//app:main.cc", Some("srcs")),
+                ("//app:app", "//lib:foo", Some("deps")),
+                (
+                    "//app:app",
+                    "@bazel_tools//tools/cpp:toolchain",
+                    Some(":cc_toolchain")
+                ),
+                ("//lib:foo", "//lib:foo.cc", Some("srcs")),
+                ("//lib:foo", "//lib:version.h", Some("hdrs")),
+                ("//lib:gen_version", "//lib:version.txt", Some("srcs")),
+                ("//lib:version.h", "//lib:gen_version", None),
+            ]
+        );
+    }
+}
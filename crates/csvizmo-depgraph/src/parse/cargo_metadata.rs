@@ -120,6 +120,9 @@ pub fn parse(input: &str) -> eyre::Result<DepGraph> {
         // Get package info to extract name and version
         if let Some(pkg) = package_map.get(&node.id) {
             attrs.insert("version".to_string(), pkg.version.to_string());
+            if let Some(license) = &pkg.license {
+                attrs.insert("license".to_string(), license.clone());
+            }
         }
 
         // Store features if present
@@ -0,0 +1,263 @@
+use std::collections::{HashMap, HashSet};
+
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::{DepGraph, Edge, NodeInfo};
+
+/// A reply object from `.cmake/api/v1/reply/`. Only the objects we need are modeled; anything
+/// else (the index file, cache, toolchains, ...) is skipped.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Reply {
+    Codemodel(Codemodel),
+    Target(Target),
+    Other(serde::de::IgnoredAny),
+}
+
+#[derive(Deserialize)]
+struct Codemodel {
+    configurations: Vec<Configuration>,
+}
+
+#[derive(Deserialize)]
+struct Configuration {
+    targets: Vec<TargetRef>,
+}
+
+#[derive(Deserialize)]
+struct TargetRef {
+    id: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Target {
+    id: String,
+    name: String,
+    #[serde(rename = "type")]
+    target_type: String,
+    #[serde(default)]
+    dependencies: Vec<TargetDep>,
+    #[serde(default)]
+    link_libraries: Vec<LinkItem>,
+    #[serde(default)]
+    interface_link_libraries: Vec<LinkItem>,
+}
+
+#[derive(Deserialize)]
+struct TargetDep {
+    id: String,
+}
+
+/// A link item is either a target (`id`) or a raw command line `fragment`.
+#[derive(Deserialize)]
+struct LinkItem {
+    id: Option<String>,
+}
+
+/// Fallback node name for a target ID when its target object wasn't part of the input.
+///
+/// Target IDs look like `name::@hash`.
+fn name_from_id(id: &str) -> &str {
+    id.split_once("::@").map_or(id, |(name, _)| name)
+}
+
+/// Parse CMake File API `codemodel-v2` reply objects into a [`DepGraph`].
+///
+/// The input is a stream of concatenated JSON objects, e.g.
+/// `cat .cmake/api/v1/reply/*.json`. Each target object becomes a node named after the target,
+/// with the target type (`EXECUTABLE`, `STATIC_LIBRARY`, `INTERFACE_LIBRARY`, ...) as its
+/// `node_type`. Each entry in a target's `dependencies` becomes an edge.
+///
+/// Codemodel 2.9 and newer also report `linkLibraries` and `interfaceLinkLibraries`. Those are
+/// used to label link edges `PUBLIC` (both), `PRIVATE` (link only), or `INTERFACE` (interface
+/// only). With older CMake versions, edges are unlabeled. Link items that aren't targets (raw
+/// `-l` flags, library paths) are skipped.
+pub fn parse(input: &str) -> eyre::Result<DepGraph> {
+    let mut names: HashMap<String, String> = HashMap::new();
+    let mut seen = HashSet::new();
+    let mut targets = Vec::new();
+    let mut nodes = IndexMap::new();
+
+    for reply in serde_json::Deserializer::from_str(input).into_iter::<Reply>() {
+        match reply? {
+            Reply::Codemodel(codemodel) => {
+                for target in codemodel.configurations.into_iter().flat_map(|c| c.targets) {
+                    nodes
+                        .entry(target.name.clone())
+                        .or_insert_with(|| NodeInfo::new(&target.name));
+                    names.insert(target.id, target.name);
+                }
+            }
+            // Multi-config generators write one target object per configuration
+            Reply::Target(target) if seen.insert(target.id.clone()) => {
+                names.insert(target.id.clone(), target.name.clone());
+                targets.push(target);
+            }
+            Reply::Target(_) | Reply::Other(_) => {}
+        }
+    }
+
+    let name_of = |id: &str| -> String {
+        names
+            .get(id)
+            .cloned()
+            .unwrap_or_else(|| name_from_id(id).to_string())
+    };
+
+    let mut edges = Vec::new();
+    for target in targets {
+        nodes
+            .entry(target.name.clone())
+            .or_insert_with(|| NodeInfo::new(&target.name))
+            .node_type = Some(target.target_type);
+
+        let link: Vec<&str> = target
+            .link_libraries
+            .iter()
+            .filter_map(|l| l.id.as_deref())
+            .collect();
+        let interface: Vec<&str> = target
+            .interface_link_libraries
+            .iter()
+            .filter_map(|l| l.id.as_deref())
+            .collect();
+
+        // Dependencies first, in CMake's order, then any link-only targets
+        let mut dep_ids: Vec<&str> = target.dependencies.iter().map(|d| d.id.as_str()).collect();
+        for id in link.iter().chain(&interface) {
+            if !dep_ids.contains(id) {
+                dep_ids.push(id);
+            }
+        }
+
+        for id in dep_ids {
+            let label = match (link.contains(&id), interface.contains(&id)) {
+                (true, true) => Some("PUBLIC"),
+                (true, false) => Some("PRIVATE"),
+                (false, true) => Some("INTERFACE"),
+                (false, false) => None,
+            };
+            let to = name_of(id);
+            nodes
+                .entry(to.clone())
+                .or_insert_with(|| NodeInfo::new(&to));
+            edges.push(Edge {
+                from: target.name.clone(),
+                to,
+                label: label.map(String::from),
+                ..Default::default()
+            });
+        }
+    }
+
+    Ok(DepGraph {
+        nodes,
+        edges,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edge_tuples(graph: &DepGraph) -> Vec<(&str, &str, Option<&str>)> {
+        graph
+            .edges
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str(), e.label.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn empty_input() {
+        let graph = parse("").unwrap();
+        assert!(graph.nodes.is_empty());
+        assert!(graph.edges.is_empty());
+    }
+
+    #[test]
+    fn invalid_json() {
+        assert!(parse("{\"name\" : ").is_err());
+    }
+
+    #[test]
+    fn fixture() {
+        let input = include_str!("../../../../data/depconv/cmake-file-api.json");
+        let graph = parse(input).unwrap();
+
+        let nodes: Vec<(&str, Option<&str>)> = graph
+            .nodes
+            .iter()
+            .map(|(id, info)| (id.as_str(), info.node_type.as_deref()))
+            .collect();
+        assert_eq!(
+            nodes,
+            vec![
+                ("app", Some("EXECUTABLE")),
+                ("core", Some("STATIC_LIBRARY")),
+                ("headers", Some("INTERFACE_LIBRARY")),
+                ("docs", Some("UTILITY")),
+            ]
+        );
+        assert_eq!(
+            edge_tuples(&graph),
+            vec![
+                ("app", "core", Some("PRIVATE")),
+                ("app", "headers", None),
+                ("app", "docs", None),
+                ("core", "headers", Some("PUBLIC")),
+            ]
+        );
+    }
+
+    #[test]
+    fn interface_only_link() {
+        let input = r#"
+{"id": "a::@1", "name": "a", "type": "INTERFACE_LIBRARY", "interfaceLinkLibraries": [{"id": "b::@1"}]}
+{"id": "b::@1", "name": "b", "type": "INTERFACE_LIBRARY"}
+"#;
+        let graph = parse(input).unwrap();
+        assert_eq!(edge_tuples(&graph), vec![("a", "b", Some("INTERFACE"))]);
+    }
+
+    #[test]
+    fn target_without_object_named_from_id() {
+        let input = r#"{"id": "a::@1", "name": "a", "type": "EXECUTABLE", "dependencies": [{"id": "missing::@2"}]}"#;
+        let graph = parse(input).unwrap();
+        assert!(graph.nodes.contains_key("missing"));
+        assert_eq!(edge_tuples(&graph), vec![("a", "missing", None)]);
+    }
+
+    #[test]
+    fn skips_index_and_other_objects() {
+        let input = r#"
+{"cmake": {"version": {"string": "3.28.3"}}, "objects": [], "reply": {}}
+{"kind": "cache", "entries": [], "version": {"major": 2, "minor": 0}}
+{"id": "a::@1", "name": "a", "type": "EXECUTABLE"}
+"#;
+        let graph = parse(input).unwrap();
+        assert_eq!(graph.nodes.len(), 1);
+        assert_eq!(graph.nodes["a"].node_type.as_deref(), Some("EXECUTABLE"));
+    }
+
+    #[test]
+    fn multiple_configurations_dedup_nodes() {
+        let input = r#"{"kind": "codemodel", "configurations": [
+            {"name": "Debug", "targets": [{"id": "a::@1", "name": "a"}]},
+            {"name": "Release", "targets": [{"id": "a::@1", "name": "a"}]}
+        ]}"#;
+        let graph = parse(input).unwrap();
+        assert_eq!(graph.nodes.len(), 1);
+    }
+
+    #[test]
+    fn multiple_configurations_dedup_edges() {
+        let target = r#"{"id": "a::@1", "name": "a", "type": "EXECUTABLE", "dependencies": [{"id": "b::@1"}]}"#;
+        let graph = parse(&format!("{target}\n{target}\n")).unwrap();
+        assert_eq!(edge_tuples(&graph), vec![("a", "b", None)]);
+    }
+}
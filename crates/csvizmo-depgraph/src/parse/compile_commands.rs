@@ -0,0 +1,384 @@
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::{DepGraph, Edge, NodeInfo};
+
+#[derive(Deserialize)]
+struct Entry {
+    directory: PathBuf,
+    file: PathBuf,
+    #[serde(default)]
+    arguments: Vec<String>,
+    command: Option<String>,
+    output: Option<PathBuf>,
+}
+
+impl Entry {
+    fn arguments(&self) -> Vec<String> {
+        match &self.command {
+            Some(command) if self.arguments.is_empty() => split_command(command),
+            _ => self.arguments.clone(),
+        }
+    }
+}
+
+/// Split a shell command into words, handling quotes and backslash escapes.
+fn split_command(command: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote = None;
+    let mut chars = command.chars();
+    while let Some(c) = chars.next() {
+        match (c, quote) {
+            ('\\', Some('\'')) => word.push(c),
+            ('\\', _) => {
+                if let Some(escaped) = chars.next() {
+                    word.push(escaped);
+                }
+                in_word = true;
+            }
+            (q @ ('\'' | '"'), None) => {
+                quote = Some(q);
+                in_word = true;
+            }
+            (c, Some(q)) if c == q => quote = None,
+            (c, None) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (c, _) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    words
+}
+
+/// Find the depfile that a compile command writes, from `-MF`, `-Wp,-MD,`, or the `-MD` default
+/// of the object file with a `.d` extension.
+fn depfile_path(entry: &Entry, args: &[String]) -> Option<PathBuf> {
+    let mut output = entry.output.clone();
+    let mut writes_depfile = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-MF" => return args.next().map(PathBuf::from),
+            "-o" => output = args.next().map(PathBuf::from),
+            "-MD" | "-MMD" => writes_depfile = true,
+            _ => {
+                if let Some(path) = arg.strip_prefix("-MF") {
+                    return Some(PathBuf::from(path));
+                }
+                if let Some(path) = arg
+                    .strip_prefix("-Wp,-MD,")
+                    .or_else(|| arg.strip_prefix("-Wp,-MMD,"))
+                {
+                    return Some(PathBuf::from(path));
+                }
+            }
+        }
+    }
+    writes_depfile
+        .then(|| output.map(|o| o.with_extension("d")))
+        .flatten()
+}
+
+/// The object file a compile command writes.
+fn object_path(entry: &Entry, args: &[String]) -> Option<PathBuf> {
+    if let Some(output) = &entry.output {
+        return Some(output.clone());
+    }
+    let index = args.iter().position(|a| a == "-o")?;
+    args.get(index + 1).map(PathBuf::from)
+}
+
+/// Lexically normalize a path, removing `.` components and resolving `..` where possible.
+///
+/// Doesn't touch the filesystem, so symlinks aren't resolved.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
+                }
+                Some(Component::RootDir) => {}
+                _ => normalized.push(".."),
+            },
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+fn node_type(path: &str) -> &'static str {
+    let ext = Path::new(path).extension().and_then(|e| e.to_str());
+    match ext {
+        Some("c" | "cc" | "cpp" | "cxx" | "c++" | "C" | "m" | "mm" | "s" | "S" | "cu") => "source",
+        // Everything else a compiler reads is included, including extensionless C++ headers
+        _ => "header",
+    }
+}
+
+/// Accumulates depfile rules into one graph, deduplicating edges across translation units.
+#[derive(Default)]
+struct IncludeGraph {
+    graph: DepGraph,
+    objects: HashSet<String>,
+    edges: HashSet<(String, String)>,
+}
+
+impl IncludeGraph {
+    fn add_node(&mut self, id: &str) {
+        self.graph
+            .nodes
+            .entry(id.to_string())
+            .or_insert_with(|| NodeInfo::new(id));
+    }
+
+    fn add_edge(&mut self, object: String, dep: String) {
+        self.add_node(&object);
+        self.add_node(&dep);
+        self.objects.insert(object.clone());
+        if self.edges.insert((object.clone(), dep.clone())) {
+            self.graph.edges.push(Edge {
+                from: object,
+                to: dep,
+                ..Default::default()
+            });
+        }
+    }
+
+    /// Add the rules of a depfile, resolving relative paths against `directory` if given.
+    fn add_depfile(&mut self, input: &str, directory: Option<&Path>) -> eyre::Result<()> {
+        let resolve = |path: &str| {
+            let path = match directory {
+                Some(directory) => directory.join(path),
+                None => PathBuf::from(path),
+            };
+            normalize(&path).to_string_lossy().into_owned()
+        };
+        let depfile = super::depfile::parse(input)?;
+        for id in depfile.nodes.keys() {
+            self.add_node(&resolve(id));
+        }
+        for edge in depfile.edges {
+            self.add_edge(resolve(&edge.from), resolve(&edge.to));
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> DepGraph {
+        for (id, info) in &mut self.graph.nodes {
+            let node_type = if self.objects.contains(id) {
+                "object"
+            } else {
+                node_type(id)
+            };
+            info.node_type = Some(node_type.to_string());
+        }
+        self.graph
+    }
+}
+
+/// Parse a `compile_commands.json` compilation database, or concatenated depfiles, into a merged
+/// header include graph.
+///
+/// For a compilation database, the depfile that each compile command writes (`-MF`, `-MD`, ...)
+/// is read from disk, relative to the command's `directory`, and paths are made absolute. Commands
+/// that haven't written their depfile yet, or don't write one, still get an edge from the object
+/// to the source file. Any other input is read as the concatenation of several `.d` files, with
+/// relative paths left relative; this is what [`super::read_input`] produces for a directory.
+///
+/// Each object is an edge source, with an edge to every source file and header it was compiled
+/// from. Edges repeated across depfiles are only added once. Nodes are keyed and labeled by their
+/// lexically normalized path, and get an `object`, `source`, or `header` node type; rule targets
+/// are objects, and sources are recognized by their extension.
+pub fn parse(input: &str) -> eyre::Result<DepGraph> {
+    let mut graph = IncludeGraph::default();
+    if !input.trim_start().starts_with('[') {
+        graph.add_depfile(input, None)?;
+        return Ok(graph.finish());
+    }
+
+    let entries: Vec<Entry> = serde_json::from_str(input)?;
+    for entry in &entries {
+        let args = entry.arguments();
+        let directory = entry.directory.as_path();
+        if let Some(object) = object_path(entry, &args) {
+            let object = normalize(&directory.join(object));
+            let file = normalize(&directory.join(&entry.file));
+            graph.add_edge(
+                object.to_string_lossy().into_owned(),
+                file.to_string_lossy().into_owned(),
+            );
+        }
+        let Some(depfile) = depfile_path(entry, &args) else {
+            continue;
+        };
+        let depfile = directory.join(depfile);
+        match std::fs::read_to_string(&depfile) {
+            Ok(text) => graph.add_depfile(&text, Some(directory))?,
+            Err(e) => tracing::warn!("Skipping depfile {}: {e}", depfile.display()),
+        }
+    }
+    Ok(graph.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node_types(graph: &DepGraph) -> Vec<(&str, &str)> {
+        graph
+            .nodes
+            .iter()
+            .map(|(id, info)| (id.as_str(), info.node_type.as_deref().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn split_commands() {
+        assert_eq!(
+            split_command(r#"/usr/bin/cc -DNAME="a b" -I'inc dir' -c foo\ bar.c"#),
+            vec!["/usr/bin/cc", "-DNAME=a b", "-Iinc dir", "-c", "foo bar.c"]
+        );
+        assert!(split_command("  ").is_empty());
+    }
+
+    #[test]
+    fn normalize_paths() {
+        assert_eq!(
+            normalize(Path::new("/build/./sub/../../src/foo.h")),
+            Path::new("/src/foo.h")
+        );
+        assert_eq!(
+            normalize(Path::new("../src/./foo.h")),
+            Path::new("../src/foo.h")
+        );
+        assert_eq!(normalize(Path::new("/../foo.h")), Path::new("/foo.h"));
+    }
+
+    #[test]
+    fn depfile_paths() {
+        let entry = |command: &str| Entry {
+            directory: PathBuf::from("/build"),
+            file: PathBuf::from("/src/foo.c"),
+            arguments: Vec::new(),
+            command: Some(command.to_string()),
+            output: None,
+        };
+        let path = |command: &str| {
+            let entry = entry(command);
+            depfile_path(&entry, &entry.arguments())
+        };
+        assert_eq!(
+            path("cc -MD -MT foo.o -MF CMakeFiles/foo.dir/foo.c.o.d -o foo.o -c foo.c"),
+            Some(PathBuf::from("CMakeFiles/foo.dir/foo.c.o.d"))
+        );
+        assert_eq!(
+            path("cc -Wp,-MMD,.foo.o.d -c foo.c"),
+            Some(PathBuf::from(".foo.o.d"))
+        );
+        assert_eq!(
+            path("cc -MMD -o obj/foo.o -c foo.c"),
+            Some(PathBuf::from("obj/foo.d"))
+        );
+        assert_eq!(path("cc -o foo.o -c foo.c"), None);
+    }
+
+    #[test]
+    fn concatenated_depfiles() {
+        let input = "a.o: ../src/a.c ../src/./common.h /usr/include/c++/12/vector\n\
+                     b.o: ../src/b.cpp ../src/common.h\n";
+        let graph = parse(input).unwrap();
+        assert_eq!(
+            node_types(&graph),
+            vec![
+                ("a.o", "object"),
+                ("../src/a.c", "source"),
+                ("../src/common.h", "header"),
+                ("/usr/include/c++/12/vector", "header"),
+                ("b.o", "object"),
+                ("../src/b.cpp", "source"),
+            ]
+        );
+        assert_eq!(graph.edges.len(), 5);
+    }
+
+    #[test]
+    fn compilation_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let build = dir.path().join("build");
+        std::fs::create_dir_all(build.join("obj")).unwrap();
+        std::fs::write(
+            build.join("obj/main.o.d"),
+            "obj/main.o: ../src/main.c ../src/util.h \\\n  ../include/config.h\n",
+        )
+        .unwrap();
+        std::fs::write(
+            build.join("obj/util.o.d"),
+            "obj/util.o: ../src/util.c ../src/util.h ../include/config.h\n",
+        )
+        .unwrap();
+
+        let build_dir = build.to_string_lossy();
+        let input = serde_json::json!([
+            {
+                "directory": build_dir,
+                "command": "cc -MD -MT obj/main.o -MF obj/main.o.d -o obj/main.o -c ../src/main.c",
+                "file": "../src/main.c",
+                "output": "obj/main.o"
+            },
+            {
+                "directory": build_dir,
+                "arguments": ["cc", "-MMD", "-MF", "obj/util.o.d", "-o", "obj/util.o", "-c", "../src/util.c"],
+                "file": "../src/util.c"
+            },
+            {
+                "directory": build_dir,
+                "arguments": ["cc", "-MD", "-MF", "obj/new.o.d", "-o", "obj/new.o", "-c", "../src/new.c"],
+                "file": "../src/new.c"
+            }
+        ])
+        .to_string();
+        let graph = parse(&input).unwrap();
+
+        let root = dir.path().to_string_lossy();
+        let ids: Vec<String> = graph
+            .nodes
+            .keys()
+            .map(|id| id.replace(root.as_ref(), ""))
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                "/build/obj/main.o",
+                "/src/main.c",
+                "/src/util.h",
+                "/include/config.h",
+                "/build/obj/util.o",
+                "/src/util.c",
+                // The depfile doesn't exist yet
+                "/build/obj/new.o",
+                "/src/new.c",
+            ]
+        );
+        let util_h = format!("{root}/src/util.h");
+        assert_eq!(graph.nodes[&util_h].node_type.as_deref(), Some("header"));
+        let ancestors = graph.edges.iter().filter(|e| e.to == util_h).count();
+        assert_eq!(ancestors, 2);
+        assert_eq!(graph.edges.len(), 7);
+    }
+}
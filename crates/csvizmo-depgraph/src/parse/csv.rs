@@ -0,0 +1,192 @@
+use csvizmo_csv::column_index;
+use indexmap::IndexMap;
+
+use crate::{DepGraph, Edge, NodeInfo};
+
+/// Split the input into tables separated by blank lines.
+fn split_tables(input: &str) -> Vec<String> {
+    let mut tables = Vec::new();
+    let mut table = String::new();
+    for line in input.lines() {
+        if line.trim().is_empty() {
+            if !table.is_empty() {
+                tables.push(std::mem::take(&mut table));
+            }
+            continue;
+        }
+        table.push_str(line);
+        table.push('\n');
+    }
+    if !table.is_empty() {
+        tables.push(table);
+    }
+    tables
+}
+
+/// The column indices of a table's known columns, and the names of its other (attr) columns.
+struct Columns {
+    known: Vec<Option<usize>>,
+    attrs: Vec<(usize, String)>,
+}
+
+fn columns(reader: &mut csv::Reader<&[u8]>, names: &[&str]) -> eyre::Result<Columns> {
+    let known: Vec<Option<usize>> = names
+        .iter()
+        .map(|name| column_index(reader, name).ok())
+        .collect();
+    let attrs = reader
+        .headers()?
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !known.contains(&Some(*i)))
+        .map(|(i, name)| (i, name.to_string()))
+        .collect();
+    Ok(Columns { known, attrs })
+}
+
+/// The non-empty attr fields of a record.
+fn record_attrs(record: &csv::StringRecord, columns: &Columns) -> IndexMap<String, String> {
+    columns
+        .attrs
+        .iter()
+        .filter_map(|(i, name)| {
+            let value = record.get(*i).filter(|v| !v.is_empty())?;
+            Some((name.clone(), value.to_string()))
+        })
+        .collect()
+}
+
+fn field(record: &csv::StringRecord, index: Option<usize>) -> Option<&str> {
+    index.and_then(|i| record.get(i)).filter(|v| !v.is_empty())
+}
+
+fn parse_nodes(reader: &mut csv::Reader<&[u8]>, graph: &mut DepGraph) -> eyre::Result<()> {
+    let columns = columns(reader, &["id", "label", "type"])?;
+    let [id, label, node_type] = columns.known[..] else {
+        unreachable!("three known columns");
+    };
+    for record in reader.records() {
+        let record = record?;
+        let Some(id) = field(&record, id) else {
+            eyre::bail!("node record missing an id: {record:?}");
+        };
+        let info = NodeInfo {
+            label: field(&record, label).unwrap_or(id).to_string(),
+            node_type: field(&record, node_type).map(String::from),
+            attrs: record_attrs(&record, &columns),
+        };
+        graph.nodes.insert(id.to_string(), info);
+    }
+    Ok(())
+}
+
+fn parse_edges(reader: &mut csv::Reader<&[u8]>, edges: &mut Vec<Edge>) -> eyre::Result<()> {
+    let columns = columns(reader, &["from", "to", "label"])?;
+    let [from, to, label] = columns.known[..] else {
+        unreachable!("three known columns");
+    };
+    for record in reader.records() {
+        let record = record?;
+        let (Some(from), Some(to)) = (field(&record, from), field(&record, to)) else {
+            eyre::bail!("edge record missing from or to: {record:?}");
+        };
+        edges.push(Edge {
+            from: from.to_string(),
+            to: to.to_string(),
+            label: field(&record, label).map(String::from),
+            attrs: record_attrs(&record, &columns),
+        });
+    }
+    Ok(())
+}
+
+/// Parse a CSV edge list, node table, or both, into a [`DepGraph`].
+///
+/// Tables are separated by blank lines, and told apart by their header: an edge list has `from`
+/// and `to` columns and an optional `label` column, and a node table has an `id` column and
+/// optional `label` and `type` columns. Every other column is an attr, and empty fields are
+/// skipped. Nodes that only appear in the edge list are added with their ID as their label.
+pub fn parse(input: &str) -> eyre::Result<DepGraph> {
+    let mut graph = DepGraph::default();
+    let mut edges = Vec::new();
+
+    for table in split_tables(input) {
+        let mut reader = csv::Reader::from_reader(table.as_bytes());
+        let headers = reader.headers()?;
+        let has = |name: &str| headers.iter().any(|h| h == name);
+        if has("from") && has("to") {
+            parse_edges(&mut reader, &mut edges)?;
+        } else if has("id") {
+            parse_nodes(&mut reader, &mut graph)?;
+        } else {
+            eyre::bail!("CSV table has neither from,to nor id columns: {headers:?}");
+        }
+    }
+
+    for edge in &edges {
+        for id in [&edge.from, &edge.to] {
+            if !graph.nodes.contains_key(id) {
+                graph.nodes.insert(id.clone(), NodeInfo::new(id.as_str()));
+            }
+        }
+    }
+    graph.edges = edges;
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edge_list() {
+        let input = "\
+from,to,label,kind
+a,b,uses,
+b,c,,dev
+";
+        let graph = parse(input).unwrap();
+        let ids: Vec<&str> = graph.nodes.keys().map(|s| s.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b", "c"]);
+        assert_eq!(graph.edges[0].label.as_deref(), Some("uses"));
+        assert!(graph.edges[0].attrs.is_empty());
+        assert_eq!(graph.edges[1].label, None);
+        assert_eq!(graph.edges[1].attrs["kind"], "dev");
+    }
+
+    #[test]
+    fn node_table_and_edge_list() {
+        let input = "\
+id,label,type,version
+a,Alpha,bin,1.0
+\"c,d\",,,
+b,,lib,0.2
+
+to,from
+b,a
+\"c,d\",b
+";
+        let graph = parse(input).unwrap();
+        let ids: Vec<&str> = graph.nodes.keys().map(|s| s.as_str()).collect();
+        assert_eq!(ids, vec!["a", "c,d", "b"]);
+        let a = &graph.nodes["a"];
+        assert_eq!(a.label, "Alpha");
+        assert_eq!(a.node_type.as_deref(), Some("bin"));
+        assert_eq!(a.attrs["version"], "1.0");
+        assert_eq!(graph.nodes["c,d"].label, "c,d");
+        assert!(graph.nodes["c,d"].attrs.is_empty());
+
+        let edges: Vec<(&str, &str)> = graph
+            .edges
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str()))
+            .collect();
+        assert_eq!(edges, vec![("a", "b"), ("b", "c,d")]);
+    }
+
+    #[test]
+    fn unknown_table() {
+        assert!(parse("name,value\na,1\n").is_err());
+        assert!(parse("from,to\na,\n").is_err());
+    }
+}
@@ -0,0 +1,290 @@
+use std::collections::HashSet;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{DepGraph, Edge, NodeInfo};
+
+/// A CycloneDX JSON document. Only the parts that map onto a [`DepGraph`] are modeled.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Bom {
+    pub bom_format: String,
+    pub spec_version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub serial_number: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Metadata>,
+    #[serde(default)]
+    pub components: Vec<Component>,
+    #[serde(default)]
+    pub dependencies: Vec<Dependency>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct Metadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub component: Option<Component>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct Component {
+    #[serde(rename = "type")]
+    pub component_type: String,
+    #[serde(rename = "bom-ref", default, skip_serializing_if = "Option::is_none")]
+    pub bom_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supplier: Option<Supplier>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub licenses: Vec<LicenseChoice>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub purl: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub components: Vec<Component>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct Supplier {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// Either an SPDX license expression, or a single license by SPDX ID or by name.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub(crate) enum LicenseChoice {
+    Expression { expression: String },
+    License { license: License },
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct License {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Dependency {
+    #[serde(rename = "ref")]
+    pub dep_ref: String,
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+}
+
+/// Map a CycloneDX component type onto the canonical node types used by the other parsers.
+fn node_type(component_type: &str) -> String {
+    match component_type {
+        "library" => "lib".to_string(),
+        "application" => "bin".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Join all licenses into one SPDX expression.
+fn license_expression(licenses: &[LicenseChoice]) -> Option<String> {
+    let parts: Vec<&str> = licenses
+        .iter()
+        .filter_map(|l| match l {
+            LicenseChoice::Expression { expression } => Some(expression.as_str()),
+            LicenseChoice::License { license } => license.id.as_deref().or(license.name.as_deref()),
+        })
+        .collect();
+    match parts.as_slice() {
+        [] => None,
+        [single] => Some(single.to_string()),
+        many => Some(
+            many.iter()
+                .map(|p| {
+                    if p.contains(' ') {
+                        format!("({p})")
+                    } else {
+                        p.to_string()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(" AND "),
+        ),
+    }
+}
+
+fn component_id(component: &Component) -> String {
+    component
+        .bom_ref
+        .clone()
+        .or_else(|| component.purl.clone())
+        .unwrap_or_else(|| match &component.version {
+            Some(version) => format!("{} {version}", component.name),
+            None => component.name.clone(),
+        })
+}
+
+fn add_component(graph: &mut DepGraph, component: Component) {
+    let id = component_id(&component);
+    let mut attrs = IndexMap::new();
+    let mut insert = |key: &str, value: Option<String>| {
+        if let Some(value) = value {
+            attrs.insert(key.to_string(), value);
+        }
+    };
+    insert("version", component.version);
+    insert("group", component.group);
+    insert("purl", component.purl);
+    insert("license", license_expression(&component.licenses));
+    insert("supplier", component.supplier.and_then(|s| s.name));
+    insert("scope", component.scope);
+
+    graph.nodes.entry(id).or_insert(NodeInfo {
+        label: component.name,
+        node_type: Some(node_type(&component.component_type)),
+        attrs,
+    });
+    for child in component.components {
+        add_component(graph, child);
+    }
+}
+
+/// Parse a CycloneDX JSON SBOM into a [`DepGraph`].
+///
+/// The `metadata.component` (the subject of the SBOM) and every entry in `components`,
+/// including nested components, become nodes keyed by their `bom-ref`. The label is the
+/// component name. `version`, `group`, `purl`, `scope`, the supplier name, and the licenses
+/// (joined into one SPDX expression) are stored in attrs. The component type becomes the
+/// `node_type`, with `library` and `application` mapped to `lib` and `bin`.
+///
+/// Each `dependsOn` entry in `dependencies` becomes an edge. Dependencies on refs that aren't
+/// components are skipped.
+pub fn parse(input: &str) -> eyre::Result<DepGraph> {
+    let bom: Bom = serde_json::from_str(input)?;
+    if bom.bom_format != "CycloneDX" {
+        eyre::bail!("unexpected bomFormat: {:?}", bom.bom_format);
+    }
+
+    let mut graph = DepGraph::default();
+    if let Some(component) = bom.metadata.and_then(|m| m.component) {
+        add_component(&mut graph, component);
+    }
+    for component in bom.components {
+        add_component(&mut graph, component);
+    }
+
+    let mut seen = HashSet::new();
+    for dependency in bom.dependencies {
+        if !graph.nodes.contains_key(&dependency.dep_ref) {
+            tracing::debug!(
+                "Skipping dependencies of unknown ref {:?}",
+                dependency.dep_ref
+            );
+            continue;
+        }
+        for to in dependency.depends_on {
+            if !graph.nodes.contains_key(&to) {
+                tracing::debug!("Skipping dependency on unknown ref {to:?}");
+                continue;
+            }
+            if seen.insert((dependency.dep_ref.clone(), to.clone())) {
+                graph.edges.push(Edge {
+                    from: dependency.dep_ref.clone(),
+                    to,
+                    ..Default::default()
+                });
+            }
+        }
+    }
+
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn not_cyclonedx() {
+        assert!(parse(r#"{"bomFormat": "SPDX", "specVersion": "1.5"}"#).is_err());
+        assert!(parse(r#"{"components": []}"#).is_err());
+    }
+
+    #[test]
+    fn minimal() {
+        let graph = parse(r#"{"bomFormat": "CycloneDX", "specVersion": "1.5"}"#).unwrap();
+        assert!(graph.nodes.is_empty());
+    }
+
+    #[test]
+    fn licenses() {
+        let expr = |json: &str| {
+            let licenses: Vec<LicenseChoice> = serde_json::from_str(json).unwrap();
+            license_expression(&licenses)
+        };
+        assert_eq!(expr("[]"), None);
+        assert_eq!(
+            expr(r#"[{"expression": "MIT OR Apache-2.0"}]"#),
+            Some("MIT OR Apache-2.0".to_string())
+        );
+        assert_eq!(
+            expr(r#"[{"license": {"id": "MIT"}}, {"license": {"name": "Custom License"}}]"#),
+            Some("MIT AND (Custom License)".to_string())
+        );
+    }
+
+    #[test]
+    fn fixture() {
+        let input = include_str!("../../../../data/depconv/small.cdx.json");
+        let graph = parse(input).unwrap();
+
+        let ids: Vec<&str> = graph.nodes.keys().map(|s| s.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "pkg:cargo/myapp@1.0.0",
+                "pkg:cargo/libfoo@0.2.1",
+                "pkg:cargo/libbar@0.1.0",
+                "pkg:generic/openssl@3.0.13",
+            ]
+        );
+
+        let app = &graph.nodes["pkg:cargo/myapp@1.0.0"];
+        assert_eq!(app.label, "myapp");
+        assert_eq!(app.node_type.as_deref(), Some("bin"));
+
+        let libfoo = &graph.nodes["pkg:cargo/libfoo@0.2.1"];
+        assert_eq!(libfoo.node_type.as_deref(), Some("lib"));
+        assert_eq!(libfoo.attrs["version"], "0.2.1");
+        assert_eq!(libfoo.attrs["purl"], "pkg:cargo/libfoo@0.2.1");
+        assert_eq!(libfoo.attrs["license"], "MIT OR Apache-2.0");
+        assert_eq!(libfoo.attrs["supplier"], "Foo Corp");
+
+        // Nested component
+        let openssl = &graph.nodes["pkg:generic/openssl@3.0.13"];
+        assert_eq!(openssl.attrs["license"], "Apache-2.0");
+
+        let edges: Vec<(&str, &str)> = graph
+            .edges
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str()))
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                ("pkg:cargo/myapp@1.0.0", "pkg:cargo/libfoo@0.2.1"),
+                ("pkg:cargo/myapp@1.0.0", "pkg:cargo/libbar@0.1.0"),
+                ("pkg:cargo/libfoo@0.2.1", "pkg:cargo/libbar@0.1.0"),
+                ("pkg:cargo/libbar@0.1.0", "pkg:generic/openssl@3.0.13"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,436 @@
+use std::collections::{HashMap, HashSet};
+
+use indexmap::IndexMap;
+
+use crate::{DepGraph, Edge, NodeInfo};
+
+/// Dependency fields, and the edge `kind` each one maps onto.
+const DEPENDENCY_FIELDS: [(&str, &str); 3] = [
+    ("Pre-Depends", "pre-depends"),
+    ("Depends", "normal"),
+    ("Recommends", "recommends"),
+];
+
+/// Package fields stored in node attrs, and the attr they're stored in.
+const ATTR_FIELDS: [(&str, &str); 4] = [
+    ("Version", "version"),
+    ("Architecture", "architecture"),
+    ("Section", "section"),
+    ("Source", "source"),
+];
+
+/// A control stanza: `Field: value` lines, where continuation lines start with whitespace.
+struct Stanza<'a> {
+    fields: Vec<(&'a str, String)>,
+}
+
+impl Stanza<'_> {
+    /// Field names are case-insensitive.
+    fn get(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Packages in a dpkg or opkg status file that aren't installed (`deinstall ok config-files`,
+    /// ...) are skipped. `Packages` files have no `Status` field.
+    fn is_installed(&self) -> bool {
+        self.get("Status")
+            .is_none_or(|status| status.split_whitespace().last() == Some("installed"))
+    }
+}
+
+fn parse_stanzas(input: &str) -> eyre::Result<Vec<Stanza<'_>>> {
+    let mut stanzas = Vec::new();
+    let mut fields: Vec<(&str, String)> = Vec::new();
+    for (lineno, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            if !fields.is_empty() {
+                stanzas.push(Stanza {
+                    fields: std::mem::take(&mut fields),
+                });
+            }
+            continue;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with([' ', '\t']) {
+            let Some((_, value)) = fields.last_mut() else {
+                eyre::bail!("line {}: continuation line outside a field", lineno + 1);
+            };
+            value.push('\n');
+            value.push_str(line.trim());
+            continue;
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            eyre::bail!("line {}: expected 'Field: value', got {line:?}", lineno + 1);
+        };
+        fields.push((name.trim(), value.trim().to_string()));
+    }
+    if !fields.is_empty() {
+        stanzas.push(Stanza { fields });
+    }
+    Ok(stanzas)
+}
+
+/// One alternative of a dependency, like `libc6 (>= 2.34)`.
+#[derive(Debug, PartialEq)]
+struct Relation<'a> {
+    name: &'a str,
+    constraint: Option<&'a str>,
+}
+
+/// Parse a relationship field like `libc6 (>= 2.34), default-mta | mail-transport-agent` into
+/// groups of alternatives.
+///
+/// Architecture qualifiers (`python3:any`), and the architecture restrictions (`[amd64]`) and build
+/// profiles (`<!nocheck>`) of source packages are dropped.
+fn parse_relations(field: &str) -> Vec<Vec<Relation<'_>>> {
+    field
+        .split(',')
+        .map(|group| {
+            group
+                .split('|')
+                .filter_map(|alternative| {
+                    let alternative = alternative.trim();
+                    let end = alternative
+                        .find(|c: char| c.is_whitespace() || matches!(c, '(' | '[' | '<'))
+                        .unwrap_or(alternative.len());
+                    let name = &alternative[..end];
+                    let name = name.split_once(':').map_or(name, |(name, _)| name);
+                    if name.is_empty() {
+                        return None;
+                    }
+                    let constraint = alternative[end..]
+                        .split_once('(')
+                        .and_then(|(_, rest)| rest.split_once(')'))
+                        .map(|(constraint, _)| constraint.trim());
+                    Some(Relation { name, constraint })
+                })
+                .collect::<Vec<_>>()
+        })
+        .filter(|group| !group.is_empty())
+        .collect()
+}
+
+/// Format a group of alternatives back into `a (>= 1) | b` form.
+fn format_alternatives(group: &[Relation]) -> String {
+    group
+        .iter()
+        .map(|r| match r.constraint {
+            Some(constraint) => format!("{} ({constraint})", r.name),
+            None => r.name.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+/// Parse Debian control stanzas (`/var/lib/dpkg/status`, apt `Packages` files, or opkg status
+/// files) into a [`DepGraph`].
+///
+/// Each package becomes a node keyed and labeled by its name, since that's how dependencies
+/// refer to it. The `Version`, `Architecture`, `Section`, and `Source` fields are stored in
+/// `attrs["version"]`, `attrs["architecture"]`, ... If a package appears more than once, as it
+/// can in a `Packages` file, the first stanza wins. Packages that a status file lists as not
+/// installed are skipped.
+///
+/// `Depends`, `Pre-Depends`, and `Recommends` become edges with `kind` set to `normal`,
+/// `pre-depends`, and `recommends` respectively. A version constraint like `>= 2.34` is stored
+/// in the edge's `constraint` attr. A dependency with alternatives (`a | b`) gets an edge to each
+/// alternative that's in the input (or to the first one, if none are), and the whole group is
+/// stored in the edge's `alternatives` attr.
+///
+/// Dependencies on a virtual package that's only known through other packages' `Provides` get a
+/// node with the `virtual` node type, with `kind = "provides"` edges to each provider.
+pub fn parse(input: &str) -> eyre::Result<DepGraph> {
+    let stanzas = parse_stanzas(input)?;
+    let mut graph = DepGraph::default();
+
+    let mut packages = Vec::new();
+    for stanza in &stanzas {
+        let Some(name) = stanza.get("Package") else {
+            eyre::bail!("control stanza without a Package field");
+        };
+        if !stanza.is_installed() || graph.nodes.contains_key(name) {
+            continue;
+        }
+        let mut attrs = IndexMap::new();
+        for (field, attr) in ATTR_FIELDS {
+            if let Some(value) = stanza.get(field) {
+                attrs.insert(attr.to_string(), value.to_string());
+            }
+        }
+        graph.nodes.insert(
+            name.to_string(),
+            NodeInfo {
+                label: name.to_string(),
+                node_type: None,
+                attrs,
+            },
+        );
+        packages.push((name, stanza));
+    }
+
+    // virtual package -> the packages that provide it
+    let mut providers: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (name, stanza) in &packages {
+        for group in parse_relations(stanza.get("Provides").unwrap_or_default()) {
+            for relation in group {
+                if !graph.nodes.contains_key(relation.name) {
+                    providers.entry(relation.name).or_default().push(name);
+                }
+            }
+        }
+    }
+
+    let mut virtuals = HashSet::new();
+    for (name, stanza) in &packages {
+        for (field, kind) in DEPENDENCY_FIELDS {
+            let Some(value) = stanza.get(field) else {
+                continue;
+            };
+            for group in parse_relations(value) {
+                let known = |r: &&Relation| {
+                    graph.nodes.contains_key(r.name) || providers.contains_key(r.name)
+                };
+                let mut targets: Vec<&Relation> = group.iter().filter(known).collect();
+                if targets.is_empty() {
+                    targets.push(&group[0]);
+                }
+                let alternatives = (group.len() > 1).then(|| format_alternatives(&group));
+
+                for target in targets {
+                    let mut attrs = IndexMap::from([("kind".to_string(), kind.to_string())]);
+                    if let Some(constraint) = target.constraint {
+                        attrs.insert("constraint".to_string(), constraint.to_string());
+                    }
+                    if let Some(alternatives) = &alternatives {
+                        attrs.insert("alternatives".to_string(), alternatives.clone());
+                    }
+                    if providers.contains_key(target.name) {
+                        virtuals.insert(target.name);
+                    }
+                    graph
+                        .nodes
+                        .entry(target.name.to_string())
+                        .or_insert_with(|| NodeInfo::new(target.name));
+                    graph.edges.push(Edge {
+                        from: name.to_string(),
+                        to: target.name.to_string(),
+                        label: None,
+                        attrs,
+                    });
+                }
+            }
+        }
+    }
+
+    // Add the virtual packages in the order they were first depended on
+    let virtual_ids: Vec<String> = graph
+        .nodes
+        .keys()
+        .filter(|id| virtuals.contains(id.as_str()))
+        .cloned()
+        .collect();
+    for id in virtual_ids {
+        graph.nodes[&id].node_type = Some("virtual".to_string());
+        for provider in &providers[id.as_str()] {
+            graph.edges.push(Edge {
+                from: id.clone(),
+                to: provider.to_string(),
+                label: None,
+                attrs: IndexMap::from([("kind".to_string(), "provides".to_string())]),
+            });
+        }
+    }
+
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type EdgeTuple<'a> = (&'a str, &'a str, Vec<(&'a str, &'a str)>);
+
+    fn edge_tuples(graph: &DepGraph) -> Vec<EdgeTuple<'_>> {
+        graph
+            .edges
+            .iter()
+            .map(|e| {
+                let attrs = e.attrs.iter().map(|(k, v)| (k.as_str(), v.as_str()));
+                (e.from.as_str(), e.to.as_str(), attrs.collect())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn relations() {
+        assert_eq!(
+            parse_relations("libc6 (>= 2.34), python3:any, foo [amd64] <!nocheck> | bar (<< 2)"),
+            vec![
+                vec![Relation {
+                    name: "libc6",
+                    constraint: Some(">= 2.34")
+                }],
+                vec![Relation {
+                    name: "python3",
+                    constraint: None
+                }],
+                vec![
+                    Relation {
+                        name: "foo",
+                        constraint: None
+                    },
+                    Relation {
+                        name: "bar",
+                        constraint: Some("<< 2")
+                    },
+                ],
+            ]
+        );
+        assert!(parse_relations("").is_empty());
+    }
+
+    #[test]
+    fn continuation_lines() {
+        let input = "Package: foo\nDescription: short\n long\n .\n more\nDepends: bar\n";
+        let stanzas = parse_stanzas(input).unwrap();
+        assert_eq!(stanzas.len(), 1);
+        assert_eq!(stanzas[0].get("description"), Some("short\nlong\n.\nmore"));
+        assert_eq!(stanzas[0].get("Depends"), Some("bar"));
+    }
+
+    #[test]
+    fn missing_package_field() {
+        assert!(parse("Version: 1.0\n").is_err());
+    }
+
+    #[test]
+    fn first_stanza_wins() {
+        let input = "Package: foo\nVersion: 2.0\n\nPackage: foo\nVersion: 1.0\n";
+        let graph = parse(input).unwrap();
+        assert_eq!(graph.nodes.len(), 1);
+        assert_eq!(graph.nodes["foo"].attrs["version"], "2.0");
+    }
+
+    #[test]
+    fn fixture() {
+        let input = include_str!("../../../../data/depconv/dpkg-status");
+        let graph = parse(input).unwrap();
+
+        let ids: Vec<&str> = graph.nodes.keys().map(|s| s.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "libc6",
+                "libgcc-s1",
+                "libssl3",
+                "openssh-client",
+                "openssh-server",
+                "postfix",
+                "logrotate",
+                "xauth",
+                "debconf",
+                "libwrap0",
+                "cron",
+                "mail-transport-agent",
+            ]
+        );
+        let libc = &graph.nodes["libc6"];
+        assert_eq!(libc.attrs["version"], "2.36-9+deb12u4");
+        assert_eq!(libc.attrs["architecture"], "amd64");
+        assert_eq!(libc.attrs["section"], "libs");
+        assert_eq!(graph.nodes["libssl3"].attrs["source"], "openssl");
+        assert_eq!(
+            graph.nodes["mail-transport-agent"].node_type.as_deref(),
+            Some("virtual")
+        );
+        // Not installed, and not depended on
+        assert!(!graph.nodes.contains_key("exim4-base"));
+
+        assert_eq!(
+            edge_tuples(&graph),
+            vec![
+                ("libc6", "libgcc-s1", vec![("kind", "normal")]),
+                (
+                    "libgcc-s1",
+                    "libc6",
+                    vec![("kind", "normal"), ("constraint", ">= 2.35")]
+                ),
+                (
+                    "libssl3",
+                    "libc6",
+                    vec![("kind", "normal"), ("constraint", ">= 2.34")]
+                ),
+                (
+                    "openssh-client",
+                    "libc6",
+                    vec![("kind", "normal"), ("constraint", ">= 2.36")]
+                ),
+                (
+                    "openssh-client",
+                    "libssl3",
+                    vec![("kind", "normal"), ("constraint", ">= 3.0.0")]
+                ),
+                (
+                    "openssh-client",
+                    "xauth",
+                    vec![
+                        ("kind", "recommends"),
+                        ("alternatives", "xauth | ssh-askpass")
+                    ]
+                ),
+                (
+                    "openssh-server",
+                    "debconf",
+                    vec![
+                        ("kind", "pre-depends"),
+                        ("constraint", ">= 0.5"),
+                        ("alternatives", "debconf (>= 0.5) | debconf-2.0")
+                    ]
+                ),
+                (
+                    "openssh-server",
+                    "openssh-client",
+                    vec![("kind", "normal"), ("constraint", "= 1:9.2p1-2+deb12u2")]
+                ),
+                (
+                    "openssh-server",
+                    "libwrap0",
+                    vec![("kind", "normal"), ("constraint", ">= 7.6-4~")]
+                ),
+                (
+                    "postfix",
+                    "libc6",
+                    vec![("kind", "normal"), ("constraint", ">= 2.34")]
+                ),
+                (
+                    "postfix",
+                    "libssl3",
+                    vec![("kind", "normal"), ("constraint", ">= 3.0.0")]
+                ),
+                (
+                    "logrotate",
+                    "cron",
+                    vec![
+                        ("kind", "normal"),
+                        ("alternatives", "cron | anacron | cron-daemon")
+                    ]
+                ),
+                (
+                    "logrotate",
+                    "mail-transport-agent",
+                    vec![("kind", "recommends")]
+                ),
+                (
+                    "mail-transport-agent",
+                    "postfix",
+                    vec![("kind", "provides")]
+                ),
+            ]
+        );
+    }
+}
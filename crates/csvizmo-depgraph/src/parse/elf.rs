@@ -0,0 +1,546 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use indexmap::IndexMap;
+
+use crate::{DepGraph, Edge, NodeInfo};
+
+/// The dynamic section of one binary, and its `ldd` resolutions.
+#[derive(Default)]
+struct Binary {
+    name: Option<String>,
+    soname: Option<String>,
+    needed: Vec<String>,
+    rpath: Option<String>,
+    runpath: Option<String>,
+    /// `ldd` entries: soname -> resolved path, or `None` if not found
+    resolved: Vec<(String, Option<String>)>,
+}
+
+impl Binary {
+    fn named(name: &str) -> Self {
+        Self {
+            name: Some(name.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn has_dynamic_section(&self) -> bool {
+        !self.needed.is_empty()
+            || self.soname.is_some()
+            || self.rpath.is_some()
+            || self.runpath.is_some()
+    }
+
+    fn set_tag(&mut self, tag: &str, value: &str) {
+        match tag {
+            "NEEDED" => self.needed.push(value.to_string()),
+            "SONAME" => self.soname = Some(value.to_string()),
+            "RPATH" => self.rpath = Some(value.to_string()),
+            "RUNPATH" => self.runpath = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    fn merge(&mut self, other: Binary) {
+        self.soname = self.soname.take().or(other.soname);
+        self.rpath = self.rpath.take().or(other.rpath);
+        self.runpath = self.runpath.take().or(other.runpath);
+        for needed in other.needed {
+            if !self.needed.contains(&needed) {
+                self.needed.push(needed);
+            }
+        }
+        self.resolved.extend(other.resolved);
+    }
+}
+
+/// A `readelf -d` entry like ` 0x0000000000000001 (NEEDED)  Shared library: [libc.so.6]`.
+fn readelf_entry(line: &str) -> Option<(&str, &str)> {
+    let rest = line.trim_start().strip_prefix("0x")?;
+    let (_, rest) = rest.split_once(" (")?;
+    let (tag, rest) = rest.split_once(')')?;
+    let (_, value) = rest.split_once('[')?;
+    Some((tag, value.trim_end().strip_suffix(']')?))
+}
+
+/// An `objdump -p` dynamic section entry like `  NEEDED               libc.so.6`.
+fn objdump_entry(line: &str) -> Option<(&str, &str)> {
+    if !line.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let mut parts = line.split_whitespace();
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(tag @ ("NEEDED" | "SONAME" | "RPATH" | "RUNPATH")), Some(value), None) => {
+            Some((tag, value))
+        }
+        _ => None,
+    }
+}
+
+/// The path in an `objdump -p` header like `/bin/ls:     file format elf64-x86-64`.
+fn objdump_header(line: &str) -> Option<&str> {
+    let (path, _) = line.split_once("file format ")?;
+    path.trim_end().strip_suffix(':')
+}
+
+/// An `ldd` entry: `soname => path (0x...)`, `soname => not found`, or `path (0x...)`.
+///
+/// Returns `None` for anything else, including the vDSO, which has no file.
+fn ldd_entry(line: &str) -> Option<(String, Option<String>)> {
+    let line = line.trim();
+    if let Some((soname, rest)) = line.split_once(" => ") {
+        let rest = rest.trim();
+        if rest == "not found" {
+            return Some((soname.to_string(), None));
+        }
+        let path = rest
+            .split_once(" (0x")
+            .map_or(rest, |(path, _)| path)
+            .trim();
+        if path.is_empty() {
+            return None;
+        }
+        return Some((soname.to_string(), Some(path.to_string())));
+    }
+    let (path, _) = line.split_once(" (0x")?;
+    if !path.starts_with('/') {
+        return None;
+    }
+    Some((file_name(path).to_string(), Some(path.to_string())))
+}
+
+/// An `ldd` header naming the binary, printed when `ldd` is given several files.
+///
+/// Other lines ending in `:`, like `ldd` error prefixes, aren't headers, so the line has to be a
+/// path, or be followed by an indented `ldd` entry.
+fn ldd_header<'a>(line: &'a str, next: Option<&str>) -> Option<&'a str> {
+    if line.contains(char::is_whitespace) {
+        return None;
+    }
+    let path = line.strip_suffix(':')?;
+    let entry_follows = next.is_some_and(|next| {
+        next.starts_with(char::is_whitespace) && (next.contains(" => ") || next.contains(" (0x"))
+    });
+    (path.contains('/') || entry_follows).then_some(path)
+}
+
+fn file_name(path: &str) -> &str {
+    Path::new(path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(path)
+}
+
+fn ensure_node<'g>(graph: &'g mut DepGraph, id: &str, node_type: &str) -> &'g mut NodeInfo {
+    graph
+        .nodes
+        .entry(id.to_string())
+        .or_insert_with(|| NodeInfo {
+            label: file_name(id).to_string(),
+            node_type: Some(node_type.to_string()),
+            attrs: IndexMap::new(),
+        })
+}
+
+/// Split the input into one [`Binary`] per section header.
+fn parse_binaries(input: &str) -> Vec<Binary> {
+    let mut binaries: Vec<Binary> = Vec::new();
+
+    let lines: Vec<&str> = input.lines().filter(|l| !l.trim().is_empty()).collect();
+    for (i, &line) in lines.iter().enumerate() {
+        if let Some(path) = line.strip_prefix("File: ") {
+            binaries.push(Binary::named(path.trim()));
+            continue;
+        }
+        if let Some(path) = objdump_header(line) {
+            binaries.push(Binary::named(path));
+            continue;
+        }
+        if line.starts_with("Dynamic section at offset") {
+            // Without a `File:` header, each dynamic section starts a new, unnamed binary
+            if binaries.last().is_none_or(Binary::has_dynamic_section) {
+                binaries.push(Binary::default());
+            }
+            continue;
+        }
+        if let Some(path) = ldd_header(line, lines.get(i + 1).copied()) {
+            binaries.push(Binary::named(path));
+            continue;
+        }
+
+        let entry = readelf_entry(line).or_else(|| objdump_entry(line));
+        let ldd = if entry.is_none() {
+            ldd_entry(line)
+        } else {
+            None
+        };
+        if entry.is_none() && ldd.is_none() {
+            continue;
+        }
+        if binaries.is_empty() {
+            binaries.push(Binary::default());
+        }
+        let current = binaries.last_mut().unwrap();
+        if let Some((tag, value)) = entry {
+            current.set_tag(tag, value);
+        } else if let Some(resolved) = ldd {
+            current.resolved.push(resolved);
+        }
+    }
+
+    binaries
+}
+
+/// Parse `readelf -d`, `objdump -p`, and `ldd` output into a runtime link graph.
+///
+/// The input may be any concatenation of the three, for any number of binaries. Each binary
+/// becomes a node keyed by its path, labeled with its file name. Its `NEEDED` entries become edges
+/// to soname nodes (`node_type = "soname"`), and each soname has an edge to the file it resolves
+/// to. Binaries with a `SONAME` get `node_type = "lib"`, and the rest `"bin"`. `SONAME`, `RPATH`,
+/// and `RUNPATH` are stored in the `soname`, `rpath`, and `runpath` attrs.
+///
+/// Sonames resolve to the path `ldd` found for them, or else to an input binary with a matching
+/// `SONAME`. Sonames that `ldd` reports as not found, and that no input provides, get
+/// `missing = "true"`. If `ldd` resolves a soname to different paths for different binaries, the
+/// soname gets an edge to each path, and each binary's edge to the soname gets a `path` attr with
+/// the one it loads.
+///
+/// `ldd` lists transitive dependencies too, so it's only used for a binary's edges when its
+/// dynamic section isn't part of the input.
+///
+/// `readelf` and `ldd` only print the name of each binary when given several files. A lone
+/// unnamed `readelf -d` section is named after its `SONAME`; otherwise, it's an error.
+pub fn parse(input: &str) -> eyre::Result<DepGraph> {
+    let mut binaries: IndexMap<String, Binary> = IndexMap::new();
+    for mut binary in parse_binaries(input) {
+        let name = match binary.name.take().or_else(|| binary.soname.clone()) {
+            Some(name) => name,
+            None if binary.needed.is_empty() && binary.resolved.is_empty() => continue,
+            None => eyre::bail!(
+                "can't tell which binary the dependencies belong to; pass readelf or ldd more than \
+                 one file so that they print file names, or use objdump -p"
+            ),
+        };
+        match binaries.get_mut(&name) {
+            Some(existing) => existing.merge(binary),
+            None => {
+                binaries.insert(name, binary);
+            }
+        }
+    }
+
+    let mut graph = DepGraph::default();
+    let mut provided: HashMap<&str, &str> = HashMap::new();
+    let mut resolved: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (name, binary) in &binaries {
+        let node_type = if binary.soname.is_some() {
+            "lib"
+        } else {
+            "bin"
+        };
+        let node = ensure_node(&mut graph, name, node_type);
+        node.node_type = Some(node_type.to_string());
+        for (key, value) in [
+            ("soname", &binary.soname),
+            ("rpath", &binary.rpath),
+            ("runpath", &binary.runpath),
+        ] {
+            if let Some(value) = value {
+                node.attrs.insert(key.to_string(), value.clone());
+            }
+        }
+        if let Some(soname) = &binary.soname {
+            provided.entry(soname).or_insert(name);
+        }
+        for (soname, path) in &binary.resolved {
+            let paths = resolved.entry(soname).or_default();
+            if let Some(path) = path.as_deref()
+                && !paths.contains(&path)
+            {
+                paths.push(path);
+            }
+        }
+    }
+
+    let mut sonames: Vec<&str> = Vec::new();
+    let mut seen = HashSet::new();
+    for (name, binary) in &binaries {
+        let needed: Vec<&str> = if binary.needed.is_empty() {
+            binary.resolved.iter().map(|(s, _)| s.as_str()).collect()
+        } else {
+            binary.needed.iter().map(String::as_str).collect()
+        };
+        for soname in needed {
+            if seen.insert(soname) {
+                sonames.push(soname);
+                ensure_node(&mut graph, soname, "soname");
+            }
+            let mut attrs = IndexMap::new();
+            if resolved.get(soname).is_some_and(|paths| paths.len() > 1)
+                && let Some((_, Some(path))) = binary.resolved.iter().find(|(s, _)| s == soname)
+            {
+                attrs.insert("path".to_string(), path.clone());
+            }
+            graph.edges.push(Edge {
+                from: name.clone(),
+                to: soname.to_string(),
+                attrs,
+                ..Default::default()
+            });
+        }
+    }
+
+    for soname in sonames {
+        let mut targets = resolved.get(soname).cloned().unwrap_or_default();
+        if targets.len() > 1 {
+            tracing::warn!("{soname:?} resolves to different paths: {targets:?}");
+        }
+        if targets.is_empty()
+            && let Some(path) = provided.get(soname)
+        {
+            targets.push(path);
+        }
+        if targets.is_empty() && resolved.contains_key(soname) {
+            graph.nodes[soname]
+                .attrs
+                .insert("missing".to_string(), "true".to_string());
+        }
+        for path in targets {
+            if path != soname {
+                ensure_node(&mut graph, path, "lib");
+                graph.edges.push(Edge {
+                    from: soname.to_string(),
+                    to: path.to_string(),
+                    ..Default::default()
+                });
+            }
+        }
+    }
+
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edge_pairs(graph: &DepGraph) -> Vec<(&str, &str)> {
+        graph
+            .edges
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn empty_input() {
+        let graph = parse("").unwrap();
+        assert!(graph.nodes.is_empty());
+    }
+
+    #[test]
+    fn entries() {
+        assert_eq!(
+            readelf_entry(" 0x0000000000000001 (NEEDED)             Shared library: [libc.so.6]"),
+            Some(("NEEDED", "libc.so.6"))
+        );
+        assert_eq!(
+            readelf_entry(" 0x000000000000000c (INIT)               0x1000"),
+            None
+        );
+        assert_eq!(
+            objdump_entry("  RUNPATH              $ORIGIN/../lib"),
+            Some(("RUNPATH", "$ORIGIN/../lib"))
+        );
+        assert_eq!(objdump_entry("  INIT                 0x1000"), None);
+        assert_eq!(
+            objdump_header("/bin/ls:     file format elf64-x86-64"),
+            Some("/bin/ls")
+        );
+        assert_eq!(
+            ldd_entry("\tlibc.so.6 => /lib/libc.so.6 (0x00007f3a1bc00000)"),
+            Some(("libc.so.6".to_string(), Some("/lib/libc.so.6".to_string())))
+        );
+        assert_eq!(
+            ldd_entry("\tlibz.so.1 => not found"),
+            Some(("libz.so.1".to_string(), None))
+        );
+        assert_eq!(
+            ldd_entry("\t/lib64/ld-linux-x86-64.so.2 (0x00007f3a1c200000)"),
+            Some((
+                "ld-linux-x86-64.so.2".to_string(),
+                Some("/lib64/ld-linux-x86-64.so.2".to_string())
+            ))
+        );
+        assert_eq!(ldd_entry("\tlinux-vdso.so.1 (0x00007ffc8a5f2000)"), None);
+        assert_eq!(ldd_entry("\tstatically linked"), None);
+    }
+
+    #[test]
+    fn single_readelf_named_by_soname() {
+        let input = "
+Dynamic section at offset 0x2df0 contains 25 entries:
+  Tag        Type                         Name/Value
+ 0x0000000000000001 (NEEDED)             Shared library: [libc.so.6]
+ 0x000000000000000e (SONAME)             Library soname: [libfoo.so.1]
+";
+        let graph = parse(input).unwrap();
+        assert_eq!(edge_pairs(&graph), vec![("libfoo.so.1", "libc.so.6")]);
+        assert_eq!(graph.nodes["libfoo.so.1"].node_type.as_deref(), Some("lib"));
+    }
+
+    #[test]
+    fn single_unnamed_executable() {
+        let input = "
+Dynamic section at offset 0x2df0 contains 25 entries:
+ 0x0000000000000001 (NEEDED)             Shared library: [libc.so.6]
+";
+        assert!(parse(input).is_err());
+    }
+
+    #[test]
+    fn ldd_only() {
+        let input = "\
+/usr/bin/true:
+\tlinux-vdso.so.1 (0x00007ffc8a5f2000)
+\tlibc.so.6 => /lib/libc.so.6 (0x00007f3a1bc00000)
+/usr/bin/false:
+\tlibc.so.6 => /lib/libc.so.6 (0x00007f3a1bc00000)
+";
+        let graph = parse(input).unwrap();
+        assert_eq!(
+            edge_pairs(&graph),
+            vec![
+                ("/usr/bin/true", "libc.so.6"),
+                ("/usr/bin/false", "libc.so.6"),
+                ("libc.so.6", "/lib/libc.so.6"),
+            ]
+        );
+        assert_eq!(graph.nodes["/usr/bin/true"].label, "true");
+        assert_eq!(
+            graph.nodes["/lib/libc.so.6"].node_type.as_deref(),
+            Some("lib")
+        );
+    }
+
+    #[test]
+    fn ldd_conflicting_resolutions() {
+        // Different RUNPATHs load a different libfoo for each binary
+        let input = "\
+/opt/a/bin/a:
+\tlibfoo.so.1 => /opt/a/lib/libfoo.so.1 (0x00007f3a1bc00000)
+\tlibc.so.6 => /lib/libc.so.6 (0x00007f3a1bc00000)
+/opt/b/bin/b:
+\tlibfoo.so.1 => /opt/b/lib/libfoo.so.1 (0x00007f3a1bc00000)
+\tlibc.so.6 => /lib/libc.so.6 (0x00007f3a1bc00000)
+";
+        let graph = parse(input).unwrap();
+        assert_eq!(
+            edge_pairs(&graph),
+            vec![
+                ("/opt/a/bin/a", "libfoo.so.1"),
+                ("/opt/a/bin/a", "libc.so.6"),
+                ("/opt/b/bin/b", "libfoo.so.1"),
+                ("/opt/b/bin/b", "libc.so.6"),
+                ("libfoo.so.1", "/opt/a/lib/libfoo.so.1"),
+                ("libfoo.so.1", "/opt/b/lib/libfoo.so.1"),
+                ("libc.so.6", "/lib/libc.so.6"),
+            ]
+        );
+        let paths: Vec<Option<&str>> = (graph.edges.iter().take(4))
+            .map(|e| e.attrs.get("path").map(String::as_str))
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                Some("/opt/a/lib/libfoo.so.1"),
+                None,
+                Some("/opt/b/lib/libfoo.so.1"),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn ldd_headers() {
+        let entry = Some("\tlibc.so.6 => /lib/libc.so.6 (0x00007f3a1bc00000)");
+        assert_eq!(ldd_header("/usr/bin/true:", None), Some("/usr/bin/true"));
+        assert_eq!(ldd_header("app:", entry), Some("app"));
+        assert_eq!(
+            ldd_header("app:", Some("\tlinux-vdso.so.1 (0x1)")),
+            Some("app")
+        );
+        assert_eq!(ldd_header("statically:", None), None);
+        assert_eq!(ldd_header("ldd:", Some("\tnot a dynamic executable")), None);
+        assert_eq!(ldd_header("ldd:", Some("app:")), None);
+        assert_eq!(ldd_header("  /usr/bin/true:", entry), None);
+    }
+
+    #[test]
+    fn ldd_status_lines_ignored() {
+        let input = "\
+/usr/bin/true:
+\tlibc.so.6 => /lib/libc.so.6 (0x00007f3a1bc00000)
+statically:
+ldd:
+\tnot a dynamic executable
+";
+        let graph = parse(input).unwrap();
+        let ids: Vec<&str> = graph.nodes.keys().map(|s| s.as_str()).collect();
+        assert_eq!(ids, vec!["/usr/bin/true", "libc.so.6", "/lib/libc.so.6"]);
+    }
+
+    #[test]
+    fn fixture() {
+        let input = include_str!("../../../../data/depconv/elf-deps.txt");
+        let graph = parse(input).unwrap();
+
+        let ids: Vec<&str> = graph.nodes.keys().map(|s| s.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "/opt/app/bin/app",
+                "/opt/app/lib/libfoo.so.1",
+                "/opt/app/lib/libbar.so.2",
+                "libfoo.so.1",
+                "libc.so.6",
+                "libbar.so.2",
+                "libz.so.1",
+                "/lib/x86_64-linux-gnu/libc.so.6",
+            ]
+        );
+
+        let app = &graph.nodes["/opt/app/bin/app"];
+        assert_eq!(app.label, "app");
+        assert_eq!(app.node_type.as_deref(), Some("bin"));
+        assert_eq!(app.attrs["runpath"], "$ORIGIN/../lib");
+
+        let libbar = &graph.nodes["/opt/app/lib/libbar.so.2"];
+        assert_eq!(libbar.node_type.as_deref(), Some("lib"));
+        assert_eq!(libbar.attrs["soname"], "libbar.so.2");
+        assert_eq!(libbar.attrs["rpath"], "/opt/vendor/lib");
+
+        assert_eq!(graph.nodes["libz.so.1"].attrs["missing"], "true");
+        assert_eq!(
+            graph.nodes["libc.so.6"].node_type.as_deref(),
+            Some("soname")
+        );
+
+        assert_eq!(
+            edge_pairs(&graph),
+            vec![
+                ("/opt/app/bin/app", "libfoo.so.1"),
+                ("/opt/app/bin/app", "libc.so.6"),
+                ("/opt/app/lib/libfoo.so.1", "libbar.so.2"),
+                ("/opt/app/lib/libfoo.so.1", "libc.so.6"),
+                ("/opt/app/lib/libbar.so.2", "libfoo.so.1"),
+                ("/opt/app/lib/libbar.so.2", "libz.so.1"),
+                ("/opt/app/lib/libbar.so.2", "libc.so.6"),
+                ("libfoo.so.1", "/opt/app/lib/libfoo.so.1"),
+                ("libc.so.6", "/lib/x86_64-linux-gnu/libc.so.6"),
+                ("libbar.so.2", "/opt/app/lib/libbar.so.2"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::{DepGraph, Edge, NodeInfo};
+
+/// The subset of `go list -json` package fields we use.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Package {
+    import_path: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    standard: bool,
+    module: Option<Module>,
+    #[serde(default)]
+    imports: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Module {
+    path: String,
+    version: Option<String>,
+}
+
+/// Parse the concatenated JSON objects from `go list -json -deps` into a [`DepGraph`].
+///
+/// Each package becomes a node keyed by its import path, with an edge to each of its direct
+/// `Imports`. `main` packages get `node_type = "bin"`, and everything else gets `"lib"`.
+/// Non-standard packages store their module path in `attrs["module"]` and, for modules other
+/// than the main module, the module version in `attrs["version"]`.
+pub fn parse(input: &str) -> eyre::Result<DepGraph> {
+    let mut graph = DepGraph::default();
+
+    for package in serde_json::Deserializer::from_str(input).into_iter::<Package>() {
+        let package = package?;
+
+        let mut attrs = IndexMap::new();
+        if let Some(module) = package.module.filter(|_| !package.standard) {
+            attrs.insert("module".to_string(), module.path);
+            if let Some(version) = module.version {
+                attrs.insert("version".to_string(), version);
+            }
+        }
+        let node_type = if package.name == "main" { "bin" } else { "lib" };
+
+        // `go list -deps` prints dependencies before their dependents, so imports may already
+        // have placeholder nodes. Replace them in place to keep their position.
+        graph.nodes.insert(
+            package.import_path.clone(),
+            NodeInfo {
+                label: package.import_path.clone(),
+                node_type: Some(node_type.to_string()),
+                attrs,
+            },
+        );
+
+        for import in package.imports {
+            graph
+                .nodes
+                .entry(import.clone())
+                .or_insert_with(|| NodeInfo::new(&import));
+            graph.edges.push(Edge {
+                from: package.import_path.clone(),
+                to: import,
+                ..Default::default()
+            });
+        }
+    }
+
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_input() {
+        let graph = parse("").unwrap();
+        assert!(graph.nodes.is_empty());
+        assert!(graph.edges.is_empty());
+    }
+
+    #[test]
+    fn invalid_json() {
+        assert!(parse("{\"ImportPath\": ").is_err());
+    }
+
+    #[test]
+    fn packages_and_imports() {
+        let input = r#"{
+	"Dir": "/usr/lib/go/src/errors",
+	"ImportPath": "errors",
+	"Name": "errors",
+	"Root": "/usr/lib/go",
+	"Goroot": true,
+	"Standard": true
+}
+{
+	"Dir": "/home/user/go/pkg/mod/github.com/pkg/errors@v0.9.1",
+	"ImportPath": "github.com/pkg/errors",
+	"Name": "errors",
+	"Module": {
+		"Path": "github.com/pkg/errors",
+		"Version": "v0.9.1"
+	},
+	"Imports": [
+		"fmt"
+	]
+}
+{
+	"Dir": "/home/user/src/app/cmd/app",
+	"ImportPath": "example.com/app/cmd/app",
+	"Name": "main",
+	"Module": {
+		"Path": "example.com/app",
+		"Main": true
+	},
+	"Imports": [
+		"errors",
+		"github.com/pkg/errors"
+	]
+}
+"#;
+        let graph = parse(input).unwrap();
+
+        let ids: Vec<&str> = graph.nodes.keys().map(|s| s.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "errors",
+                "github.com/pkg/errors",
+                "fmt",
+                "example.com/app/cmd/app"
+            ]
+        );
+
+        let std_errors = &graph.nodes["errors"];
+        assert_eq!(std_errors.node_type.as_deref(), Some("lib"));
+        assert!(std_errors.attrs.is_empty());
+
+        let pkg_errors = &graph.nodes["github.com/pkg/errors"];
+        assert_eq!(pkg_errors.attrs["module"], "github.com/pkg/errors");
+        assert_eq!(pkg_errors.attrs["version"], "v0.9.1");
+
+        // Only mentioned as an import; no package object
+        assert!(graph.nodes["fmt"].node_type.is_none());
+
+        let app = &graph.nodes["example.com/app/cmd/app"];
+        assert_eq!(app.node_type.as_deref(), Some("bin"));
+        assert_eq!(app.attrs["module"], "example.com/app");
+        assert!(app.attrs.get("version").is_none());
+
+        let edges: Vec<(&str, &str)> = graph
+            .edges
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str()))
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                ("github.com/pkg/errors", "fmt"),
+                ("example.com/app/cmd/app", "errors"),
+                ("example.com/app/cmd/app", "github.com/pkg/errors"),
+            ]
+        );
+    }
+
+    #[test]
+    fn placeholder_replaced_in_place() {
+        let input = r#"
+{"ImportPath": "a", "Name": "main", "Imports": ["b"]}
+{"ImportPath": "b", "Name": "b", "Module": {"Path": "b", "Version": "v1.0.0"}}
+"#;
+        let graph = parse(input).unwrap();
+        let ids: Vec<&str> = graph.nodes.keys().map(|s| s.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b"]);
+        assert_eq!(graph.nodes["b"].attrs["version"], "v1.0.0");
+    }
+}
@@ -0,0 +1,137 @@
+use indexmap::IndexMap;
+
+use crate::{DepGraph, Edge, NodeInfo};
+
+/// Parse `go mod graph` output into a [`DepGraph`].
+///
+/// Each line is a `module@version module@version` requirement edge. The node ID is the full
+/// `module@version` string, since several versions of one module can appear in the graph. The
+/// label is the module path, and the version is stored in `attrs["version"]`. The main module has
+/// no version.
+///
+/// The `go@version` and `toolchain@version` pseudo-modules that newer Go versions include are
+/// skipped; they're Go toolchain requirements, not dependencies.
+pub fn parse(input: &str) -> eyre::Result<DepGraph> {
+    let mut graph = DepGraph::default();
+
+    for line in input.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let mut parts = line.split_whitespace();
+        let (Some(from), Some(to), None) = (parts.next(), parts.next(), parts.next()) else {
+            eyre::bail!("expected 'module@version module@version', got: {line:?}");
+        };
+        if is_toolchain(to) {
+            continue;
+        }
+
+        ensure_node(&mut graph, from);
+        ensure_node(&mut graph, to);
+        graph.edges.push(Edge {
+            from: from.to_string(),
+            to: to.to_string(),
+            ..Default::default()
+        });
+    }
+
+    Ok(graph)
+}
+
+fn is_toolchain(module: &str) -> bool {
+    module.starts_with("go@") || module.starts_with("toolchain@")
+}
+
+fn ensure_node(graph: &mut DepGraph, id: &str) {
+    graph.nodes.entry(id.to_string()).or_insert_with(|| {
+        let mut attrs = IndexMap::new();
+        let label = match id.split_once('@') {
+            Some((module, version)) => {
+                attrs.insert("version".into(), version.into());
+                module
+            }
+            None => id,
+        };
+        NodeInfo {
+            label: label.to_string(),
+            node_type: None,
+            attrs,
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_input() {
+        let graph = parse("").unwrap();
+        assert!(graph.nodes.is_empty());
+        assert!(graph.edges.is_empty());
+    }
+
+    #[test]
+    fn edges_and_versions() {
+        let input = "\
+example.com/app github.com/pkg/errors@v0.9.1
+example.com/app golang.org/x/net@v0.20.0
+golang.org/x/net@v0.20.0 golang.org/x/text@v0.14.0
+";
+        let graph = parse(input).unwrap();
+        let ids: Vec<&str> = graph.nodes.keys().map(|s| s.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "example.com/app",
+                "github.com/pkg/errors@v0.9.1",
+                "golang.org/x/net@v0.20.0",
+                "golang.org/x/text@v0.14.0",
+            ]
+        );
+
+        let app = &graph.nodes["example.com/app"];
+        assert_eq!(app.label, "example.com/app");
+        assert!(app.attrs.get("version").is_none());
+
+        let net = &graph.nodes["golang.org/x/net@v0.20.0"];
+        assert_eq!(net.label, "golang.org/x/net");
+        assert_eq!(net.attrs["version"], "v0.20.0");
+
+        assert_eq!(graph.edges.len(), 3);
+        assert_eq!(graph.edges[2].from, "golang.org/x/net@v0.20.0");
+        assert_eq!(graph.edges[2].to, "golang.org/x/text@v0.14.0");
+    }
+
+    #[test]
+    fn multiple_versions_are_distinct_nodes() {
+        let input = "\
+example.com/app golang.org/x/text@v0.14.0
+golang.org/x/net@v0.20.0 golang.org/x/text@v0.13.0
+";
+        let graph = parse(input).unwrap();
+        assert!(graph.nodes.contains_key("golang.org/x/text@v0.14.0"));
+        assert!(graph.nodes.contains_key("golang.org/x/text@v0.13.0"));
+    }
+
+    #[test]
+    fn skips_toolchain_requirements() {
+        let input = "\
+example.com/app go@1.22
+example.com/app toolchain@go1.22.1
+example.com/app golang.org/x/text@v0.14.0
+golang.org/x/text@v0.14.0 go@1.18
+";
+        let graph = parse(input).unwrap();
+        assert_eq!(graph.nodes.len(), 2);
+        assert_eq!(graph.edges.len(), 1);
+    }
+
+    #[test]
+    fn malformed_line() {
+        assert!(parse("example.com/app\n").is_err());
+        assert!(parse("a@v1 b@v2 c@v3\n").is_err());
+    }
+}
@@ -0,0 +1,372 @@
+use std::collections::HashMap;
+
+use indexmap::IndexMap;
+
+use crate::{DepGraph, Edge, NodeInfo};
+
+/// Parse a line of `gradle dependencies` output into (depth, text), or None for anything that
+/// isn't a tree line. Depth 0 is a direct dependency of the project.
+fn parse_line(line: &str) -> Option<(usize, &str)> {
+    let mut depth = 0;
+    let mut rest = line;
+
+    loop {
+        if rest.starts_with("+--- ") || rest.starts_with("\\--- ") {
+            rest = &rest[5..];
+            break;
+        }
+        if rest.starts_with("|    ") || rest.starts_with("     ") {
+            rest = &rest[5..];
+            depth += 1;
+            continue;
+        }
+        return None;
+    }
+
+    let text = rest.trim_end();
+    if text.is_empty() {
+        None
+    } else {
+        Some((depth, text))
+    }
+}
+
+/// A configuration header like `runtimeClasspath - Runtime classpath of source set 'main'.`
+fn parse_configuration(line: &str) -> Option<&str> {
+    let name = line.split_once(" - ").map_or(line, |(name, _)| name);
+    let valid = name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name.chars().all(|c| c.is_ascii_alphanumeric());
+    valid.then_some(name)
+}
+
+/// A project header like `Root project 'demo'` or `Project ':app'`.
+fn parse_project(line: &str) -> Option<&str> {
+    let rest = line
+        .strip_prefix("Root project ")
+        .or_else(|| line.strip_prefix("Project "))?;
+    // Gradle may append a description after the quoted name
+    let rest = rest.strip_prefix('\'')?;
+    let (name, _) = rest.split_once('\'')?;
+    Some(name)
+}
+
+/// A dependency parsed from the text of a tree line.
+struct Dependency<'a> {
+    /// `group:artifact` or `:project`
+    name: &'a str,
+    /// The version that was requested, if any
+    requested: Option<&'a str>,
+    /// The version that was selected, after conflict resolution
+    resolved: Option<&'a str>,
+}
+
+impl Dependency<'_> {
+    fn id(&self) -> String {
+        match self.resolved.or(self.requested) {
+            Some(version) => format!("{} {version}", self.name),
+            None => self.name.to_string(),
+        }
+    }
+}
+
+/// Parse the text of a tree line, or None for dependency constraints (`(c)`), which aren't
+/// dependencies.
+fn parse_dependency(text: &str) -> Option<Dependency<'_>> {
+    let mut rest = text;
+    loop {
+        if let Some(stripped) = rest
+            .strip_suffix("(*)")
+            .or_else(|| rest.strip_suffix("(n)"))
+            .or_else(|| rest.strip_suffix("FAILED"))
+        {
+            rest = stripped.trim_end();
+        } else if rest.ends_with("(c)") {
+            return None;
+        } else {
+            break;
+        }
+    }
+
+    let (requested, resolved) = match rest.split_once(" -> ") {
+        Some((requested, resolved)) => (requested, Some(resolved.trim())),
+        None => (rest, None),
+    };
+
+    if let Some(project) = requested.strip_prefix("project ") {
+        return Some(Dependency {
+            name: project,
+            requested: None,
+            resolved: None,
+        });
+    }
+
+    // group:artifact[:version]; the version may be a rich version like `{strictly 1.7.36}`
+    let mut parts = requested.splitn(3, ':');
+    let (name, version) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(group), Some(artifact), version) => {
+            let len = group.len() + 1 + artifact.len();
+            (&requested[..len], version)
+        }
+        _ => (requested, None),
+    };
+    Some(Dependency {
+        name,
+        requested: version,
+        resolved,
+    })
+}
+
+/// Parse `gradle dependencies` output into a [`DepGraph`].
+///
+/// Each dependency becomes a node keyed `group:artifact version` using the version Gradle
+/// resolved, with the `group:artifact` as the label and the version in `attrs["version"]`.
+/// Project dependencies (`project :lib`) are keyed by their project path. If the output includes
+/// a `Project ':app'` header, the project becomes the root node; otherwise each configuration's
+/// direct dependencies are roots.
+///
+/// Edges store the configurations they appear in (`compileClasspath`, `testRuntimeClasspath`, ...)
+/// in a comma-separated `scope` attr. When conflict resolution (`->`) picked a different version
+/// than the one requested, the requested version is stored in the edge's `requested` attr.
+///
+/// Dependency constraints (`(c)`) are skipped. Repeated subtrees (`(*)`) aren't expanded by
+/// Gradle, but the first occurrence already has the full subtree.
+pub fn parse(input: &str) -> eyre::Result<DepGraph> {
+    let mut graph = DepGraph::default();
+    let mut project: Option<String> = None;
+    let mut configuration: Option<String> = None;
+    // stack[i] = node ID at depth i; `None` for skipped constraint lines
+    let mut stack: Vec<Option<String>> = Vec::new();
+    // (from, to) -> index into graph.edges
+    let mut edge_index: HashMap<(String, String), usize> = HashMap::new();
+
+    for line in input.lines() {
+        let Some((depth, text)) = parse_line(line) else {
+            if let Some(name) = parse_project(line) {
+                project = Some(name.to_string());
+                graph
+                    .nodes
+                    .entry(name.to_string())
+                    .or_insert_with(|| NodeInfo::new(name));
+            } else if let Some(name) = parse_configuration(line) {
+                configuration = Some(name.to_string());
+            }
+            stack.clear();
+            continue;
+        };
+
+        if depth > stack.len() {
+            eyre::bail!("unexpected depth jump at line: {text:?}");
+        }
+        stack.truncate(depth);
+
+        let Some(dep) = parse_dependency(text) else {
+            stack.push(None);
+            continue;
+        };
+        let id = dep.id();
+        let node = graph.nodes.entry(id.clone()).or_insert_with(|| {
+            let mut attrs = IndexMap::new();
+            if let Some(version) = dep.resolved.or(dep.requested) {
+                attrs.insert("version".to_string(), version.to_string());
+            }
+            NodeInfo {
+                label: dep.name.to_string(),
+                node_type: None,
+                attrs,
+            }
+        });
+        if node.attrs.is_empty()
+            && let Some(version) = dep.resolved.or(dep.requested)
+        {
+            node.attrs
+                .insert("version".to_string(), version.to_string());
+        }
+
+        let parent = if depth == 0 {
+            project.clone()
+        } else {
+            stack.last().cloned().flatten()
+        };
+        if let Some(parent) = parent {
+            let index = *edge_index
+                .entry((parent.clone(), id.clone()))
+                .or_insert_with(|| {
+                    let mut attrs = IndexMap::new();
+                    if let (Some(requested), Some(resolved)) = (dep.requested, dep.resolved)
+                        && requested != resolved
+                    {
+                        attrs.insert("requested".to_string(), requested.to_string());
+                    }
+                    graph.edges.push(Edge {
+                        from: parent,
+                        to: id.clone(),
+                        label: None,
+                        attrs,
+                    });
+                    graph.edges.len() - 1
+                });
+            if let Some(configuration) = &configuration {
+                let attrs = &mut graph.edges[index].attrs;
+                match attrs.get_mut("scope") {
+                    Some(scope) => {
+                        if !scope.split(',').any(|s| s == configuration) {
+                            scope.push(',');
+                            scope.push_str(configuration);
+                        }
+                    }
+                    None => {
+                        attrs.insert("scope".to_string(), configuration.clone());
+                    }
+                }
+            }
+        }
+
+        stack.push(Some(id));
+    }
+
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type EdgeTuple<'a> = (&'a str, &'a str, Vec<(&'a str, &'a str)>);
+
+    fn edge_tuples(graph: &DepGraph) -> Vec<EdgeTuple<'_>> {
+        graph
+            .edges
+            .iter()
+            .map(|e| {
+                let attrs = e.attrs.iter().map(|(k, v)| (k.as_str(), v.as_str()));
+                (e.from.as_str(), e.to.as_str(), attrs.collect())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn lines() {
+        assert_eq!(
+            parse_line("+--- org.slf4j:slf4j-api:1.7.36"),
+            Some((0, "org.slf4j:slf4j-api:1.7.36"))
+        );
+        assert_eq!(
+            parse_line("|    |    \\--- a:b:1.0 (*)"),
+            Some((2, "a:b:1.0 (*)"))
+        );
+        assert_eq!(parse_line("     \\--- a:b:1.0"), Some((1, "a:b:1.0")));
+        assert_eq!(parse_line("No dependencies"), None);
+        assert_eq!(
+            parse_configuration("runtimeClasspath - Runtime classpath."),
+            Some("runtimeClasspath")
+        );
+        assert_eq!(parse_configuration("No dependencies"), None);
+        assert_eq!(parse_project("Root project 'demo'"), Some("demo"));
+        assert_eq!(parse_project("Project ':app' - The app"), Some(":app"));
+    }
+
+    #[test]
+    fn dependencies() {
+        let dep = parse_dependency("com.google.guava:guava:31.1-jre -> 32.1.2-jre (*)").unwrap();
+        assert_eq!(dep.name, "com.google.guava:guava");
+        assert_eq!(dep.requested, Some("31.1-jre"));
+        assert_eq!(dep.resolved, Some("32.1.2-jre"));
+        assert_eq!(dep.id(), "com.google.guava:guava 32.1.2-jre");
+
+        let dep = parse_dependency("com.squareup.okhttp3:okhttp -> 4.11.0").unwrap();
+        assert_eq!(dep.name, "com.squareup.okhttp3:okhttp");
+        assert_eq!(dep.requested, None);
+
+        let dep = parse_dependency("org.slf4j:slf4j-api:{strictly 1.7.36} -> 1.7.36").unwrap();
+        assert_eq!(dep.requested, Some("{strictly 1.7.36}"));
+
+        let dep = parse_dependency("project :lib").unwrap();
+        assert_eq!(dep.id(), ":lib");
+
+        assert!(parse_dependency("org.jetbrains.kotlin:kotlin-stdlib:1.9.0 (c)").is_none());
+    }
+
+    #[test]
+    fn depth_jump() {
+        assert!(parse("+--- a:b:1.0\n|    |    \\--- c:d:1.0\n").is_err());
+    }
+
+    #[test]
+    fn fixture() {
+        let input = include_str!("../../../../data/depconv/gradle-dependencies.txt");
+        let graph = parse(input).unwrap();
+
+        let ids: Vec<&str> = graph.nodes.keys().map(|s| s.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                ":app",
+                "org.jetbrains.kotlin:kotlin-stdlib 1.9.0",
+                "org.jetbrains:annotations 13.0",
+                "com.google.guava:guava 32.1.2-jre",
+                "com.google.guava:failureaccess 1.0.1",
+                "com.squareup.okhttp3:okhttp 4.11.0",
+                ":lib",
+                "junit:junit 4.13.2",
+                "org.hamcrest:hamcrest-core 1.3",
+            ]
+        );
+        let guava = &graph.nodes["com.google.guava:guava 32.1.2-jre"];
+        assert_eq!(guava.label, "com.google.guava:guava");
+        assert_eq!(guava.attrs["version"], "32.1.2-jre");
+
+        assert_eq!(
+            edge_tuples(&graph),
+            vec![
+                (
+                    ":app",
+                    "org.jetbrains.kotlin:kotlin-stdlib 1.9.0",
+                    vec![("scope", "compileClasspath,testRuntimeClasspath")]
+                ),
+                (
+                    "org.jetbrains.kotlin:kotlin-stdlib 1.9.0",
+                    "org.jetbrains:annotations 13.0",
+                    vec![("scope", "compileClasspath,testRuntimeClasspath")]
+                ),
+                (
+                    ":app",
+                    "com.google.guava:guava 32.1.2-jre",
+                    vec![
+                        ("requested", "31.1-jre"),
+                        ("scope", "compileClasspath,testRuntimeClasspath")
+                    ]
+                ),
+                (
+                    "com.google.guava:guava 32.1.2-jre",
+                    "com.google.guava:failureaccess 1.0.1",
+                    vec![("scope", "compileClasspath,testRuntimeClasspath")]
+                ),
+                (
+                    ":app",
+                    "com.squareup.okhttp3:okhttp 4.11.0",
+                    vec![("scope", "compileClasspath,testRuntimeClasspath")]
+                ),
+                (
+                    ":app",
+                    ":lib",
+                    vec![("scope", "compileClasspath,testRuntimeClasspath")]
+                ),
+                (
+                    ":lib",
+                    "org.jetbrains.kotlin:kotlin-stdlib 1.9.0",
+                    vec![("scope", "compileClasspath,testRuntimeClasspath")]
+                ),
+                (
+                    ":app",
+                    "junit:junit 4.13.2",
+                    vec![("scope", "testRuntimeClasspath")]
+                ),
+                (
+                    "junit:junit 4.13.2",
+                    "org.hamcrest:hamcrest-core 1.3",
+                    vec![("scope", "testRuntimeClasspath")]
+                ),
+            ]
+        );
+    }
+}
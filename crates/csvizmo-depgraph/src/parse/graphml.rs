@@ -0,0 +1,427 @@
+use std::collections::HashMap;
+
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+
+use crate::{DepGraph, Edge, NodeInfo};
+
+/// An open XML element on the parser stack.
+enum Frame {
+    Graph(Box<DepGraph>),
+    /// A `<node>` element. If it contains a nested `<graph>`, it's a container for a subgraph
+    /// rather than a real node, and its data is applied to the subgraph instead.
+    Node {
+        id: String,
+        info: NodeInfo,
+        has_graph: bool,
+    },
+    Edge(Edge),
+    /// A `<data>` element. `complex` is set when the data contains child elements (e.g. yEd's
+    /// `<y:ShapeNode>` graphics), which are not representable as plain string attrs.
+    Data {
+        key: String,
+        text: String,
+        complex: bool,
+    },
+    /// Any element we don't care about (`<desc>`, `<port>`, `<default>`, ...).
+    Ignored,
+}
+
+/// A GraphML `<key>` declaration.
+struct Key {
+    /// The attribute name from `attr.name`, falling back to the key ID.
+    name: String,
+}
+
+fn attr(e: &BytesStart, name: &[u8]) -> eyre::Result<Option<String>> {
+    for a in e.attributes() {
+        let a = a?;
+        if a.key.local_name().as_ref() == name {
+            return Ok(Some(a.unescape_value()?.into_owned()));
+        }
+    }
+    Ok(None)
+}
+
+fn required_attr(e: &BytesStart, name: &[u8]) -> eyre::Result<String> {
+    attr(e, name)?.ok_or_else(|| {
+        eyre::eyre!(
+            "GraphML <{}> missing required '{}' attribute",
+            String::from_utf8_lossy(e.local_name().as_ref()),
+            String::from_utf8_lossy(name)
+        )
+    })
+}
+
+/// Parse a GraphML document into a [`DepGraph`].
+///
+/// `<data>` values are mapped through their `<key>` declarations by `attr.name`:
+/// - Node `label` and `type` become [`NodeInfo::label`] and [`NodeInfo::node_type`].
+/// - Edge `label` becomes [`Edge::label`].
+/// - Everything else becomes a graph, node, or edge attr.
+///
+/// Nested `<graph>` elements (GraphML's representation of group nodes) become subgraphs; the
+/// container node is dropped, and its data is applied to the subgraph's attrs. Data containing
+/// nested XML (e.g. yEd graphics) is skipped. All edges are treated as directed.
+pub fn parse(input: &str) -> eyre::Result<DepGraph> {
+    let mut reader = Reader::from_str(input);
+    let mut keys: HashMap<String, Key> = HashMap::new();
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut root: Option<DepGraph> = None;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) => {
+                let frame = start_element(&e, &mut keys, &mut stack)?;
+                stack.push(frame);
+            }
+            Event::Empty(e) => {
+                let frame = start_element(&e, &mut keys, &mut stack)?;
+                end_element(frame, &keys, &mut stack, &mut root)?;
+            }
+            Event::End(_) => {
+                let frame = stack
+                    .pop()
+                    .ok_or_else(|| eyre::eyre!("unbalanced GraphML element"))?;
+                end_element(frame, &keys, &mut stack, &mut root)?;
+            }
+            Event::Text(e) => {
+                if let Some(Frame::Data { text, .. }) = stack.last_mut() {
+                    text.push_str(&e.unescape()?);
+                }
+            }
+            Event::CData(e) => {
+                if let Some(Frame::Data { text, .. }) = stack.last_mut() {
+                    text.push_str(&String::from_utf8_lossy(&e));
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    root.ok_or_else(|| eyre::eyre!("GraphML document has no <graph> element"))
+}
+
+fn start_element(
+    e: &BytesStart,
+    keys: &mut HashMap<String, Key>,
+    stack: &mut [Frame],
+) -> eyre::Result<Frame> {
+    // Child elements inside <data> mean the value is structured XML, not a string.
+    if let Some(Frame::Data { complex, .. }) = stack.last_mut() {
+        *complex = true;
+        return Ok(Frame::Ignored);
+    }
+
+    let frame = match e.local_name().as_ref() {
+        b"key" => {
+            let id = required_attr(e, b"id")?;
+            let name = attr(e, b"attr.name")?.unwrap_or_else(|| id.clone());
+            keys.insert(id, Key { name });
+            Frame::Ignored
+        }
+        b"graph" => {
+            if let Some(Frame::Node { has_graph, .. }) = stack.last_mut() {
+                *has_graph = true;
+            }
+            Frame::Graph(Box::new(DepGraph {
+                id: attr(e, b"id")?,
+                ..Default::default()
+            }))
+        }
+        b"node" => {
+            let id = required_attr(e, b"id")?;
+            Frame::Node {
+                info: NodeInfo::new(id.clone()),
+                id,
+                has_graph: false,
+            }
+        }
+        b"edge" => Frame::Edge(Edge {
+            from: required_attr(e, b"source")?,
+            to: required_attr(e, b"target")?,
+            ..Default::default()
+        }),
+        b"data" => Frame::Data {
+            key: required_attr(e, b"key")?,
+            text: String::new(),
+            complex: false,
+        },
+        _ => Frame::Ignored,
+    };
+    Ok(frame)
+}
+
+fn end_element(
+    frame: Frame,
+    keys: &HashMap<String, Key>,
+    stack: &mut [Frame],
+    root: &mut Option<DepGraph>,
+) -> eyre::Result<()> {
+    match frame {
+        Frame::Graph(graph) => match stack.last() {
+            // A nested graph becomes a subgraph of the graph enclosing its container node.
+            Some(Frame::Node { .. }) => {
+                let parent = stack
+                    .iter_mut()
+                    .rev()
+                    .find_map(|f| match f {
+                        Frame::Graph(g) => Some(g),
+                        _ => None,
+                    })
+                    .ok_or_else(|| eyre::eyre!("GraphML <node> outside of a <graph>"))?;
+                parent.subgraphs.push(*graph);
+            }
+            _ if !stack.iter().any(|f| matches!(f, Frame::Graph(_))) => {
+                if root.is_some() {
+                    eyre::bail!("GraphML documents with multiple top-level graphs are unsupported");
+                }
+                *root = Some(*graph);
+            }
+            _ => eyre::bail!("unexpected <graph> element in GraphML"),
+        },
+        Frame::Node {
+            id,
+            info,
+            has_graph,
+        } => {
+            let Some(Frame::Graph(parent)) = stack.last_mut() else {
+                eyre::bail!("GraphML <node> outside of a <graph>");
+            };
+            if has_graph {
+                // The container's data (e.g. a group label) describes the subgraph it holds,
+                // which was just pushed as the parent's last subgraph.
+                if let Some(sg) = parent.subgraphs.last_mut() {
+                    if info.label != id {
+                        sg.attrs.insert("label".to_string(), info.label);
+                    }
+                    sg.attrs.extend(info.attrs);
+                }
+            } else {
+                parent.nodes.insert(id, info);
+            }
+        }
+        Frame::Edge(edge) => {
+            let Some(Frame::Graph(parent)) = stack.last_mut() else {
+                eyre::bail!("GraphML <edge> outside of a <graph>");
+            };
+            parent.edges.push(edge);
+        }
+        Frame::Data { key, text, complex } => {
+            if complex {
+                return Ok(());
+            }
+            let name = keys.get(&key).map(|k| k.name.as_str()).unwrap_or(&key);
+            match stack.last_mut() {
+                Some(Frame::Graph(graph)) => {
+                    graph.attrs.insert(name.to_string(), text);
+                }
+                Some(Frame::Node { info, .. }) => match name {
+                    "label" => info.label = text,
+                    "type" => info.node_type = Some(text),
+                    _ => {
+                        info.attrs.insert(name.to_string(), text);
+                    }
+                },
+                Some(Frame::Edge(edge)) => match name {
+                    "label" => edge.label = Some(text),
+                    _ => {
+                        edge.attrs.insert(name.to_string(), text);
+                    }
+                },
+                // <data> directly under <graphml> is document-level metadata; drop it.
+                _ => {}
+            }
+        }
+        Frame::Ignored => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_graph() {
+        let graph = parse(
+            r#"<?xml version="1.0"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <graph edgedefault="directed"/>
+</graphml>"#,
+        )
+        .unwrap();
+        assert!(graph.id.is_none());
+        assert!(graph.nodes.is_empty());
+        assert!(graph.edges.is_empty());
+    }
+
+    #[test]
+    fn no_graph_element() {
+        assert!(parse(r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns"/>"#).is_err());
+    }
+
+    #[test]
+    fn nodes_without_data() {
+        let graph = parse(
+            r#"<graphml>
+  <graph id="G" edgedefault="directed">
+    <node id="a"/>
+    <node id="b"/>
+    <edge source="a" target="b"/>
+  </graph>
+</graphml>"#,
+        )
+        .unwrap();
+        assert_eq!(graph.id.as_deref(), Some("G"));
+        assert_eq!(graph.nodes.len(), 2);
+        assert_eq!(graph.nodes["a"].label, "a");
+        assert_eq!(graph.edges.len(), 1);
+        assert_eq!(graph.edges[0].from, "a");
+        assert_eq!(graph.edges[0].to, "b");
+        assert_eq!(graph.edges[0].label, None);
+    }
+
+    #[test]
+    fn keys_map_by_attr_name() {
+        let graph = parse(
+            r#"<graphml>
+  <key id="d0" for="graph" attr.name="rankdir" attr.type="string"/>
+  <key id="d1" for="node" attr.name="label" attr.type="string"/>
+  <key id="d2" for="node" attr.name="type" attr.type="string"/>
+  <key id="d3" for="node" attr.name="version" attr.type="string"/>
+  <key id="d4" for="edge" attr.name="label" attr.type="string"/>
+  <key id="d5" for="edge" attr.name="kind" attr.type="string"/>
+  <graph edgedefault="directed">
+    <data key="d0">LR</data>
+    <node id="a">
+      <data key="d1">Alpha &amp; co</data>
+      <data key="d2">lib</data>
+      <data key="d3">1.0</data>
+    </node>
+    <node id="b"/>
+    <edge source="a" target="b">
+      <data key="d4">uses</data>
+      <data key="d5">dev</data>
+    </edge>
+  </graph>
+</graphml>"#,
+        )
+        .unwrap();
+        assert_eq!(graph.attrs["rankdir"], "LR");
+        let a = &graph.nodes["a"];
+        assert_eq!(a.label, "Alpha & co");
+        assert_eq!(a.node_type.as_deref(), Some("lib"));
+        assert_eq!(a.attrs["version"], "1.0");
+        assert_eq!(a.attrs.len(), 1);
+        assert_eq!(graph.edges[0].label.as_deref(), Some("uses"));
+        assert_eq!(graph.edges[0].attrs["kind"], "dev");
+    }
+
+    #[test]
+    fn key_without_attr_name_uses_id() {
+        let graph = parse(
+            r#"<graphml>
+  <key id="weight" for="edge"/>
+  <graph edgedefault="directed">
+    <node id="a"/>
+    <edge source="a" target="a"><data key="weight">3</data></edge>
+  </graph>
+</graphml>"#,
+        )
+        .unwrap();
+        assert_eq!(graph.edges[0].attrs["weight"], "3");
+    }
+
+    #[test]
+    fn nested_graphs_become_subgraphs() {
+        let graph = parse(
+            r#"<graphml>
+  <key id="d0" for="node" attr.name="label"/>
+  <graph edgedefault="directed">
+    <node id="n0">
+      <data key="d0">Group</data>
+      <graph id="n0:" edgedefault="directed">
+        <node id="n0::n0"><data key="d0">inner</data></node>
+        <node id="n0::n1">
+          <graph id="n0::n1:" edgedefault="directed">
+            <node id="n0::n1::n0"/>
+          </graph>
+        </node>
+      </graph>
+    </node>
+    <node id="n1"/>
+    <edge source="n1" target="n0::n0"/>
+  </graph>
+</graphml>"#,
+        )
+        .unwrap();
+        assert_eq!(graph.nodes.len(), 1);
+        assert!(graph.nodes.contains_key("n1"));
+        assert_eq!(graph.subgraphs.len(), 1);
+
+        let sg = &graph.subgraphs[0];
+        assert_eq!(sg.id.as_deref(), Some("n0:"));
+        assert_eq!(sg.attrs["label"], "Group");
+        assert_eq!(sg.nodes.len(), 1);
+        assert_eq!(sg.nodes["n0::n0"].label, "inner");
+        assert_eq!(sg.subgraphs.len(), 1);
+        assert!(sg.subgraphs[0].nodes.contains_key("n0::n1::n0"));
+
+        assert_eq!(graph.all_nodes().len(), 3);
+        assert_eq!(graph.edges.len(), 1);
+    }
+
+    #[test]
+    fn structured_data_is_skipped() {
+        // yEd stores node graphics as nested XML inside <data>
+        let graph = parse(
+            r#"<graphml xmlns:y="http://www.yworks.com/xml/graphml">
+  <key id="d6" for="node" yfiles.type="nodegraphics"/>
+  <graph edgedefault="directed">
+    <node id="n0">
+      <data key="d6">
+        <y:ShapeNode>
+          <y:NodeLabel>Alpha</y:NodeLabel>
+        </y:ShapeNode>
+      </data>
+    </node>
+  </graph>
+</graphml>"#,
+        )
+        .unwrap();
+        assert_eq!(graph.nodes["n0"].label, "n0");
+        assert!(graph.nodes["n0"].attrs.is_empty());
+    }
+
+    #[test]
+    fn cdata_values() {
+        let graph = parse(
+            r#"<graphml>
+  <key id="d0" for="node" attr.name="label"/>
+  <graph edgedefault="directed">
+    <node id="a"><data key="d0"><![CDATA[<a> & <b>]]></data></node>
+  </graph>
+</graphml>"#,
+        )
+        .unwrap();
+        assert_eq!(graph.nodes["a"].label, "<a> & <b>");
+    }
+
+    #[test]
+    fn edge_missing_target() {
+        let result =
+            parse(r#"<graphml><graph edgedefault="directed"><edge source="a"/></graph></graphml>"#);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn roundtrip_with_emit() {
+        let input = include_str!("../../../../data/depconv/small.graphml");
+        let graph = parse(input).unwrap();
+        let mut buf = Vec::new();
+        crate::emit::emit(crate::emit::OutputFormat::GraphMl, &graph, &mut buf).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), input);
+    }
+}
@@ -0,0 +1,287 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{DepGraph, Edge, NodeInfo};
+
+/// A JSON Graph Format (JGF) document with a single top-level graph.
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct Document {
+    pub graph: Graph,
+}
+
+/// A JGF graph object.
+///
+/// `subgraphs` is an extension to JGF: each subgraph is itself a graph object, recursively.
+/// Other JGF consumers ignore it, and see only the top-level nodes and edges.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub(crate) struct Graph {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub directed: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub metadata: IndexMap<String, Value>,
+    #[serde(default)]
+    pub nodes: Nodes,
+    #[serde(default)]
+    pub edges: Vec<JgfEdge>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subgraphs: Vec<Graph>,
+}
+
+/// JGF v2 stores nodes as an object keyed by ID; JGF v1 used an array of nodes with an `id`.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub(crate) enum Nodes {
+    Map(IndexMap<String, JgfNode>),
+    List(Vec<JgfListNode>),
+}
+
+impl Default for Nodes {
+    fn default() -> Self {
+        Self::Map(IndexMap::new())
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub(crate) struct JgfNode {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub metadata: IndexMap<String, Value>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct JgfListNode {
+    pub id: String,
+    #[serde(flatten)]
+    pub node: JgfNode,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub(crate) struct JgfEdge {
+    pub source: String,
+    pub target: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relation: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub metadata: IndexMap<String, Value>,
+}
+
+/// Convert a JGF metadata value to an attr string.
+///
+/// Strings are taken verbatim; anything else (numbers, bools, arrays, objects -- e.g. after
+/// post-processing with jq) is kept as its compact JSON text.
+fn value_to_string(value: Value) -> String {
+    match value {
+        Value::String(s) => s,
+        other => other.to_string(),
+    }
+}
+
+fn convert_metadata(metadata: IndexMap<String, Value>) -> IndexMap<String, String> {
+    metadata
+        .into_iter()
+        .map(|(k, v)| (k, value_to_string(v)))
+        .collect()
+}
+
+fn convert_node(id: &str, node: JgfNode) -> NodeInfo {
+    let mut attrs = convert_metadata(node.metadata);
+    let node_type = attrs.shift_remove("type");
+    NodeInfo {
+        label: node.label.unwrap_or_else(|| id.to_string()),
+        node_type,
+        attrs,
+    }
+}
+
+fn convert_graph(graph: Graph) -> DepGraph {
+    let mut result = DepGraph {
+        id: graph.id,
+        attrs: convert_metadata(graph.metadata),
+        ..Default::default()
+    };
+    if let Some(label) = graph.label {
+        result.attrs.entry("label".to_string()).or_insert(label);
+    }
+
+    match graph.nodes {
+        Nodes::Map(nodes) => {
+            for (id, node) in nodes {
+                let info = convert_node(&id, node);
+                result.nodes.insert(id, info);
+            }
+        }
+        Nodes::List(nodes) => {
+            for JgfListNode { id, node } in nodes {
+                let info = convert_node(&id, node);
+                result.nodes.insert(id, info);
+            }
+        }
+    }
+
+    for edge in graph.edges {
+        let mut attrs = convert_metadata(edge.metadata);
+        if let Some(relation) = edge.relation {
+            attrs.entry("relation".to_string()).or_insert(relation);
+        }
+        result.edges.push(Edge {
+            from: edge.source,
+            to: edge.target,
+            label: edge.label,
+            attrs,
+        });
+    }
+
+    result.subgraphs = graph.subgraphs.into_iter().map(convert_graph).collect();
+    result
+}
+
+/// Parse a JSON Graph Format document into a [`DepGraph`].
+///
+/// Accepts both JGF v2 (nodes keyed by ID) and v1 (nodes as an array with `id` fields). Graph,
+/// node, and edge `metadata` become attrs, except node `metadata.type`, which becomes
+/// [`NodeInfo::node_type`]. Nested graphs under the `subgraphs` extension key become subgraphs.
+/// Edge `relation` is kept as a `relation` attr. Multi-graph (`graphs`) documents are not
+/// supported.
+pub fn parse(input: &str) -> eyre::Result<DepGraph> {
+    let doc: Document = serde_json::from_str(input)?;
+    Ok(convert_graph(doc.graph))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_graph() {
+        let graph = parse(r#"{"graph": {}}"#).unwrap();
+        assert!(graph.id.is_none());
+        assert!(graph.nodes.is_empty());
+        assert!(graph.edges.is_empty());
+    }
+
+    #[test]
+    fn missing_graph_key() {
+        assert!(parse(r#"{"graphs": []}"#).is_err());
+    }
+
+    #[test]
+    fn v2_nodes_and_edges() {
+        let graph = parse(
+            r#"{
+  "graph": {
+    "id": "deps",
+    "directed": true,
+    "metadata": {"rankdir": "LR"},
+    "nodes": {
+      "a": {"label": "alpha", "metadata": {"type": "lib", "version": "1.0"}},
+      "b": {}
+    },
+    "edges": [
+      {"source": "a", "target": "b", "label": "uses", "metadata": {"kind": "dev"}}
+    ]
+  }
+}"#,
+        )
+        .unwrap();
+        assert_eq!(graph.id.as_deref(), Some("deps"));
+        assert_eq!(graph.attrs["rankdir"], "LR");
+
+        let a = &graph.nodes["a"];
+        assert_eq!(a.label, "alpha");
+        assert_eq!(a.node_type.as_deref(), Some("lib"));
+        assert_eq!(a.attrs.len(), 1);
+        assert_eq!(a.attrs["version"], "1.0");
+        assert_eq!(graph.nodes["b"].label, "b");
+
+        assert_eq!(graph.edges.len(), 1);
+        assert_eq!(graph.edges[0].from, "a");
+        assert_eq!(graph.edges[0].to, "b");
+        assert_eq!(graph.edges[0].label.as_deref(), Some("uses"));
+        assert_eq!(graph.edges[0].attrs["kind"], "dev");
+    }
+
+    #[test]
+    fn v1_node_list() {
+        let graph =
+            parse(r#"{"graph": {"nodes": [{"id": "x", "label": "X"}, {"id": "y"}], "edges": []}}"#)
+                .unwrap();
+        let keys: Vec<&str> = graph.nodes.keys().map(|s| s.as_str()).collect();
+        assert_eq!(keys, vec!["x", "y"]);
+        assert_eq!(graph.nodes["x"].label, "X");
+        assert_eq!(graph.nodes["y"].label, "y");
+    }
+
+    #[test]
+    fn non_string_metadata_stringified() {
+        let graph = parse(
+            r#"{"graph": {"nodes": {"a": {"metadata": {"size": 42, "pinned": true, "tags": ["x", "y"]}}}}}"#,
+        )
+        .unwrap();
+        let attrs = &graph.nodes["a"].attrs;
+        assert_eq!(attrs["size"], "42");
+        assert_eq!(attrs["pinned"], "true");
+        assert_eq!(attrs["tags"], r#"["x","y"]"#);
+    }
+
+    #[test]
+    fn graph_label_and_edge_relation() {
+        let graph = parse(
+            r#"{"graph": {"label": "My Graph", "nodes": {"a": {}}, "edges": [{"source": "a", "target": "a", "relation": "depends"}]}}"#,
+        )
+        .unwrap();
+        assert_eq!(graph.attrs["label"], "My Graph");
+        assert_eq!(graph.edges[0].attrs["relation"], "depends");
+    }
+
+    #[test]
+    fn recursive_subgraphs() {
+        let graph = parse(
+            r#"{
+  "graph": {
+    "nodes": {"top": {}},
+    "edges": [{"source": "top", "target": "inner"}],
+    "subgraphs": [
+      {
+        "id": "outer",
+        "metadata": {"label": "Outer"},
+        "nodes": {"mid": {}},
+        "subgraphs": [{"nodes": {"inner": {"label": "Inner"}}}]
+      }
+    ]
+  }
+}"#,
+        )
+        .unwrap();
+        assert_eq!(graph.nodes.len(), 1);
+        assert_eq!(graph.subgraphs.len(), 1);
+        let outer = &graph.subgraphs[0];
+        assert_eq!(outer.id.as_deref(), Some("outer"));
+        assert_eq!(outer.attrs["label"], "Outer");
+        assert!(outer.nodes.contains_key("mid"));
+        assert_eq!(outer.subgraphs[0].nodes["inner"].label, "Inner");
+        assert!(outer.subgraphs[0].id.is_none());
+        assert_eq!(graph.all_nodes().len(), 3);
+    }
+
+    #[test]
+    fn edge_missing_target() {
+        assert!(parse(r#"{"graph": {"edges": [{"source": "a"}]}}"#).is_err());
+    }
+
+    #[test]
+    fn roundtrip_with_emit() {
+        let input = include_str!("../../../../data/depconv/small.jgf");
+        let graph = parse(input).unwrap();
+        let mut buf = Vec::new();
+        crate::emit::emit(crate::emit::OutputFormat::Json, &graph, &mut buf).unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), input);
+    }
+}
@@ -0,0 +1,354 @@
+use std::collections::HashSet;
+
+use indexmap::IndexMap;
+
+use crate::{DepGraph, Edge, NodeInfo};
+
+/// Parse a line of `mvn dependency:tree` output into (depth, text), or None for blank lines.
+///
+/// Maven's `[INFO] ` log prefix is stripped. Depth 0 is a module.
+fn parse_line(line: &str) -> Option<(usize, &str)> {
+    let line = line
+        .strip_prefix("[INFO]")
+        .map(|l| l.strip_prefix(' ').unwrap_or(l))
+        .unwrap_or(line);
+    let mut depth = 0;
+    let mut rest = line;
+
+    loop {
+        if rest.starts_with("+- ") || rest.starts_with("\\- ") {
+            rest = &rest[3..];
+            depth += 1;
+            break;
+        }
+        if depth_continues(rest) {
+            rest = &rest[3..];
+            depth += 1;
+            continue;
+        }
+        break;
+    }
+
+    let text = rest.trim_end();
+    if text.is_empty() {
+        None
+    } else {
+        Some((depth, text))
+    }
+}
+
+/// A pipe or blank continuation that's followed by more of the tree prefix, rather than by text.
+fn depth_continues(rest: &str) -> bool {
+    (rest.starts_with("|  ") || rest.starts_with("   "))
+        && rest[3..].starts_with(['|', '+', '\\', ' '])
+}
+
+/// Maven coordinates: `groupId:artifactId:type[:classifier]:version[:scope]`.
+#[derive(Debug, PartialEq)]
+struct Coordinates<'a> {
+    name: String,
+    packaging: &'a str,
+    classifier: Option<&'a str>,
+    version: &'a str,
+    scope: Option<&'a str>,
+}
+
+/// Parse coordinates. Modules have no scope, and dependencies always have one.
+fn parse_coordinates(text: &str, has_scope: bool) -> Option<Coordinates<'_>> {
+    if text.contains(char::is_whitespace) {
+        return None;
+    }
+    let parts: Vec<&str> = text.split(':').collect();
+    let (scope, parts) = if has_scope {
+        let (scope, rest) = parts.split_last()?;
+        (Some(*scope), rest)
+    } else {
+        (None, parts.as_slice())
+    };
+    let (group, artifact, packaging, classifier, version) = match parts {
+        [group, artifact, packaging, version] => (group, artifact, packaging, None, version),
+        [group, artifact, packaging, classifier, version] => {
+            (group, artifact, packaging, Some(*classifier), version)
+        }
+        _ => return None,
+    };
+    Some(Coordinates {
+        name: format!("{group}:{artifact}"),
+        packaging,
+        classifier,
+        version,
+        scope,
+    })
+}
+
+/// A dependency line: coordinates, plus what `-Dverbose` adds.
+struct Dependency<'a> {
+    coordinates: Coordinates<'a>,
+    /// The version the dependency was resolved to, if it was omitted for a conflict
+    resolved: Option<&'a str>,
+    /// The version the POM asked for, if dependency management changed it
+    managed_from: Option<&'a str>,
+    optional: bool,
+}
+
+/// Parse a dependency line, including `-Dverbose` annotations like `(optional)`,
+/// `(version managed from 1.0)`, and `(g:a:jar:1.0:compile - omitted for conflict with 2.0)`.
+fn parse_dependency(text: &str) -> Option<Dependency<'_>> {
+    // Omitted dependencies are wrapped in parentheses, with the reason after ` - `
+    if let Some(inner) = text.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+        let (coordinates, reason) = inner.split_once(" - ")?;
+        return Some(Dependency {
+            coordinates: parse_coordinates(coordinates, true)?,
+            resolved: reason.strip_prefix("omitted for conflict with "),
+            managed_from: None,
+            optional: false,
+        });
+    }
+
+    let (coordinates, annotations) = text.split_once(' ').unwrap_or((text, ""));
+    let mut dep = Dependency {
+        coordinates: parse_coordinates(coordinates, true)?,
+        resolved: None,
+        managed_from: None,
+        optional: false,
+    };
+    let annotations = annotations.trim();
+    let annotations = annotations
+        .strip_prefix('(')
+        .and_then(|a| a.strip_suffix(')'))
+        .unwrap_or(annotations);
+    for annotation in annotations.split("; ") {
+        if annotation == "optional" {
+            dep.optional = true;
+        } else if let Some(version) = annotation.strip_prefix("version managed from ") {
+            dep.managed_from = Some(version);
+        }
+    }
+    Some(dep)
+}
+
+fn add_node(graph: &mut DepGraph, coordinates: &Coordinates, version: &str) -> String {
+    let id = format!("{} {version}", coordinates.name);
+    graph.nodes.entry(id.clone()).or_insert_with(|| {
+        let mut attrs = IndexMap::from([("version".to_string(), version.to_string())]);
+        if coordinates.packaging != "jar" {
+            attrs.insert("type".to_string(), coordinates.packaging.to_string());
+        }
+        if let Some(classifier) = coordinates.classifier {
+            attrs.insert("classifier".to_string(), classifier.to_string());
+        }
+        NodeInfo {
+            label: coordinates.name.clone(),
+            node_type: None,
+            attrs,
+        }
+    });
+    id
+}
+
+/// Parse `mvn dependency:tree` output into a [`DepGraph`].
+///
+/// Each artifact becomes a node keyed `groupId:artifactId version`, with the `groupId:artifactId`
+/// as the label and the version in `attrs["version"]`. Packaging types other than `jar` are stored
+/// in `attrs["type"]`, and classifiers in `attrs["classifier"]`. Each module of a multi-module
+/// build is a root. The `[INFO]` log prefix and any log lines around the trees are ignored.
+///
+/// Edges store the dependency scope in a `scope` attr, and `optional = "true"` for optional
+/// dependencies. With `-Dverbose`, dependencies omitted for a version conflict get an edge to the
+/// version that was picked, and the requested version is stored in the edge's `requested` attr,
+/// as is the original version of dependencies whose version was managed. Edges repeated across
+/// modules are only added once.
+pub fn parse(input: &str) -> eyre::Result<DepGraph> {
+    let mut graph = DepGraph::default();
+    // stack[i] = node ID at depth i
+    let mut stack: Vec<String> = Vec::new();
+    // Multi-module builds repeat the subtrees of modules that other modules depend on
+    let mut seen = HashSet::new();
+
+    for line in input.lines() {
+        let Some((depth, text)) = parse_line(line) else {
+            continue;
+        };
+
+        if depth == 0 {
+            stack.clear();
+            if let Some(module) = parse_coordinates(text, false) {
+                let version = module.version;
+                stack.push(add_node(&mut graph, &module, version));
+            }
+            continue;
+        }
+
+        if stack.is_empty() {
+            continue;
+        }
+        if depth > stack.len() {
+            eyre::bail!("unexpected depth jump at line: {text:?}");
+        }
+        stack.truncate(depth);
+
+        let Some(dep) = parse_dependency(text) else {
+            eyre::bail!("unrecognized maven dependency: {text:?}");
+        };
+        let requested = dep.coordinates.version;
+        let version = dep.resolved.unwrap_or(requested);
+        let id = add_node(&mut graph, &dep.coordinates, version);
+
+        let mut attrs = IndexMap::new();
+        if let Some(scope) = dep.coordinates.scope {
+            attrs.insert("scope".to_string(), scope.to_string());
+        }
+        if let Some(requested) = dep
+            .managed_from
+            .or(dep.resolved.is_some().then_some(requested))
+        {
+            attrs.insert("requested".to_string(), requested.to_string());
+        }
+        if dep.optional {
+            attrs.insert("optional".to_string(), "true".to_string());
+        }
+        let from = &stack[depth - 1];
+        if seen.insert((from.clone(), id.clone())) {
+            graph.edges.push(Edge {
+                from: from.clone(),
+                to: id.clone(),
+                label: None,
+                attrs,
+            });
+        }
+
+        stack.push(id);
+    }
+
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type EdgeTuple<'a> = (&'a str, &'a str, Vec<(&'a str, &'a str)>);
+
+    fn edge_tuples(graph: &DepGraph) -> Vec<EdgeTuple<'_>> {
+        graph
+            .edges
+            .iter()
+            .map(|e| {
+                let attrs = e.attrs.iter().map(|(k, v)| (k.as_str(), v.as_str()));
+                (e.from.as_str(), e.to.as_str(), attrs.collect())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn lines() {
+        assert_eq!(
+            parse_line("[INFO] com.example:app:jar:1.0"),
+            Some((0, "com.example:app:jar:1.0"))
+        );
+        assert_eq!(
+            parse_line("[INFO] |  \\- a:b:jar:1.0:compile"),
+            Some((2, "a:b:jar:1.0:compile"))
+        );
+        assert_eq!(
+            parse_line("   \\- a:b:jar:1.0:test"),
+            Some((2, "a:b:jar:1.0:test"))
+        );
+        assert_eq!(parse_line("[INFO] "), None);
+    }
+
+    #[test]
+    fn coordinates() {
+        assert_eq!(
+            parse_coordinates(
+                "io.netty:netty-transport:jar:linux-x86_64:4.1.100:runtime",
+                true
+            ),
+            Some(Coordinates {
+                name: "io.netty:netty-transport".to_string(),
+                packaging: "jar",
+                classifier: Some("linux-x86_64"),
+                version: "4.1.100",
+                scope: Some("runtime"),
+            })
+        );
+        assert_eq!(
+            parse_coordinates("com.example:app:war:1.0-SNAPSHOT", false)
+                .unwrap()
+                .scope,
+            None
+        );
+        assert_eq!(parse_coordinates("BUILD SUCCESS", false), None);
+        assert_eq!(parse_coordinates("com.example:app", false), None);
+    }
+
+    #[test]
+    fn fixture() {
+        let input = include_str!("../../../../data/depconv/mvn-dependency-tree.txt");
+        let graph = parse(input).unwrap();
+
+        let ids: Vec<&str> = graph.nodes.keys().map(|s| s.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "com.example:core 1.0-SNAPSHOT",
+                "org.slf4j:slf4j-api 2.0.9",
+                "com.example:app 1.0-SNAPSHOT",
+                "com.google.guava:guava 32.1.2-jre",
+                "com.google.guava:failureaccess 1.0.1",
+                "com.google.code.findbugs:jsr305 3.0.2",
+                "junit:junit 4.13.2",
+                "org.hamcrest:hamcrest-core 1.3",
+            ]
+        );
+        let app = &graph.nodes["com.example:app 1.0-SNAPSHOT"];
+        assert_eq!(app.label, "com.example:app");
+        assert_eq!(app.attrs["type"], "war");
+
+        assert_eq!(
+            edge_tuples(&graph),
+            vec![
+                (
+                    "com.example:core 1.0-SNAPSHOT",
+                    "org.slf4j:slf4j-api 2.0.9",
+                    vec![("scope", "compile")]
+                ),
+                (
+                    "com.example:app 1.0-SNAPSHOT",
+                    "com.example:core 1.0-SNAPSHOT",
+                    vec![("scope", "compile")]
+                ),
+                (
+                    "com.example:app 1.0-SNAPSHOT",
+                    "com.google.guava:guava 32.1.2-jre",
+                    vec![("scope", "compile"), ("requested", "31.1-jre")]
+                ),
+                (
+                    "com.google.guava:guava 32.1.2-jre",
+                    "com.google.guava:failureaccess 1.0.1",
+                    vec![("scope", "compile")]
+                ),
+                (
+                    "com.google.guava:guava 32.1.2-jre",
+                    "com.google.code.findbugs:jsr305 3.0.2",
+                    vec![("scope", "compile"), ("optional", "true")]
+                ),
+                (
+                    "com.example:app 1.0-SNAPSHOT",
+                    "junit:junit 4.13.2",
+                    vec![("scope", "test")]
+                ),
+                (
+                    "junit:junit 4.13.2",
+                    "org.hamcrest:hamcrest-core 1.3",
+                    vec![("scope", "test")]
+                ),
+                (
+                    "com.example:app 1.0-SNAPSHOT",
+                    "org.slf4j:slf4j-api 2.0.9",
+                    vec![("scope", "compile"), ("requested", "1.7.36")]
+                ),
+            ]
+        );
+    }
+}
@@ -1,18 +1,43 @@
+mod bazel_query;
 mod cargo_metadata;
 mod cargo_tree;
+mod cmake_file_api;
+mod compile_commands;
+mod csv;
+pub(crate) mod cyclonedx;
 mod depfile;
 #[cfg(feature = "dot")]
 pub(crate) mod dot;
+mod dpkg;
+mod elf;
+mod go_list;
+mod go_mod_graph;
+mod gradle;
+mod graphml;
+pub(crate) mod json;
+mod maven;
 mod mermaid;
+mod ninja;
+mod npm_lock;
 mod pathlist;
+mod pipdeptree;
+mod plantuml;
+mod pnpm_lock;
+mod poetry_lock;
+mod rust_modules;
+mod spdx;
 mod style;
 mod tgf;
 mod tree;
+mod uv_lock;
 
 use std::fmt;
-use std::path::Path;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 
 use clap::ValueEnum;
+use csvizmo_utils::stdio::get_input_reader;
+use eyre::WrapErr;
 
 use crate::DepGraph;
 
@@ -20,8 +45,33 @@ use crate::DepGraph;
 #[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
 pub enum InputFormat {
     CargoMetadata,
+    Json,
+    #[value(name = "cyclonedx")]
+    CycloneDx,
+    Spdx,
+    CmakeFileApi,
+    CompileCommands,
+    GoList,
+    BazelQuery,
+    NpmLock,
+    Pipdeptree,
+    #[value(name = "graphml")]
+    GraphMl,
+    PnpmLock,
+    UvLock,
+    PoetryLock,
+    RustModules,
     Mermaid,
+    #[value(name = "plantuml")]
+    PlantUml,
     Dot,
+    Ninja,
+    GoModGraph,
+    Elf,
+    Gradle,
+    Maven,
+    Dpkg,
+    Csv,
     Tgf,
     Depfile,
     CargoTree,
@@ -45,10 +95,15 @@ impl TryFrom<&Path> for InputFormat {
             .ok_or_else(|| eyre::eyre!("no file extension: {}", path.display()))?;
         match ext {
             "dot" | "gv" => Ok(Self::Dot),
+            "graphml" => Ok(Self::GraphMl),
             "mmd" | "mermaid" => Ok(Self::Mermaid),
+            "puml" | "plantuml" => Ok(Self::PlantUml),
             "tgf" => Ok(Self::Tgf),
+            "csv" => Ok(Self::Csv),
             "d" => Ok(Self::Depfile),
-            "json" => Ok(Self::CargoMetadata),
+            "jgf" => Ok(Self::Json),
+            // Several formats share .json; leave it to content detection
+            "json" => eyre::bail!("ambiguous file extension: .{ext}"),
             _ => eyre::bail!("unrecognized dependency graph file extension: .{ext}"),
         }
     }
@@ -68,12 +123,70 @@ fn normalize_node_type(raw: &str) -> String {
     }
 }
 
+/// Read the input text from a file, or from stdin if `path` is `None`.
+///
+/// Directories are read according to the explicit `format`, or [`directory_format`] if there
+/// isn't one. The sources of a crate are concatenated for [`InputFormat::RustModules`]. For
+/// [`InputFormat::CompileCommands`] and [`InputFormat::Depfile`], the directory is searched
+/// recursively for `.d` depfiles, which are concatenated in sorted order to merge into one include
+/// graph. No other format can be read from a directory.
+pub fn read_input(path: Option<&Path>, format: Option<InputFormat>) -> eyre::Result<String> {
+    let mut text = String::new();
+    let Some(dir) = path.filter(|p| p.is_dir()) else {
+        let mut reader = get_input_reader(&path.map(Path::to_path_buf))?;
+        reader.read_to_string(&mut text)?;
+        return Ok(text);
+    };
+    match format.unwrap_or_else(|| directory_format(dir)) {
+        InputFormat::RustModules => return rust_modules::read_crate(dir),
+        InputFormat::CompileCommands | InputFormat::Depfile => {}
+        format => eyre::bail!("cannot read {format} input from a directory: {dir:?}"),
+    }
+
+    let mut depfiles = Vec::new();
+    find_depfiles(dir, &mut depfiles)?;
+    depfiles.sort();
+    tracing::info!("Found {} depfiles in {}", depfiles.len(), dir.display());
+    for depfile in depfiles {
+        let contents = std::fs::read_to_string(&depfile)
+            .wrap_err(format!("Failed to read depfile: {depfile:?}"))?;
+        text.push_str(&contents);
+        if !text.ends_with('\n') {
+            text.push('\n');
+        }
+    }
+    Ok(text)
+}
+
+/// The format of a directory input: a crate, if it or its `src/` has a `lib.rs` or `main.rs`, and
+/// a build tree otherwise.
+fn directory_format(dir: &Path) -> InputFormat {
+    if rust_modules::source_dir(dir).is_some() {
+        InputFormat::RustModules
+    } else {
+        InputFormat::CompileCommands
+    }
+}
+
+fn find_depfiles(dir: &Path, depfiles: &mut Vec<PathBuf>) -> eyre::Result<()> {
+    for entry in std::fs::read_dir(dir).wrap_err(format!("Failed to read directory: {dir:?}"))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            find_depfiles(&path, depfiles)?;
+        } else if path.extension().is_some_and(|ext| ext == "d") {
+            depfiles.push(path);
+        }
+    }
+    Ok(())
+}
+
 /// Resolve input format using explicit flag, file extension, or content detection.
 ///
 /// Resolution order:
 /// 1. Explicit flag if provided
-/// 2. File extension if path is available
-/// 3. Content detection from input string
+/// 2. [`directory_format`] if the path is a directory
+/// 3. File extension if path is available
+/// 4. Content detection from input string
 ///
 /// Returns an error if format cannot be determined.
 pub fn resolve_input_format(
@@ -84,6 +197,11 @@ pub fn resolve_input_format(
     if let Some(f) = flag {
         return Ok(f);
     }
+    if let Some(dir) = path.filter(|p| p.is_dir()) {
+        let f = directory_format(dir);
+        tracing::info!("Detected input format: {f:?} from directory input");
+        return Ok(f);
+    }
     let ext_err = match path.map(InputFormat::try_from) {
         Some(Ok(f)) => {
             tracing::info!("Detected input format: {f:?} from file extension");
@@ -108,6 +226,27 @@ pub fn parse(format: InputFormat, input: &str) -> eyre::Result<DepGraph> {
         InputFormat::Dot => dot::parse(input),
         #[cfg(not(feature = "dot"))]
         InputFormat::Dot => eyre::bail!("'dot' feature not enabled to maintain MIT license"),
+        InputFormat::GraphMl => graphml::parse(input),
+        InputFormat::Json => json::parse(input),
+        InputFormat::CycloneDx => cyclonedx::parse(input),
+        InputFormat::Spdx => spdx::parse(input),
+        InputFormat::CmakeFileApi => cmake_file_api::parse(input),
+        InputFormat::CompileCommands => compile_commands::parse(input),
+        InputFormat::Ninja => ninja::parse(input),
+        InputFormat::GoModGraph => go_mod_graph::parse(input),
+        InputFormat::GoList => go_list::parse(input),
+        InputFormat::BazelQuery => bazel_query::parse(input),
+        InputFormat::Elf => elf::parse(input),
+        InputFormat::Gradle => gradle::parse(input),
+        InputFormat::Maven => maven::parse(input),
+        InputFormat::Dpkg => dpkg::parse(input),
+        InputFormat::NpmLock => npm_lock::parse(input),
+        InputFormat::PnpmLock => pnpm_lock::parse(input),
+        InputFormat::Pipdeptree => pipdeptree::parse(input),
+        InputFormat::UvLock => uv_lock::parse(input),
+        InputFormat::PoetryLock => poetry_lock::parse(input),
+        InputFormat::RustModules => rust_modules::parse(input),
+        InputFormat::Csv => csv::parse(input),
         InputFormat::Tgf => tgf::parse(input),
         InputFormat::Depfile => depfile::parse(input),
         InputFormat::Pathlist => pathlist::parse(input),
@@ -115,6 +254,7 @@ pub fn parse(format: InputFormat, input: &str) -> eyre::Result<DepGraph> {
         InputFormat::CargoTree => cargo_tree::parse(input),
         InputFormat::CargoMetadata => cargo_metadata::parse(input),
         InputFormat::Mermaid => mermaid::parse(input),
+        InputFormat::PlantUml => plantuml::parse(input),
     }?;
 
     style::apply_default_styles(&mut graph);
@@ -0,0 +1,195 @@
+use crate::{DepGraph, Edge, NodeInfo};
+
+/// Parse ninja tool output into a [`DepGraph`].
+///
+/// The input may be any concatenation of:
+/// - `ninja -t deps`: a `target: #deps N, deps mtime M (STATE)` header followed by indented
+///   dependency paths. Each dependency becomes an edge from target -> dependency. These are the
+///   implicit (e.g. header) dependencies that `ninja -t graph` does not show.
+/// - `ninja -t targets all`: `target: rule` lines. The rule becomes the target's `node_type`.
+/// - `.ninja_log`: tab-separated `start end mtime output hash` lines. The build time of each
+///   output, in milliseconds, becomes its `duration` attr. If an output was built more than once,
+///   the last entry wins, like ninja itself.
+///
+/// The sections can appear in any order, so one graph can be built with
+/// `{ ninja -t targets all; ninja -t deps; cat .ninja_log; } | depconv -I ninja`.
+pub fn parse(input: &str) -> eyre::Result<DepGraph> {
+    let mut graph = DepGraph::default();
+    // The target of the `ninja -t deps` block we're in, if any
+    let mut deps_target: Option<String> = None;
+
+    for line in input.lines() {
+        if line.trim().is_empty() {
+            deps_target = None;
+            continue;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+
+        if line.starts_with(char::is_whitespace) {
+            let Some(target) = &deps_target else {
+                eyre::bail!("dependency outside of a '#deps' block: {line:?}");
+            };
+            let dep = line.trim();
+            ensure_node(&mut graph, dep);
+            graph.edges.push(Edge {
+                from: target.clone(),
+                to: dep.to_string(),
+                ..Default::default()
+            });
+            continue;
+        }
+        deps_target = None;
+
+        if let Some((output, duration)) = parse_log_entry(line) {
+            ensure_node(&mut graph, output)
+                .attrs
+                .insert("duration".to_string(), duration.to_string());
+        } else if let Some((target, _)) = line.split_once(": #deps ") {
+            ensure_node(&mut graph, target);
+            deps_target = Some(target.to_string());
+        } else if let Some((target, rule)) = line.rsplit_once(": ") {
+            ensure_node(&mut graph, target).node_type = Some(rule.trim().to_string());
+        } else {
+            eyre::bail!("unrecognized ninja output line: {line:?}");
+        }
+    }
+
+    Ok(graph)
+}
+
+/// Parse a `.ninja_log` entry into its output path and duration in milliseconds.
+fn parse_log_entry(line: &str) -> Option<(&str, u64)> {
+    let mut fields = line.split('\t');
+    let start: u64 = fields.next()?.parse().ok()?;
+    let end: u64 = fields.next()?.parse().ok()?;
+    let _mtime = fields.next()?;
+    let output = fields.next()?;
+    let _hash = fields.next()?;
+    if fields.next().is_some() {
+        return None;
+    }
+    Some((output, end.saturating_sub(start)))
+}
+
+fn ensure_node<'g>(graph: &'g mut DepGraph, id: &str) -> &'g mut NodeInfo {
+    graph
+        .nodes
+        .entry(id.to_string())
+        .or_insert_with(|| NodeInfo::new(id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_input() {
+        let graph = parse("").unwrap();
+        assert!(graph.nodes.is_empty());
+        assert!(graph.edges.is_empty());
+    }
+
+    #[test]
+    fn deps() {
+        let input = "\
+foo.o: #deps 2, deps mtime 1700000000 (VALID)
+    ../src/foo.cpp
+    ../include/foo.h
+
+bar.o: #deps 2, deps mtime 1700000001 (STALE)
+    ../src/bar.cpp
+    ../include/foo.h
+
+";
+        let graph = parse(input).unwrap();
+        let ids: Vec<&str> = graph.nodes.keys().map(|s| s.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "foo.o",
+                "../src/foo.cpp",
+                "../include/foo.h",
+                "bar.o",
+                "../src/bar.cpp"
+            ]
+        );
+        let edges: Vec<(&str, &str)> = graph
+            .edges
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str()))
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                ("foo.o", "../src/foo.cpp"),
+                ("foo.o", "../include/foo.h"),
+                ("bar.o", "../src/bar.cpp"),
+                ("bar.o", "../include/foo.h"),
+            ]
+        );
+    }
+
+    #[test]
+    fn targets_set_rule_as_node_type() {
+        let input =
+            "foo.o: CXX_COMPILER__foo_Debug\nfoo: CXX_EXECUTABLE_LINKER__foo_Debug\nall: phony\n";
+        let graph = parse(input).unwrap();
+        assert_eq!(graph.nodes.len(), 3);
+        assert!(graph.edges.is_empty());
+        assert_eq!(
+            graph.nodes["foo.o"].node_type.as_deref(),
+            Some("CXX_COMPILER__foo_Debug")
+        );
+        assert_eq!(graph.nodes["all"].node_type.as_deref(), Some("phony"));
+    }
+
+    #[test]
+    fn windows_drive_letter_in_target() {
+        let graph = parse("C:/build/foo.obj: cxx\n").unwrap();
+        assert_eq!(
+            graph.nodes["C:/build/foo.obj"].node_type.as_deref(),
+            Some("cxx")
+        );
+    }
+
+    #[test]
+    fn log_durations() {
+        let input = "\
+# ninja log v5
+0\t120\t1700000000000000000\tfoo.o\t1a2b3c4d5e6f7a8b
+5\t80\t1700000000000000000\tbar.o\t8b7a6f5e4d3c2b1a
+200\t250\t1700000000000000000\tfoo.o\t1a2b3c4d5e6f7a8b
+";
+        let graph = parse(input).unwrap();
+        assert_eq!(graph.nodes.len(), 2);
+        assert_eq!(graph.nodes["foo.o"].attrs["duration"], "50");
+        assert_eq!(graph.nodes["bar.o"].attrs["duration"], "75");
+    }
+
+    #[test]
+    fn combined() {
+        let input = include_str!("../../../../data/depconv/small.ninja");
+        let graph = parse(input).unwrap();
+        let foo = &graph.nodes["CMakeFiles/app.dir/foo.cpp.o"];
+        assert_eq!(foo.node_type.as_deref(), Some("CXX_COMPILER__app_Debug"));
+        assert_eq!(foo.attrs["duration"], "1532");
+        assert!(
+            graph
+                .edges
+                .iter()
+                .any(|e| e.from == "CMakeFiles/app.dir/foo.cpp.o" && e.to == "../include/foo.h")
+        );
+    }
+
+    #[test]
+    fn dep_outside_block() {
+        assert!(parse("    ../src/foo.cpp\n").is_err());
+    }
+
+    #[test]
+    fn garbage_line() {
+        assert!(parse("this is not ninja output\n").is_err());
+    }
+}
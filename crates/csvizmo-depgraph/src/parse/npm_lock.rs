@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::{DepGraph, Edge, NodeInfo};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Lockfile {
+    lockfile_version: Option<u32>,
+    packages: Option<IndexMap<String, Package>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Package {
+    name: Option<String>,
+    version: Option<String>,
+    #[serde(default)]
+    link: bool,
+    resolved: Option<String>,
+    #[serde(default)]
+    dependencies: IndexMap<String, String>,
+    #[serde(default)]
+    dev_dependencies: IndexMap<String, String>,
+    #[serde(default)]
+    optional_dependencies: IndexMap<String, String>,
+    #[serde(default)]
+    peer_dependencies: IndexMap<String, String>,
+}
+
+/// The package name for a `packages` key, e.g. `node_modules/a/node_modules/@scope/b` ->
+/// `@scope/b`.
+fn name_from_location(location: &str) -> &str {
+    match location.rfind("node_modules/") {
+        Some(i) => &location[i + "node_modules/".len()..],
+        None => location.rsplit('/').next().unwrap_or(location),
+    }
+}
+
+/// Resolve `name` as required from the package at `location`, the same way Node does: look in
+/// `<dir>/node_modules/<name>` for each ancestor directory, nearest first.
+fn resolve<'a>(
+    packages: &'a IndexMap<String, Package>,
+    location: &str,
+    name: &str,
+) -> Option<&'a str> {
+    let mut dir = location;
+    loop {
+        let candidate = if dir.is_empty() {
+            format!("node_modules/{name}")
+        } else {
+            format!("{dir}/node_modules/{name}")
+        };
+        if let Some((key, package)) = packages.get_key_value(&candidate) {
+            // Workspace packages are symlinked into node_modules
+            return match (package.link, &package.resolved) {
+                (true, Some(target)) => packages.get_key_value(target).map(|(k, _)| k.as_str()),
+                _ => Some(key.as_str()),
+            };
+        }
+        if dir.is_empty() {
+            return None;
+        }
+        dir = dir.rsplit_once('/').map_or("", |(parent, _)| parent);
+    }
+}
+
+/// Parse an npm `package-lock.json` (lockfile version 2 or 3) into a [`DepGraph`].
+///
+/// Each installed package becomes a node keyed `name version`, labeled with its name, and with
+/// its version in `attrs["version"]`. Copies of the same package installed at several
+/// `node_modules` locations are merged into one node. Each dependency is resolved the same way
+/// Node resolves it, and becomes an edge with a `kind` attr of `normal`, `dev`, `optional`, or
+/// `peer` (comma-joined if a dependency is listed more than once). Dependencies that aren't
+/// installed, like optional platform-specific packages or missing peers, are skipped.
+pub fn parse(input: &str) -> eyre::Result<DepGraph> {
+    let lockfile: Lockfile = serde_json::from_str(input)?;
+    let Some(packages) = lockfile.packages else {
+        eyre::bail!(
+            "package-lock.json version {} is not supported; only versions 2 and 3 have 'packages'",
+            lockfile.lockfile_version.unwrap_or(1)
+        );
+    };
+
+    let mut graph = DepGraph::default();
+    let mut ids: HashMap<&str, String> = HashMap::new();
+    for (location, package) in &packages {
+        if package.link {
+            continue;
+        }
+        let name = package
+            .name
+            .as_deref()
+            .unwrap_or_else(|| name_from_location(location));
+        let mut attrs = IndexMap::new();
+        let id = match &package.version {
+            Some(version) => {
+                attrs.insert("version".to_string(), version.clone());
+                format!("{name} {version}")
+            }
+            None => name.to_string(),
+        };
+        graph.nodes.entry(id.clone()).or_insert_with(|| NodeInfo {
+            label: name.to_string(),
+            node_type: None,
+            attrs,
+        });
+        ids.insert(location, id);
+    }
+
+    let mut edges: IndexMap<(String, String), Vec<&str>> = IndexMap::new();
+    for (location, package) in &packages {
+        let Some(from) = ids.get(location.as_str()) else {
+            continue;
+        };
+        let sections = [
+            (&package.dependencies, "normal"),
+            (&package.dev_dependencies, "dev"),
+            (&package.optional_dependencies, "optional"),
+            (&package.peer_dependencies, "peer"),
+        ];
+        for (deps, kind) in sections {
+            for name in deps.keys() {
+                let Some(to) = resolve(&packages, location, name).and_then(|l| ids.get(l)) else {
+                    tracing::debug!("{location:?} dependency {name:?} is not installed");
+                    continue;
+                };
+                let kinds = edges.entry((from.clone(), to.clone())).or_default();
+                if !kinds.contains(&kind) {
+                    kinds.push(kind);
+                }
+            }
+        }
+    }
+
+    graph.edges = edges
+        .into_iter()
+        .map(|((from, to), kinds)| Edge {
+            from,
+            to,
+            label: None,
+            attrs: IndexMap::from([("kind".to_string(), kinds.join(","))]),
+        })
+        .collect();
+
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edge_tuples(graph: &DepGraph) -> Vec<(&str, &str, &str)> {
+        graph
+            .edges
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str(), e.attrs["kind"].as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn v1_not_supported() {
+        let input = r#"{"name": "app", "lockfileVersion": 1, "dependencies": {}}"#;
+        let Err(err) = parse(input) else {
+            panic!("expected an error");
+        };
+        assert!(err.to_string().contains("version 1"));
+    }
+
+    #[test]
+    fn fixture() {
+        let input = include_str!("../../../../data/depconv/package-lock.json");
+        let graph = parse(input).unwrap();
+
+        let ids: Vec<&str> = graph.nodes.keys().map(|s| s.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "app 1.0.0",
+                "@scope/util 2.1.0",
+                "ms 2.0.0",
+                "debug 4.3.4",
+                "fsevents 2.3.3",
+                "ms 2.1.2",
+                "react 18.2.0",
+                "typescript 5.3.3",
+                "ui 0.1.0",
+            ]
+        );
+        assert_eq!(graph.nodes["@scope/util 2.1.0"].label, "@scope/util");
+        assert_eq!(graph.nodes["ms 2.0.0"].attrs["version"], "2.0.0");
+
+        assert_eq!(
+            edge_tuples(&graph),
+            vec![
+                ("app 1.0.0", "@scope/util 2.1.0", "normal"),
+                ("app 1.0.0", "debug 4.3.4", "normal"),
+                ("app 1.0.0", "ui 0.1.0", "normal"),
+                ("app 1.0.0", "typescript 5.3.3", "dev"),
+                ("app 1.0.0", "fsevents 2.3.3", "optional"),
+                ("@scope/util 2.1.0", "ms 2.0.0", "normal"),
+                ("debug 4.3.4", "ms 2.1.2", "normal"),
+                ("ui 0.1.0", "debug 4.3.4", "normal"),
+                ("ui 0.1.0", "react 18.2.0", "normal,peer"),
+            ]
+        );
+    }
+}
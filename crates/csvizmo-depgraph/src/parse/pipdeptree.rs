@@ -0,0 +1,166 @@
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::{DepGraph, Edge, NodeInfo};
+
+#[derive(Deserialize)]
+struct Package {
+    key: String,
+    package_name: String,
+    installed_version: String,
+    required_version: Option<String>,
+    #[serde(default)]
+    dependencies: Vec<Package>,
+}
+
+impl Package {
+    fn id(&self) -> String {
+        format!("{} {}", self.key, self.installed_version)
+    }
+}
+
+fn add_package(graph: &mut DepGraph, package: &Package) -> String {
+    let id = package.id();
+    if graph.nodes.contains_key(&id) {
+        return id;
+    }
+    graph.nodes.insert(
+        id.clone(),
+        NodeInfo {
+            label: package.package_name.clone(),
+            node_type: None,
+            attrs: IndexMap::from([("version".to_string(), package.installed_version.clone())]),
+        },
+    );
+    for dep in &package.dependencies {
+        let to = add_package(graph, dep);
+        let label = dep
+            .required_version
+            .as_deref()
+            .filter(|v| *v != "Any")
+            .map(String::from);
+        graph.edges.push(Edge {
+            from: id.clone(),
+            to,
+            label,
+            ..Default::default()
+        });
+    }
+    id
+}
+
+/// Parse `pipdeptree --json-tree` output into a [`DepGraph`].
+///
+/// Each installed package becomes a node keyed `name version`, where `name` is pipdeptree's
+/// normalized `key`. The label is the package name as published, and the version is stored in
+/// `attrs["version"]`. Each requirement becomes an edge labeled with its version specifier, or
+/// unlabeled if the requirement has no specifier.
+///
+/// pipdeptree repeats the subtree of a package every time it's required; each package's
+/// dependencies are only added the first time it's seen.
+pub fn parse(input: &str) -> eyre::Result<DepGraph> {
+    let roots: Vec<Package> = serde_json::from_str(input)?;
+    let mut graph = DepGraph::default();
+    for root in &roots {
+        add_package(&mut graph, root);
+    }
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_input() {
+        let graph = parse("[]").unwrap();
+        assert!(graph.nodes.is_empty());
+    }
+
+    #[test]
+    fn not_a_tree() {
+        assert!(parse(r#"{"key": "flask"}"#).is_err());
+    }
+
+    #[test]
+    fn json_tree() {
+        let input = r#"[
+    {
+        "key": "flask",
+        "package_name": "Flask",
+        "installed_version": "3.0.0",
+        "required_version": "3.0.0",
+        "dependencies": [
+            {
+                "key": "click",
+                "package_name": "click",
+                "installed_version": "8.1.7",
+                "required_version": ">=8.1.3",
+                "dependencies": []
+            },
+            {
+                "key": "jinja2",
+                "package_name": "Jinja2",
+                "installed_version": "3.1.2",
+                "required_version": ">=3.1.2",
+                "dependencies": [
+                    {
+                        "key": "markupsafe",
+                        "package_name": "MarkupSafe",
+                        "installed_version": "2.1.3",
+                        "required_version": ">=2.0",
+                        "dependencies": []
+                    }
+                ]
+            }
+        ]
+    },
+    {
+        "key": "werkzeug",
+        "package_name": "Werkzeug",
+        "installed_version": "3.0.1",
+        "required_version": "3.0.1",
+        "dependencies": [
+            {
+                "key": "markupsafe",
+                "package_name": "MarkupSafe",
+                "installed_version": "2.1.3",
+                "required_version": "Any",
+                "dependencies": []
+            }
+        ]
+    }
+]"#;
+        let graph = parse(input).unwrap();
+
+        let ids: Vec<&str> = graph.nodes.keys().map(|s| s.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "flask 3.0.0",
+                "click 8.1.7",
+                "jinja2 3.1.2",
+                "markupsafe 2.1.3",
+                "werkzeug 3.0.1"
+            ]
+        );
+        let jinja = &graph.nodes["jinja2 3.1.2"];
+        assert_eq!(jinja.label, "Jinja2");
+        assert_eq!(jinja.attrs["version"], "3.1.2");
+
+        let edges: Vec<(&str, &str, Option<&str>)> = graph
+            .edges
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str(), e.label.as_deref()))
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                ("flask 3.0.0", "click 8.1.7", Some(">=8.1.3")),
+                ("jinja2 3.1.2", "markupsafe 2.1.3", Some(">=2.0")),
+                ("flask 3.0.0", "jinja2 3.1.2", Some(">=3.1.2")),
+                ("werkzeug 3.0.1", "markupsafe 2.1.3", None),
+            ]
+        );
+    }
+}
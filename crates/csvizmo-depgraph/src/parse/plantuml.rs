@@ -0,0 +1,821 @@
+use std::collections::HashMap;
+
+use indexmap::IndexMap;
+
+use crate::{DepGraph, Edge, NodeInfo};
+
+/// Element keywords of component, deployment, class, and usecase diagrams.
+///
+/// Multi-word keywords come before their prefixes.
+const ELEMENT_KEYWORDS: &[&str] = &[
+    "abstract class",
+    "abstract",
+    "actor",
+    "agent",
+    "annotation",
+    "artifact",
+    "boundary",
+    "card",
+    "circle",
+    "class",
+    "cloud",
+    "collections",
+    "component",
+    "control",
+    "database",
+    "entity",
+    "enum",
+    "exception",
+    "file",
+    "folder",
+    "frame",
+    "hexagon",
+    "interface",
+    "label",
+    "metaclass",
+    "namespace",
+    "node",
+    "package",
+    "person",
+    "protocol",
+    "queue",
+    "rectangle",
+    "stack",
+    "storage",
+    "struct",
+    "usecase",
+];
+
+/// Class diagram keywords, whose `{ ... }` blocks hold members rather than nested elements.
+const CLASS_KEYWORDS: &[&str] = &[
+    "abstract class",
+    "abstract",
+    "annotation",
+    "class",
+    "entity",
+    "enum",
+    "exception",
+    "interface",
+    "metaclass",
+    "protocol",
+    "struct",
+];
+
+/// Directives that don't describe elements or relationships.
+const IGNORED_DIRECTIVES: &[&str] = &[
+    "!",
+    "@",
+    "allowmixing",
+    "autonumber",
+    "caption",
+    "footer",
+    "header",
+    "hide",
+    "legend",
+    "newpage",
+    "note",
+    "remove",
+    "restore",
+    "scale",
+    "set",
+    "show",
+    "skinparam",
+    "sprite",
+    "title",
+];
+
+/// Lines being skipped over, until the end of a multi-line block.
+enum Skip {
+    /// A `/' ... '/` block comment
+    Comment,
+    /// A block like `note ... end note`, ended by a line starting with the given keyword (with
+    /// whitespace removed)
+    Until(&'static str),
+    /// A class body or `skinparam` block, with the given number of unclosed braces
+    Braces(usize),
+}
+
+/// Parse an element name at the start of `s`: a quoted string, `[component]`, `:actor:`,
+/// `(usecase)`, `() interface`, or a bare identifier.
+///
+/// Returns the name, the element type implied by its delimiters, and the rest of `s`.
+fn parse_name(s: &str) -> Option<(&str, Option<&'static str>, &str)> {
+    let delimited = |open: char, close: char, node_type: Option<&'static str>| {
+        let rest = &s[open.len_utf8()..];
+        let end = rest.find(close)?;
+        Some((
+            rest[..end].trim(),
+            node_type,
+            &rest[end + close.len_utf8()..],
+        ))
+    };
+    match s.chars().next()? {
+        '"' => delimited('"', '"', None),
+        '[' => delimited('[', ']', Some("component")),
+        ':' => delimited(':', ':', Some("actor")),
+        '(' if s.starts_with("()") => {
+            let (name, _, rest) = parse_name(s[2..].trim_start())?;
+            Some((name, Some("interface"), rest))
+        }
+        '(' => delimited('(', ')', Some("usecase")),
+        _ => {
+            // Dots separate packages, but `..` starts an arrow
+            let mut end = 0;
+            let mut chars = s.char_indices().peekable();
+            while let Some((i, c)) = chars.next() {
+                let next = chars.peek().map(|&(_, n)| n);
+                let is_name = c.is_alphanumeric()
+                    || matches!(c, '_' | '$')
+                    || (c == '.' && next.is_some_and(|n| n.is_alphanumeric() || n == '_'));
+                if !is_name {
+                    break;
+                }
+                end = i + c.len_utf8();
+            }
+            (end > 0).then(|| (&s[..end], None, &s[end..]))
+        }
+    }
+}
+
+/// Parse a quoted string at the start of `s`, returning it and the rest of `s`.
+fn parse_quoted(s: &str) -> Option<(&str, &str)> {
+    let rest = s.strip_prefix('"')?;
+    let end = rest.find('"')?;
+    Some((&rest[..end], &rest[end + 1..]))
+}
+
+/// An arrow like `-->`, `..|>`, `*--`, `<-up-`, or `-[#red,dashed]->`.
+#[derive(Debug, PartialEq)]
+struct Arrow<'a> {
+    left: &'a str,
+    right: &'a str,
+    dashed: bool,
+    /// Hidden arrows only affect layout
+    hidden: bool,
+}
+
+impl Arrow<'_> {
+    /// Whether the arrow points from its right end to its left end.
+    fn is_reversed(&self) -> bool {
+        // Arrowheads mark the target, and diamonds (`*` and `o`) mark the owner
+        (self.right.is_empty() && matches!(self.left, "<|" | "<" | "^"))
+            || (self.left.is_empty() && matches!(self.right, "*" | "o" | "#" | "+"))
+    }
+
+    /// The relationship the arrow style describes, in UML terms.
+    fn kind(&self) -> &'static str {
+        let heads = [self.left, self.right];
+        if heads.iter().any(|h| matches!(*h, "<|" | "|>" | "^")) {
+            if self.dashed { "implements" } else { "extends" }
+        } else if heads.contains(&"*") {
+            "composition"
+        } else if heads.contains(&"o") {
+            "aggregation"
+        } else if self.dashed {
+            "dependency"
+        } else {
+            "association"
+        }
+    }
+}
+
+/// Parse an arrow at the start of `s`, returning it and the rest of `s`.
+fn parse_arrow(s: &str) -> Option<(Arrow<'_>, &str)> {
+    const LEFT_HEADS: &[&str] = &["<|", "<", "*", "o", "#", "+", "^"];
+    const RIGHT_HEADS: &[&str] = &["|>", ">", "*", "o", "#", "+", "^"];
+    const DIRECTIONS: &[&str] = &["up", "down", "left", "right", "u", "d", "l", "r"];
+
+    let left = LEFT_HEADS
+        .iter()
+        .find(|h| s.starts_with(**h))
+        .map_or("", |h| *h);
+    let mut rest = &s[left.len()..];
+    let mut body = 0;
+    let mut dashed = false;
+    let mut hidden = false;
+    loop {
+        if let Some(r) = rest.strip_prefix(['-', '.']) {
+            dashed |= rest.starts_with('.');
+            body += 1;
+            rest = r;
+            continue;
+        }
+        if body == 0 {
+            break;
+        }
+        if let Some(r) = rest.strip_prefix('[') {
+            let end = r.find(']')?;
+            let options = &r[..end];
+            hidden |= options.contains("hidden");
+            dashed |= options.contains("dashed") || options.contains("dotted");
+            rest = &r[end + 1..];
+            continue;
+        }
+        let word_end = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        if DIRECTIONS.contains(&&rest[..word_end]) && rest[word_end..].starts_with(['-', '.']) {
+            rest = &rest[word_end..];
+            continue;
+        }
+        break;
+    }
+    if body == 0 {
+        return None;
+    }
+
+    let mut right = RIGHT_HEADS
+        .iter()
+        .find(|h| rest.starts_with(**h))
+        .map_or("", |h| *h);
+    // An `o` followed by a name is the start of the name, rather than an aggregation
+    if right == "o" && rest[1..].starts_with(|c: char| c.is_alphanumeric()) {
+        right = "";
+    }
+    let rest = &rest[right.len()..];
+    let arrow = Arrow {
+        left,
+        right,
+        dashed,
+        hidden,
+    };
+    Some((arrow, rest))
+}
+
+/// A relationship line, like `A "1" *-- "many" B : contains`.
+struct Relationship<'a> {
+    from: (&'a str, Option<&'static str>),
+    to: (&'a str, Option<&'static str>),
+    arrow: Arrow<'a>,
+    from_multiplicity: Option<&'a str>,
+    to_multiplicity: Option<&'a str>,
+    label: Option<&'a str>,
+}
+
+fn parse_relationship(line: &str) -> Option<Relationship<'_>> {
+    let (from, from_type, rest) = parse_name(line)?;
+    let rest = rest.trim_start();
+    let (from_multiplicity, rest) = match parse_quoted(rest) {
+        Some((m, r)) => (Some(m), r.trim_start()),
+        None => (None, rest),
+    };
+    let (arrow, rest) = parse_arrow(rest)?;
+    let rest = rest.trim_start();
+
+    // A quoted string is a multiplicity if it's followed by the name it applies to
+    let (to_multiplicity, rest) = match parse_quoted(rest) {
+        Some((m, r))
+            if !r.trim_start().starts_with(':') && parse_name(r.trim_start()).is_some() =>
+        {
+            (Some(m), r.trim_start())
+        }
+        _ => (None, rest),
+    };
+    let (to, to_type, rest) = parse_name(rest)?;
+
+    // Labels can end with a `<` or `>` marking the direction to read them in
+    let label = rest
+        .trim()
+        .strip_prefix(':')
+        .map(|l| l.trim().trim_matches(['<', '>']).trim())
+        .filter(|l| !l.is_empty());
+    Some(Relationship {
+        from: (from, from_type),
+        to: (to, to_type),
+        arrow,
+        from_multiplicity,
+        to_multiplicity,
+        label,
+    })
+}
+
+/// An element declaration, like `component [Web Server] as web <<service>>`.
+struct Declaration<'a> {
+    keyword: &'static str,
+    id: &'a str,
+    label: &'a str,
+    /// The name of an aliased element, which relationships may use instead of its alias
+    aliased_name: Option<&'a str>,
+    stereotype: Option<&'a str>,
+    /// Classes named by `extends` and `implements` clauses
+    parents: Vec<(&'a str, &'static str)>,
+    opens_block: bool,
+}
+
+fn parse_declaration<'a>(keyword: &'static str, rest: &'a str) -> Option<Declaration<'a>> {
+    let rest = rest.trim_start();
+    let is_display_name = rest.starts_with(['"', '[', ':', '(']);
+    let (name, _, mut rest) = parse_name(rest)?;
+    // Skip generic parameters, like `class Foo<T>`
+    if rest.starts_with('<') && !rest.starts_with("<<") {
+        rest = &rest[rest.find('>')? + 1..];
+    }
+
+    let mut id = name;
+    let mut label = name;
+    let mut aliased_name = None;
+    let trimmed = rest.trim_start();
+    if let Some(after) = trimmed.strip_prefix("as ") {
+        let after = after.trim_start();
+        let alias_is_display_name = after.starts_with('"');
+        let (alias, _, r) = parse_name(after)?;
+        if is_display_name || !alias_is_display_name {
+            id = alias;
+            aliased_name = Some(name);
+        } else {
+            label = alias;
+        }
+        rest = r;
+    }
+
+    let stereotype = rest.find("<<").and_then(|start| {
+        let inner = &rest[start + 2..];
+        inner.find(">>").map(|end| inner[..end].trim())
+    });
+
+    let mut parents = Vec::new();
+    let mut relation = None;
+    for token in rest.split_whitespace() {
+        match token {
+            "extends" => relation = Some("extends"),
+            "implements" => relation = Some("implements"),
+            _ if token.starts_with(['{', '#', '<', '[']) => relation = None,
+            _ => {
+                if let Some(kind) = relation {
+                    let names = token.split(',').filter(|p| !p.is_empty());
+                    parents.extend(names.map(|p| (p, kind)));
+                }
+            }
+        }
+    }
+
+    Some(Declaration {
+        keyword,
+        id,
+        label,
+        aliased_name,
+        stereotype,
+        parents,
+        opens_block: rest.trim_end().ends_with('{'),
+    })
+}
+
+fn find_node_mut<'a>(graph: &'a mut DepGraph, id: &str) -> Option<&'a mut NodeInfo> {
+    if graph.nodes.contains_key(id) {
+        return graph.nodes.get_mut(id);
+    }
+    graph
+        .subgraphs
+        .iter_mut()
+        .find_map(|sg| find_node_mut(sg, id))
+}
+
+#[derive(Default)]
+struct Parser {
+    /// The root graph, followed by each open subgraph
+    scopes: Vec<DepGraph>,
+    /// Whether each open `{` started a subgraph, rather than e.g. a `together` group
+    braces: Vec<bool>,
+    /// Element names and aliases to node IDs
+    ids: HashMap<String, String>,
+    skip: Option<Skip>,
+}
+
+impl Parser {
+    fn scope(&mut self) -> &mut DepGraph {
+        self.scopes.last_mut().expect("root scope is never popped")
+    }
+
+    /// The node ID for an element name or alias, adding a node to the current scope if it's new.
+    fn node(&mut self, name: &str, node_type: Option<&str>) -> String {
+        if let Some(id) = self.ids.get(name) {
+            return id.clone();
+        }
+        self.ids.insert(name.to_string(), name.to_string());
+        let mut info = NodeInfo::new(name);
+        info.node_type = node_type.map(String::from);
+        self.scope().nodes.insert(name.to_string(), info);
+        name.to_string()
+    }
+
+    fn node_mut(&mut self, id: &str) -> Option<&mut NodeInfo> {
+        self.scopes
+            .iter_mut()
+            .rev()
+            .find_map(|g| find_node_mut(g, id))
+    }
+
+    fn close_scope(&mut self) {
+        if self.braces.pop() == Some(true) {
+            let sg = self.scopes.pop().expect("subgraph scope");
+            self.scope().subgraphs.push(sg);
+        }
+    }
+
+    fn declare(&mut self, decl: Declaration) {
+        let is_class = CLASS_KEYWORDS.contains(&decl.keyword);
+        if decl.opens_block && !is_class {
+            let mut sg = DepGraph {
+                id: Some(decl.id.to_string()),
+                ..Default::default()
+            };
+            if decl.label != decl.id {
+                sg.attrs.insert("label".to_string(), decl.label.to_string());
+            }
+            self.scopes.push(sg);
+            self.braces.push(true);
+            return;
+        }
+        if decl.opens_block {
+            self.skip = Some(Skip::Braces(1));
+        }
+
+        let id = self.node(decl.id, None);
+        if let Some(name) = decl.aliased_name {
+            self.ids.insert(name.to_string(), id.clone());
+        }
+        let node_type = match decl.keyword {
+            "abstract class" => "abstract",
+            keyword => keyword,
+        };
+        if let Some(info) = self.node_mut(&id) {
+            info.label = decl.label.to_string();
+            info.node_type = Some(node_type.to_string());
+            if let Some(stereotype) = decl.stereotype {
+                info.attrs
+                    .insert("stereotype".to_string(), stereotype.to_string());
+            }
+        }
+
+        for (parent, kind) in decl.parents {
+            let to = self.node(parent, None);
+            self.scope().edges.push(Edge {
+                from: id.clone(),
+                to,
+                label: None,
+                attrs: IndexMap::from([("kind".to_string(), kind.to_string())]),
+            });
+        }
+    }
+
+    fn relate(&mut self, rel: Relationship) {
+        let from = self.node(rel.from.0, rel.from.1);
+        let to = self.node(rel.to.0, rel.to.1);
+        if rel.arrow.hidden {
+            return;
+        }
+
+        let mut attrs = IndexMap::from([("kind".to_string(), rel.arrow.kind().to_string())]);
+        let (from, to, tail, head) = if rel.arrow.is_reversed() {
+            (to, from, rel.to_multiplicity, rel.from_multiplicity)
+        } else {
+            (from, to, rel.from_multiplicity, rel.to_multiplicity)
+        };
+        if let Some(tail) = tail {
+            attrs.insert("taillabel".to_string(), tail.to_string());
+        }
+        if let Some(head) = head {
+            attrs.insert("headlabel".to_string(), head.to_string());
+        }
+        self.scope().edges.push(Edge {
+            from,
+            to,
+            label: rel.label.map(String::from),
+            attrs,
+        });
+    }
+
+    /// Whether a skipped block ends at this line.
+    fn skip_line(&mut self, line: &str) {
+        let done = match &mut self.skip {
+            None => return,
+            Some(Skip::Comment) => line.contains("'/"),
+            Some(Skip::Until(end)) => {
+                let squashed: String = line.split_whitespace().collect();
+                squashed.starts_with(*end)
+            }
+            Some(Skip::Braces(depth)) => {
+                *depth += line.matches('{').count();
+                *depth = depth.saturating_sub(line.matches('}').count());
+                *depth == 0
+            }
+        };
+        if done {
+            self.skip = None;
+        }
+    }
+
+    /// Start skipping a multi-line directive, like a `note` without a `:` or a bare `title`.
+    fn skip_directive(&mut self, line: &str) {
+        let keyword = line.split_whitespace().next().unwrap_or_default();
+        self.skip = match keyword {
+            "note" if !line.contains(':') && !line.starts_with("note \"") => {
+                Some(Skip::Until("endnote"))
+            }
+            "legend" => Some(Skip::Until("endlegend")),
+            "title" if line == "title" => Some(Skip::Until("endtitle")),
+            "header" if line == "header" => Some(Skip::Until("endheader")),
+            "footer" if line == "footer" => Some(Skip::Until("endfooter")),
+            _ if line.ends_with('{') => Some(Skip::Braces(1)),
+            _ => None,
+        };
+    }
+
+    fn parse_line(&mut self, line: &str) {
+        let line = line.trim();
+        if self.skip.is_some() {
+            self.skip_line(line);
+            return;
+        }
+        if line.is_empty() || line.starts_with('\'') {
+            return;
+        }
+        if let Some(rest) = line.strip_prefix("/'") {
+            if !rest.contains("'/") {
+                self.skip = Some(Skip::Comment);
+            }
+            return;
+        }
+        if let Some(name) = line.strip_prefix("@startuml") {
+            let name = name.trim();
+            if !name.is_empty() {
+                self.scopes[0].id = Some(name.to_string());
+            }
+            return;
+        }
+        if line.starts_with('}') {
+            self.close_scope();
+            return;
+        }
+        match line {
+            "left to right direction" => {
+                let attrs = &mut self.scopes[0].attrs;
+                attrs.insert("rankdir".to_string(), "LR".to_string());
+                return;
+            }
+            "top to bottom direction" => {
+                let attrs = &mut self.scopes[0].attrs;
+                attrs.insert("rankdir".to_string(), "TB".to_string());
+                return;
+            }
+            _ => {}
+        }
+        if line == "<style>" {
+            self.skip = Some(Skip::Until("</style>"));
+            return;
+        }
+        let is_directive = |d: &&str| {
+            line.strip_prefix(*d).is_some_and(|rest| {
+                d.len() == 1 || rest.is_empty() || rest.starts_with(char::is_whitespace)
+            })
+        };
+        if IGNORED_DIRECTIVES.iter().any(is_directive) {
+            self.skip_directive(line);
+            return;
+        }
+        if line.starts_with("together") {
+            self.braces.push(false);
+            return;
+        }
+
+        let keyword = ELEMENT_KEYWORDS.iter().find(|k| {
+            line.strip_prefix(**k)
+                .is_some_and(|rest| rest.starts_with(char::is_whitespace))
+        });
+        if let Some(keyword) = keyword
+            && let Some(decl) = parse_declaration(keyword, &line[keyword.len()..])
+        {
+            self.declare(decl);
+        } else if let Some(rel) = parse_relationship(line) {
+            self.relate(rel);
+        } else if let Some((_, Some(keyword), _)) = parse_name(line)
+            && let Some(decl) = parse_declaration(keyword, line)
+        {
+            // Shorthand declarations, like `[Web Server] as web` or `:User:`
+            self.declare(decl);
+        } else {
+            tracing::debug!("Skipping unrecognized PlantUML line: {line:?}");
+        }
+    }
+}
+
+/// Parse a PlantUML component, deployment, class, or usecase diagram into a [`DepGraph`].
+///
+/// Elements become nodes keyed by their alias, or their name if they don't have one, with the
+/// element keyword (`component`, `class`, `actor`, ...) as the node type and any `<<stereotype>>`
+/// in `attrs["stereotype"]`. Shorthands like `[component]`, `() interface`, `:actor:`, and
+/// `(usecase)` are recognized, and elements only used in relationships get a node too.
+///
+/// Relationships become edges, with the UML relationship their arrow style describes in the
+/// `kind` attr: `-->` is an `association`, `..>` a `dependency`, `--|>` and `..|>` are `extends`
+/// and `implements`, and `*--` and `o--` are `composition` and `aggregation`. Edges point from the
+/// owner of a composition or aggregation, and towards the arrowhead otherwise. Multiplicities are
+/// stored in `taillabel` and `headlabel` attrs, and hidden arrows are skipped.
+///
+/// `package`, `node`, `folder`, and other container blocks become nested subgraphs. Notes,
+/// titles, legends, `skinparam`, and other styling directives are ignored.
+pub fn parse(input: &str) -> eyre::Result<DepGraph> {
+    let mut parser = Parser {
+        scopes: vec![DepGraph::default()],
+        ..Default::default()
+    };
+    for line in input.lines() {
+        if line.trim_start().starts_with("@enduml") {
+            break;
+        }
+        parser.parse_line(line);
+    }
+    if parser.scopes.len() > 1 {
+        let id = parser.scope().id.clone().unwrap_or_default();
+        eyre::bail!("unclosed block: {id}");
+    }
+    Ok(parser.scopes.remove(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edge_tuples(graph: &DepGraph) -> Vec<(&str, &str, &str)> {
+        graph
+            .all_edges()
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str(), e.attrs["kind"].as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(
+            parse_name("foo.Bar --> x"),
+            Some(("foo.Bar", None, " --> x"))
+        );
+        assert_eq!(parse_name("foo..>x"), Some(("foo", None, "..>x")));
+        assert_eq!(
+            parse_name("[Web Server] as web"),
+            Some(("Web Server", Some("component"), " as web"))
+        );
+        assert_eq!(parse_name(":User:"), Some(("User", Some("actor"), "")));
+        assert_eq!(
+            parse_name("() \"Data Access\""),
+            Some(("Data Access", Some("interface"), ""))
+        );
+        assert_eq!(parse_name("(Login)"), Some(("Login", Some("usecase"), "")));
+        assert_eq!(parse_name("--> x"), None);
+    }
+
+    #[test]
+    fn arrows() {
+        let arrow = |s| parse_arrow(s).map(|(a, _)| (a.kind(), a.is_reversed(), a.hidden));
+        assert_eq!(arrow("--> b"), Some(("association", false, false)));
+        assert_eq!(arrow("-> b"), Some(("association", false, false)));
+        assert_eq!(arrow(".> b"), Some(("dependency", false, false)));
+        assert_eq!(arrow("..> b"), Some(("dependency", false, false)));
+        assert_eq!(arrow("-up-> b"), Some(("association", false, false)));
+        assert_eq!(
+            arrow("-[#red,dashed]-> b"),
+            Some(("dependency", false, false))
+        );
+        assert_eq!(arrow("-[hidden]-> b"), Some(("association", false, true)));
+        assert_eq!(arrow("--|> b"), Some(("extends", false, false)));
+        assert_eq!(arrow("<|-- b"), Some(("extends", true, false)));
+        assert_eq!(arrow("..|> b"), Some(("implements", false, false)));
+        assert_eq!(arrow("*-- b"), Some(("composition", false, false)));
+        assert_eq!(arrow("--o b"), Some(("aggregation", true, false)));
+        assert_eq!(arrow("<-- b"), Some(("association", true, false)));
+        assert_eq!(arrow("<--> b"), Some(("association", false, false)));
+        assert_eq!(arrow("-- b"), Some(("association", false, false)));
+        assert_eq!(arrow("> b"), None);
+        // `o` starts the target's name, not an aggregation
+        assert_eq!(parse_arrow("--other").unwrap().1, "other");
+    }
+
+    #[test]
+    fn class_diagram() {
+        let input = "\
+@startuml
+abstract class Shape
+class Circle extends Shape implements Drawable, Serializable {
+  - radius : double
+  + area() : double
+}
+interface Drawable
+Canvas \"1\" *-- \"many\" Shape : draws >
+Shape <|-- Square
+Circle ..> Math
+@enduml
+";
+        let graph = parse(input).unwrap();
+        let types: Vec<(&str, Option<&str>)> = graph
+            .nodes
+            .iter()
+            .map(|(id, info)| (id.as_str(), info.node_type.as_deref()))
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                ("Shape", Some("abstract")),
+                ("Circle", Some("class")),
+                ("Drawable", Some("interface")),
+                ("Serializable", None),
+                ("Canvas", None),
+                ("Square", None),
+                ("Math", None),
+            ]
+        );
+        assert_eq!(
+            edge_tuples(&graph),
+            vec![
+                ("Circle", "Shape", "extends"),
+                ("Circle", "Drawable", "implements"),
+                ("Circle", "Serializable", "implements"),
+                ("Canvas", "Shape", "composition"),
+                ("Square", "Shape", "extends"),
+                ("Circle", "Math", "dependency"),
+            ]
+        );
+        let draws = &graph.edges[3];
+        assert_eq!(draws.label.as_deref(), Some("draws"));
+        assert_eq!(draws.attrs["taillabel"], "1");
+        assert_eq!(draws.attrs["headlabel"], "many");
+    }
+
+    #[test]
+    fn usecase_diagram() {
+        let input = "\
+@startuml
+:Admin: as admin
+actor User
+User --> (Log in)
+admin --> (Log in)
+(Log in) .> (Check password) : include
+@enduml
+";
+        let graph = parse(input).unwrap();
+        assert_eq!(graph.nodes["admin"].label, "Admin");
+        assert_eq!(graph.nodes["admin"].node_type.as_deref(), Some("actor"));
+        assert_eq!(graph.nodes["Log in"].node_type.as_deref(), Some("usecase"));
+        assert_eq!(
+            edge_tuples(&graph),
+            vec![
+                ("User", "Log in", "association"),
+                ("admin", "Log in", "association"),
+                ("Log in", "Check password", "dependency"),
+            ]
+        );
+    }
+
+    #[test]
+    fn unclosed_package() {
+        assert!(parse("package foo {\n[a] --> [b]\n").is_err());
+    }
+
+    #[test]
+    fn fixture() {
+        let input = include_str!("../../../../data/depconv/architecture.puml");
+        let graph = parse(input).unwrap();
+        assert_eq!(graph.id.as_deref(), Some("architecture"));
+        assert_eq!(graph.attrs["rankdir"], "LR");
+
+        let ids: Vec<&str> = graph.all_nodes().keys().map(|s| s.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "User",
+                "dot_parser",
+                "depconv",
+                "depfilter",
+                "parse",
+                "emit",
+                "algorithm",
+                "graph",
+            ]
+        );
+        let bins = &graph.subgraphs[0];
+        assert_eq!(bins.id.as_deref(), Some("bins"));
+        assert_eq!(bins.attrs["label"], "Binaries");
+        assert_eq!(graph.subgraphs[1].id.as_deref(), Some("csvizmo_depgraph"));
+        let depconv = &bins.nodes["depconv"];
+        assert_eq!(depconv.node_type.as_deref(), Some("component"));
+        assert_eq!(depconv.attrs["stereotype"], "bin");
+        assert_eq!(
+            graph.subgraphs[1].nodes["graph"].node_type.as_deref(),
+            Some("interface")
+        );
+
+        assert_eq!(
+            edge_tuples(&graph),
+            vec![
+                ("User", "depconv", "association"),
+                ("depconv", "parse", "association"),
+                ("depconv", "emit", "association"),
+                ("depfilter", "parse", "association"),
+                ("depfilter", "algorithm", "dependency"),
+                ("parse", "graph", "association"),
+                ("emit", "graph", "association"),
+                ("algorithm", "graph", "association"),
+                ("parse", "dot_parser", "dependency"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,324 @@
+use indexmap::IndexMap;
+use yaml_rust2::{Yaml, YamlLoader};
+
+use crate::{DepGraph, Edge, NodeInfo};
+
+/// A scalar as a string. YAML parses unquoted versions like `1.0` as numbers.
+fn scalar(yaml: &Yaml) -> Option<String> {
+    match yaml {
+        Yaml::String(s) | Yaml::Real(s) => Some(s.clone()),
+        Yaml::Integer(i) => Some(i.to_string()),
+        _ => None,
+    }
+}
+
+/// Iterate over the `(key, value)` entries of a mapping, skipping non-string keys.
+fn entries(yaml: &Yaml) -> impl Iterator<Item = (String, &Yaml)> {
+    yaml.as_hash()
+        .into_iter()
+        .flatten()
+        .filter_map(|(k, v)| Some((scalar(k)?, v)))
+}
+
+/// Strip the peer dependency suffix, e.g. `18.2.0(react@18.2.0)` -> `18.2.0`.
+fn strip_peers(s: &str) -> &str {
+    s.split_once('(').map_or(s, |(before, _)| before)
+}
+
+/// Split a `packages` or `snapshots` key like `/@scope/name@1.0.0(peer@2.0.0)` into its name
+/// and version.
+fn split_key(key: &str) -> Option<(&str, &str)> {
+    let key = strip_peers(key.strip_prefix('/').unwrap_or(key));
+    // Skip the first character so a scope's leading '@' isn't taken as the separator
+    let first = key.chars().next()?.len_utf8();
+    let at = key[first..].rfind('@')? + first;
+    Some((&key[..at], &key[at + 1..]))
+}
+
+/// Join a `link:` path onto the importer it's relative to, e.g. `packages/a` + `../b` ->
+/// `packages/b`.
+fn join_importer(importer: &str, link: &str) -> String {
+    let mut parts: Vec<&str> = importer.split('/').filter(|p| *p != ".").collect();
+    for part in link.split('/') {
+        match part {
+            "." | "" => {}
+            ".." => {
+                parts.pop();
+            }
+            _ => parts.push(part),
+        }
+    }
+    if parts.is_empty() {
+        ".".to_string()
+    } else {
+        parts.join("/")
+    }
+}
+
+/// The node ID that the dependency `name` at the given lockfile `version` refers to.
+fn target_id(importer: Option<&str>, name: &str, version: &str) -> String {
+    if let Some(link) = version.strip_prefix("link:") {
+        return join_importer(importer.unwrap_or("."), link);
+    }
+    // Aliased dependencies (`string-width-cjs: string-width@4.2.3`) and v6 absolute versions
+    // (`/name@1.0.0`) name the package in the version
+    if let Some((name, version)) = split_key(version) {
+        return format!("{name} {version}");
+    }
+    format!("{name} {}", strip_peers(version))
+}
+
+#[derive(Default)]
+struct Builder {
+    graph: DepGraph,
+    edges: IndexMap<(String, String), Vec<&'static str>>,
+}
+
+impl Builder {
+    fn add_edge(&mut self, from: &str, to: String, kind: &'static str) {
+        if !self.graph.nodes.contains_key(&to) {
+            tracing::debug!("{from:?} dependency {to:?} not found in lockfile");
+            return;
+        }
+        let kinds = self.edges.entry((from.to_string(), to)).or_default();
+        if !kinds.contains(&kind) {
+            kinds.push(kind);
+        }
+    }
+
+    fn finish(mut self) -> DepGraph {
+        self.graph.edges = self
+            .edges
+            .into_iter()
+            .map(|((from, to), kinds)| Edge {
+                from,
+                to,
+                label: None,
+                attrs: IndexMap::from([("kind".to_string(), kinds.join(","))]),
+            })
+            .collect();
+        self.graph
+    }
+}
+
+/// Parse a `pnpm-lock.yaml` (lockfile version 6 or 9) into a [`DepGraph`].
+///
+/// Each package becomes a node keyed `name version` like [`super::npm_lock`], with its version
+/// in `attrs["version"]`. Variants of a package resolved against different peers are merged into
+/// one node. Each workspace project (importer) becomes a node keyed by its path, with `.` for the
+/// workspace root. Dependencies become edges with a `kind` attr of `normal`, `dev`, `optional`,
+/// or `peer`.
+pub fn parse(input: &str) -> eyre::Result<DepGraph> {
+    let docs = YamlLoader::load_from_str(input)?;
+    let Some(doc) = docs.first() else {
+        return Ok(DepGraph::default());
+    };
+    let mut builder = Builder::default();
+
+    // Lockfiles for a single project have no importers; the root's dependencies are top-level
+    let importers: Vec<(String, &Yaml)> = if doc["importers"].is_hash() {
+        entries(&doc["importers"]).collect()
+    } else {
+        vec![(".".to_string(), doc)]
+    };
+    for (path, _) in &importers {
+        builder
+            .graph
+            .nodes
+            .insert(path.clone(), NodeInfo::new(path));
+    }
+
+    let packages = &doc["packages"];
+    for (key, _) in entries(packages) {
+        let Some((name, version)) = split_key(&key) else {
+            eyre::bail!("invalid pnpm package key: {key:?}");
+        };
+        builder
+            .graph
+            .nodes
+            .entry(format!("{name} {version}"))
+            .or_insert_with(|| NodeInfo {
+                label: name.to_string(),
+                node_type: None,
+                attrs: IndexMap::from([("version".to_string(), version.to_string())]),
+            });
+    }
+
+    const SECTIONS: [(&str, &str); 3] = [
+        ("dependencies", "normal"),
+        ("devDependencies", "dev"),
+        ("optionalDependencies", "optional"),
+    ];
+
+    for (path, importer) in &importers {
+        for (section, kind) in SECTIONS {
+            for (name, dep) in entries(&importer[section]) {
+                // v6+ has `{specifier, version}`; older lockfiles have just the version
+                let Some(version) = scalar(&dep["version"]).or_else(|| scalar(dep)) else {
+                    continue;
+                };
+                builder.add_edge(path, target_id(Some(path), &name, &version), kind);
+            }
+        }
+    }
+
+    // v9 moved per-instance dependencies from `packages` to `snapshots`
+    let snapshots = if doc["snapshots"].is_hash() {
+        &doc["snapshots"]
+    } else {
+        packages
+    };
+    for (key, snapshot) in entries(snapshots) {
+        let Some((name, version)) = split_key(&key) else {
+            eyre::bail!("invalid pnpm package key: {key:?}");
+        };
+        let from = format!("{name} {version}");
+        // Resolved peers are listed as dependencies; the package metadata says which are peers
+        let package = &packages[format!("{name}@{version}").as_str()];
+        let package = if package.is_badvalue() {
+            snapshot
+        } else {
+            package
+        };
+        let peers: Vec<String> = entries(&package["peerDependencies"])
+            .map(|(k, _)| k)
+            .collect();
+
+        for (section, kind) in SECTIONS {
+            for (dep_name, dep_version) in entries(&snapshot[section]) {
+                let Some(dep_version) = scalar(dep_version) else {
+                    continue;
+                };
+                let kind = if peers.contains(&dep_name) {
+                    "peer"
+                } else {
+                    kind
+                };
+                builder.add_edge(&from, target_id(None, &dep_name, &dep_version), kind);
+            }
+        }
+    }
+
+    Ok(builder.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edge_tuples(graph: &DepGraph) -> Vec<(&str, &str, &str)> {
+        graph
+            .edges
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str(), e.attrs["kind"].as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn split_keys() {
+        assert_eq!(split_key("react@18.2.0"), Some(("react", "18.2.0")));
+        assert_eq!(split_key("/react@18.2.0"), Some(("react", "18.2.0")));
+        assert_eq!(
+            split_key("@scope/util@2.1.0(react@18.2.0)"),
+            Some(("@scope/util", "2.1.0"))
+        );
+        assert_eq!(split_key("18.2.0"), None);
+        assert_eq!(split_key(""), None);
+        assert_eq!(split_key("/"), None);
+        assert_eq!(split_key("é@1.0.0"), Some(("é", "1.0.0")));
+    }
+
+    #[test]
+    fn join_importers() {
+        assert_eq!(join_importer("packages/a", "../b"), "packages/b");
+        assert_eq!(join_importer(".", "packages/b"), "packages/b");
+        assert_eq!(join_importer("packages/a", "../.."), ".");
+    }
+
+    #[test]
+    fn empty_input() {
+        let graph = parse("").unwrap();
+        assert!(graph.nodes.is_empty());
+    }
+
+    #[test]
+    fn v9_fixture() {
+        let input = include_str!("../../../../data/depconv/pnpm-lock.yaml");
+        let graph = parse(input).unwrap();
+
+        let ids: Vec<&str> = graph.nodes.keys().map(|s| s.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                ".",
+                "packages/ui",
+                "@scope/util 2.1.0",
+                "debug 4.3.4",
+                "ms 2.0.0",
+                "ms 2.1.2",
+                "react 18.2.0",
+                "typescript 5.3.3",
+            ]
+        );
+        assert_eq!(graph.nodes["@scope/util 2.1.0"].label, "@scope/util");
+        assert_eq!(graph.nodes["ms 2.0.0"].attrs["version"], "2.0.0");
+
+        assert_eq!(
+            edge_tuples(&graph),
+            vec![
+                (".", "@scope/util 2.1.0", "normal"),
+                (".", "debug 4.3.4", "normal"),
+                (".", "packages/ui", "normal"),
+                (".", "typescript 5.3.3", "dev"),
+                ("packages/ui", "debug 4.3.4", "normal"),
+                ("packages/ui", "react 18.2.0", "normal"),
+                ("@scope/util 2.1.0", "ms 2.0.0", "normal"),
+                ("@scope/util 2.1.0", "react 18.2.0", "peer"),
+                ("debug 4.3.4", "ms 2.1.2", "normal"),
+            ]
+        );
+    }
+
+    #[test]
+    fn v6_single_project() {
+        let input = "\
+lockfileVersion: '6.0'
+
+dependencies:
+  debug:
+    specifier: ^4.3.4
+    version: 4.3.4
+
+devDependencies:
+  ms:
+    specifier: ^2.1.2
+    version: 2.1.2
+
+packages:
+
+  /debug@4.3.4:
+    resolution: {integrity: sha512-CCCC}
+    dependencies:
+      ms: 2.1.2
+    dev: false
+
+  /ms@2.1.2:
+    resolution: {integrity: sha512-EEEE}
+";
+        let graph = parse(input).unwrap();
+        assert_eq!(graph.nodes.len(), 3);
+        assert_eq!(
+            edge_tuples(&graph),
+            vec![
+                (".", "debug 4.3.4", "normal"),
+                (".", "ms 2.1.2", "dev"),
+                ("debug 4.3.4", "ms 2.1.2", "normal"),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_yaml() {
+        assert!(parse("packages: [\n").is_err());
+    }
+}
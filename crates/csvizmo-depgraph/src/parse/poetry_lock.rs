@@ -0,0 +1,424 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::{DepGraph, Edge, NodeInfo};
+
+#[derive(Deserialize)]
+struct Lockfile {
+    #[serde(default, rename = "package")]
+    packages: Vec<Package>,
+}
+
+#[derive(Deserialize)]
+struct Package {
+    name: String,
+    version: String,
+    #[serde(default)]
+    dependencies: IndexMap<String, Constraints>,
+    #[serde(default)]
+    extras: IndexMap<String, Vec<String>>,
+}
+
+/// A `[package.dependencies]` value: a bare version, a table, or a list of tables with
+/// different markers.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Constraints {
+    Version(String),
+    Single(Constraint),
+    Multiple(Vec<Constraint>),
+}
+
+#[derive(Deserialize)]
+struct Constraint {
+    version: Option<String>,
+    #[serde(default)]
+    extras: Vec<String>,
+    markers: Option<String>,
+    #[serde(default)]
+    optional: bool,
+}
+
+impl Constraints {
+    fn into_vec(self) -> Vec<Constraint> {
+        match self {
+            Self::Version(version) => vec![Constraint {
+                version: Some(version),
+                extras: Vec::new(),
+                markers: None,
+                optional: false,
+            }],
+            Self::Single(constraint) => vec![constraint],
+            Self::Multiple(constraints) => constraints,
+        }
+    }
+}
+
+/// Normalize a package name per PEP 503: lowercase, with runs of `-`, `_`, and `.` replaced by
+/// a single `-`.
+fn normalize_name(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    for c in name.chars() {
+        if matches!(c, '-' | '_' | '.') {
+            if !normalized.ends_with('-') {
+                normalized.push('-');
+            }
+        } else {
+            normalized.push(c.to_ascii_lowercase());
+        }
+    }
+    normalized
+}
+
+/// The package name at the start of a requirement string like `PySocks (>=1.5.6,!=1.5.7)`.
+fn requirement_name(requirement: &str) -> &str {
+    let end = requirement
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        .unwrap_or(requirement.len());
+    &requirement[..end]
+}
+
+/// The numeric release segments of a PEP 440 version, e.g. `1.26.4` -> `[1, 26, 4]`.
+///
+/// Epochs and pre-release, post-release, and local suffixes are ignored, which is close enough
+/// to pick between the versions of a package in one lockfile.
+fn release(version: &str) -> Vec<u64> {
+    let version = version.trim();
+    let version = version.split_once('!').map_or(version, |(_, v)| v);
+    let mut segments = Vec::new();
+    for segment in version.trim_start_matches(['v', 'V']).split('.') {
+        let end = segment
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(segment.len());
+        let Ok(n) = segment[..end].parse() else {
+            break;
+        };
+        segments.push(n);
+        if end != segment.len() {
+            break;
+        }
+    }
+    segments
+}
+
+/// Compare releases, padding the shorter one with zeros, so `1.0` == `1.0.0`.
+fn cmp_release(a: &[u64], b: &[u64]) -> Ordering {
+    (0..a.len().max(b.len()))
+        .map(|i| a.get(i).unwrap_or(&0).cmp(b.get(i).unwrap_or(&0)))
+        .find(|o| o.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Whether the first `prefix.len()` segments of `release` match `prefix`.
+fn has_prefix(release: &[u64], prefix: &[u64]) -> bool {
+    prefix
+        .iter()
+        .enumerate()
+        .all(|(i, p)| release.get(i).unwrap_or(&0) == p)
+}
+
+/// Whether a version satisfies a PEP 440 specifier like `>=1.21.1,<3` or `==1.26.*`.
+///
+/// Clauses without an operator mean `==`, and `*` matches everything.
+fn satisfies(version: &str, specifier: &str) -> bool {
+    const OPERATORS: [&str; 8] = ["===", "==", "!=", "~=", ">=", "<=", ">", "<"];
+    let version = release(version);
+    specifier
+        .split(',')
+        .map(|clause| clause.trim().trim_matches(['(', ')']).trim())
+        .filter(|clause| !clause.is_empty() && *clause != "*")
+        .all(|clause| {
+            let (op, other) = OPERATORS
+                .iter()
+                .find_map(|op| Some((*op, clause.strip_prefix(op)?.trim())))
+                .unwrap_or(("==", clause));
+            if let Some(prefix) = other.strip_suffix(".*") {
+                let matches = has_prefix(&version, &release(prefix));
+                return if op == "!=" { !matches } else { matches };
+            }
+            let other = release(other);
+            let ord = cmp_release(&version, &other);
+            match op {
+                "!=" => ord.is_ne(),
+                ">=" => ord.is_ge(),
+                "<=" => ord.is_le(),
+                ">" => ord.is_gt(),
+                "<" => ord.is_lt(),
+                // Compatible release: ~=1.4.2 means >=1.4.2, ==1.4.*
+                "~=" => {
+                    ord.is_ge() && has_prefix(&version, &other[..other.len().saturating_sub(1)])
+                }
+                _ => ord.is_eq(),
+            }
+        })
+}
+
+/// Parse a Poetry `poetry.lock` file into a [`DepGraph`].
+///
+/// Each locked package becomes a node keyed `name version`, with the version in
+/// `attrs["version"]`. The project itself isn't part of `poetry.lock`, so the graph has one root
+/// per direct dependency. Each dependency becomes an edge labeled with its version constraint
+/// (unlabeled for `*`). Requested extras are stored in a comma-separated `extras` attr, and
+/// environment markers in a `marker` attr. Optional dependencies get an `optional` attr naming
+/// the extras that enable them, the same way the cargo metadata parser names features.
+///
+/// If several versions of a dependency are locked (for different markers), each constraint's edge
+/// goes to the newest locked version that satisfies its version specifier. Dependencies that aren't locked, like optional ones for extras nobody asked
+/// for, are skipped.
+pub fn parse(input: &str) -> eyre::Result<DepGraph> {
+    let lockfile: Lockfile = toml::from_str(input)?;
+    let mut graph = DepGraph::default();
+
+    let mut by_name: HashMap<String, Vec<(String, String)>> = HashMap::new();
+    for package in &lockfile.packages {
+        let id = format!("{} {}", package.name, package.version);
+        by_name
+            .entry(normalize_name(&package.name))
+            .or_default()
+            .push((id.clone(), package.version.clone()));
+        graph.nodes.insert(
+            id,
+            NodeInfo {
+                label: package.name.clone(),
+                node_type: None,
+                attrs: IndexMap::from([("version".to_string(), package.version.clone())]),
+            },
+        );
+    }
+
+    for package in lockfile.packages {
+        let from = format!("{} {}", package.name, package.version);
+        for (name, constraints) in package.dependencies {
+            let normalized = normalize_name(&name);
+            let Some(locked) = by_name.get(&normalized) else {
+                tracing::debug!("{from:?} dependency {name:?} is not locked");
+                continue;
+            };
+            let enabled_by: Vec<&str> = package
+                .extras
+                .iter()
+                .filter(|(_, reqs)| {
+                    reqs.iter()
+                        .any(|r| normalize_name(requirement_name(r)) == normalized)
+                })
+                .map(|(extra, _)| extra.as_str())
+                .collect();
+
+            for constraint in constraints.into_vec() {
+                let specifier = constraint.version.as_deref().unwrap_or("*");
+                let to = locked
+                    .iter()
+                    .filter(|(_, version)| satisfies(version, specifier))
+                    .max_by(|(_, a), (_, b)| cmp_release(&release(a), &release(b)))
+                    .unwrap_or_else(|| {
+                        tracing::debug!("no locked {name:?} satisfies {specifier:?}");
+                        &locked[0]
+                    });
+                let mut attrs = IndexMap::new();
+                if constraint.optional && !enabled_by.is_empty() {
+                    attrs.insert("optional".to_string(), enabled_by.join(","));
+                }
+                if !constraint.extras.is_empty() {
+                    attrs.insert("extras".to_string(), constraint.extras.join(","));
+                }
+                if let Some(markers) = constraint.markers {
+                    attrs.insert("marker".to_string(), markers);
+                }
+                graph.edges.push(Edge {
+                    from: from.clone(),
+                    to: to.0.clone(),
+                    label: constraint.version.filter(|v| v != "*"),
+                    attrs,
+                });
+            }
+        }
+    }
+
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type EdgeTuple<'a> = (&'a str, &'a str, Option<&'a str>, Vec<(&'a str, &'a str)>);
+
+    fn edge_tuples(graph: &DepGraph) -> Vec<EdgeTuple<'_>> {
+        graph
+            .edges
+            .iter()
+            .map(|e| {
+                let attrs = e.attrs.iter().map(|(k, v)| (k.as_str(), v.as_str()));
+                (
+                    e.from.as_str(),
+                    e.to.as_str(),
+                    e.label.as_deref(),
+                    attrs.collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn normalize() {
+        assert_eq!(normalize_name("PySocks"), "pysocks");
+        assert_eq!(normalize_name("zope.interface"), "zope-interface");
+        assert_eq!(normalize_name("Foo__Bar-.baz"), "foo-bar-baz");
+    }
+
+    #[test]
+    fn requirement_names() {
+        assert_eq!(requirement_name("PySocks (>=1.5.6,!=1.5.7)"), "PySocks");
+        assert_eq!(requirement_name("chardet>=3.0.2,<6"), "chardet");
+        assert_eq!(requirement_name("pysocks"), "pysocks");
+    }
+
+    #[test]
+    fn specifiers() {
+        assert!(satisfies("1.26.4", ">=1.26"));
+        assert!(!satisfies("1.24.4", ">=1.26"));
+        assert!(satisfies("1.24.4", "<1.26"));
+        assert!(satisfies("1.5.8", ">=1.5.6,!=1.5.7"));
+        assert!(!satisfies("1.5.7", ">=1.5.6,!=1.5.7"));
+        assert!(satisfies("2.2.0", "(>=1.21.1,<3)"));
+        assert!(satisfies("1.0", "==1.0.0"));
+        assert!(satisfies("1.26.4", "==1.26.*"));
+        assert!(!satisfies("1.26.4", "!=1.26.*"));
+        assert!(satisfies("1.4.5", "~=1.4.2"));
+        assert!(!satisfies("1.5.0", "~=1.4.2"));
+        assert!(satisfies("4.9.0", "*"));
+        assert!(satisfies("2.0.0rc1", ">=2"));
+    }
+
+    #[test]
+    fn multiple_locked_versions() {
+        let input = r#"
+[[package]]
+name = "app"
+version = "0.1.0"
+
+[package.dependencies]
+numpy = [
+    {version = ">=1.26", markers = "python_version >= \"3.9\""},
+    {version = "<1.26", markers = "python_version < \"3.9\""},
+]
+
+[[package]]
+name = "numpy"
+version = "1.24.4"
+
+[[package]]
+name = "numpy"
+version = "1.26.4"
+"#;
+        let graph = parse(input).unwrap();
+        assert_eq!(
+            edge_tuples(&graph),
+            vec![
+                (
+                    "app 0.1.0",
+                    "numpy 1.26.4",
+                    Some(">=1.26"),
+                    vec![("marker", "python_version >= \"3.9\"")]
+                ),
+                (
+                    "app 0.1.0",
+                    "numpy 1.24.4",
+                    Some("<1.26"),
+                    vec![("marker", "python_version < \"3.9\"")]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn unlocked_dependencies_skipped() {
+        let input = r#"
+[[package]]
+name = "app"
+version = "0.1.0"
+
+[package.dependencies]
+requests = ">=2"
+PySocks = {version = ">=1.5.6", optional = true}
+certifi = "*"
+
+[package.extras]
+socks = ["PySocks (>=1.5.6)"]
+
+[[package]]
+name = "certifi"
+version = "2024.2.2"
+"#;
+        let graph = parse(input).unwrap();
+        assert_eq!(graph.nodes.len(), 2);
+        assert_eq!(
+            edge_tuples(&graph),
+            vec![("app 0.1.0", "certifi 2024.2.2", None, vec![])]
+        );
+    }
+
+    #[test]
+    fn fixture() {
+        let input = include_str!("../../../../data/depconv/poetry.lock");
+        let graph = parse(input).unwrap();
+
+        let ids: Vec<&str> = graph.nodes.keys().map(|s| s.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "certifi 2024.2.2",
+                "colorama 0.4.6",
+                "pysocks 1.7.1",
+                "requests 2.31.0",
+                "typing-extensions 4.9.0",
+                "urllib3 2.2.0",
+            ]
+        );
+
+        assert_eq!(
+            edge_tuples(&graph),
+            vec![
+                (
+                    "requests 2.31.0",
+                    "certifi 2024.2.2",
+                    Some(">=2017.4.17"),
+                    vec![]
+                ),
+                (
+                    "requests 2.31.0",
+                    "pysocks 1.7.1",
+                    Some(">=1.5.6,!=1.5.7"),
+                    vec![("optional", "socks")]
+                ),
+                (
+                    "requests 2.31.0",
+                    "urllib3 2.2.0",
+                    Some(">=1.21.1,<3"),
+                    vec![("extras", "socks")]
+                ),
+                (
+                    "urllib3 2.2.0",
+                    "colorama 0.4.6",
+                    None,
+                    vec![("marker", "sys_platform == \"win32\"")]
+                ),
+                (
+                    "urllib3 2.2.0",
+                    "typing-extensions 4.9.0",
+                    Some(">=4.0"),
+                    vec![("marker", "python_version < \"3.11\"")]
+                ),
+                (
+                    "urllib3 2.2.0",
+                    "typing-extensions 4.9.0",
+                    Some(">=4.6"),
+                    vec![("marker", "python_version >= \"3.11\"")]
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,690 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use eyre::WrapErr;
+use indexmap::IndexMap;
+
+use crate::{DepGraph, Edge, NodeInfo};
+
+/// The source directory of a crate: `dir/src` or `dir` itself, whichever has a `lib.rs` or
+/// `main.rs`.
+pub(crate) fn source_dir(dir: &Path) -> Option<PathBuf> {
+    let is_root = |dir: &Path| dir.join("lib.rs").is_file() || dir.join("main.rs").is_file();
+    [dir.join("src"), dir.to_path_buf()]
+        .into_iter()
+        .find(|dir| is_root(dir))
+}
+
+/// Concatenate the `.rs` files of a crate the way `tail -n +1` does, with a `==> path <==` header
+/// before each file.
+pub(crate) fn read_crate(dir: &Path) -> eyre::Result<String> {
+    let Some(src) = source_dir(dir) else {
+        eyre::bail!("no lib.rs or main.rs in {}", dir.display());
+    };
+    let mut files = Vec::new();
+    find_sources(&src, &mut files)?;
+    files.sort();
+    tracing::info!("Found {} source files in {}", files.len(), src.display());
+
+    let mut text = String::new();
+    for file in files {
+        let contents = std::fs::read_to_string(&file)
+            .wrap_err(format!("Failed to read source file: {file:?}"))?;
+        let path = file.strip_prefix(dir).unwrap_or(&file);
+        text.push_str(&format!("==> {} <==\n", path.display()));
+        text.push_str(&contents);
+        text.push('\n');
+    }
+    Ok(text)
+}
+
+fn find_sources(dir: &Path, files: &mut Vec<PathBuf>) -> eyre::Result<()> {
+    for entry in std::fs::read_dir(dir).wrap_err(format!("Failed to read directory: {dir:?}"))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            find_sources(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Split `tail -n +1` style input into (path, contents) pairs. Input without any headers is a
+/// single `lib.rs`.
+fn split_files(input: &str) -> IndexMap<String, &str> {
+    let mut files = IndexMap::new();
+    let mut current: Option<(String, usize)> = None;
+    let mut offset = 0;
+    for line in input.split_inclusive('\n') {
+        let header = line
+            .trim_end()
+            .strip_prefix("==> ")
+            .and_then(|l| l.strip_suffix(" <=="));
+        if let Some(path) = header {
+            if let Some((path, start)) = current.take() {
+                files.insert(path, &input[start..offset]);
+            }
+            current = Some((path.to_string(), offset + line.len()));
+        }
+        offset += line.len();
+    }
+    match current {
+        Some((path, start)) => {
+            files.insert(path, &input[start..]);
+        }
+        None => {
+            files.insert("lib.rs".to_string(), input);
+        }
+    }
+    files
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Token<'a> {
+    Ident(&'a str),
+    Punct(&'a str),
+}
+
+/// Split Rust source into identifiers and punctuation, skipping whitespace, comments, literals, and
+/// lifetimes. `::` is a single token; all other punctuation is one character.
+fn tokenize(src: &str) -> Vec<Token<'_>> {
+    let bytes = src.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    // Skip a string literal whose opening quote is at `start`, closed by `"` and `hashes` `#`s
+    let skip_string = |start: usize, hashes: usize, escapes: bool| -> usize {
+        let mut j = start + 1;
+        while j < bytes.len() {
+            match bytes[j] {
+                b'\\' if escapes => j += 2,
+                b'"' if bytes[j + 1..]
+                    .iter()
+                    .take(hashes)
+                    .filter(|&&b| b == b'#')
+                    .count()
+                    == hashes =>
+                {
+                    return j + 1 + hashes;
+                }
+                _ => j += 1,
+            }
+        }
+        bytes.len()
+    };
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        let next = bytes.get(i + 1).copied();
+        match c {
+            _ if c.is_ascii_whitespace() => i += 1,
+            b'/' if next == Some(b'/') => {
+                i = src[i..].find('\n').map_or(bytes.len(), |n| i + n);
+            }
+            b'/' if next == Some(b'*') => {
+                let mut depth = 0;
+                while i < bytes.len() {
+                    if bytes[i..].starts_with(b"/*") {
+                        depth += 1;
+                        i += 2;
+                    } else if bytes[i..].starts_with(b"*/") {
+                        depth -= 1;
+                        i += 2;
+                        if depth == 0 {
+                            break;
+                        }
+                    } else {
+                        i += 1;
+                    }
+                }
+            }
+            b'"' => i = skip_string(i, 0, true),
+            b'\'' => {
+                // A char literal, or a lifetime, whose name is tokenized as an identifier
+                if next == Some(b'\\') {
+                    i = src[i + 2..].find('\'').map_or(bytes.len(), |n| i + 3 + n);
+                } else if let Some(ch) = src[i + 1..].chars().next()
+                    && bytes.get(i + 1 + ch.len_utf8()) == Some(&b'\'')
+                {
+                    i += 2 + ch.len_utf8();
+                } else {
+                    i += 1;
+                }
+            }
+            _ if c.is_ascii_alphabetic() || c == b'_' || c >= 0x80 => {
+                let start = i;
+                while i < bytes.len()
+                    && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_' || bytes[i] >= 0x80)
+                {
+                    i += 1;
+                }
+                let ident = &src[start..i];
+                let prefix = matches!(ident, "r" | "br" | "cr");
+                match bytes.get(i) {
+                    Some(b'"') if prefix => i = skip_string(i, 0, false),
+                    Some(b'"') if matches!(ident, "b" | "c") => i = skip_string(i, 0, true),
+                    Some(b'#') if prefix => {
+                        let hashes = bytes[i..].iter().take_while(|&&b| b == b'#').count();
+                        if bytes.get(i + hashes) == Some(&b'"') {
+                            i = skip_string(i + hashes, hashes, false);
+                        } else {
+                            // A raw identifier like `r#type`
+                            i += 1;
+                        }
+                    }
+                    _ => tokens.push(Token::Ident(ident)),
+                }
+            }
+            _ if c.is_ascii_digit() => {
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                    i += 1;
+                }
+            }
+            b':' if next == Some(b':') => {
+                tokens.push(Token::Punct("::"));
+                i += 2;
+            }
+            _ => {
+                tokens.push(Token::Punct(&src[i..i + 1]));
+                i += 1;
+            }
+        }
+    }
+    tokens
+}
+
+/// Expand a `use` tree like `crate::{a, b::{self, c}}` into its paths.
+fn use_paths<'a>(tokens: &[Token<'a>], pos: &mut usize, prefix: Vec<&'a str>) -> Vec<Vec<&'a str>> {
+    let mut prefix = prefix;
+    while let Some(token) = tokens.get(*pos) {
+        match token {
+            Token::Punct("::") => *pos += 1,
+            Token::Punct("*") => {
+                *pos += 1;
+                return vec![prefix];
+            }
+            Token::Punct("{") => {
+                *pos += 1;
+                let mut paths = Vec::new();
+                while let Some(token) = tokens.get(*pos) {
+                    match token {
+                        Token::Punct("}") => {
+                            *pos += 1;
+                            break;
+                        }
+                        Token::Punct(",") => *pos += 1,
+                        _ => {
+                            let start = *pos;
+                            paths.extend(use_paths(tokens, pos, prefix.clone()));
+                            if *pos == start {
+                                // Not a use tree; give up on the rest of the group
+                                return paths;
+                            }
+                        }
+                    }
+                }
+                return paths;
+            }
+            Token::Ident(ident) => {
+                prefix.push(ident);
+                *pos += 1;
+                if tokens.get(*pos) != Some(&Token::Punct("::")) {
+                    // Skip a rename like `as Other`
+                    if tokens.get(*pos) == Some(&Token::Ident("as")) {
+                        *pos += 2;
+                    }
+                    return vec![prefix];
+                }
+            }
+            Token::Punct(_) => break,
+        }
+    }
+    Vec::new()
+}
+
+struct Module {
+    path: Vec<String>,
+    parent: Option<usize>,
+    children: IndexMap<String, usize>,
+    file: Option<String>,
+    uses: Vec<Vec<String>>,
+}
+
+impl Module {
+    fn id(&self) -> String {
+        if self.path.is_empty() {
+            "crate".to_string()
+        } else {
+            format!("crate::{}", self.path.join("::"))
+        }
+    }
+}
+
+struct ModuleTree<'a> {
+    files: IndexMap<String, &'a str>,
+    modules: Vec<Module>,
+}
+
+impl ModuleTree<'_> {
+    fn add_module(&mut self, parent: usize, name: &str, file: Option<String>) -> usize {
+        let mut path = self.modules[parent].path.clone();
+        path.push(name.to_string());
+        let index = self.modules.len();
+        self.modules.push(Module {
+            path,
+            parent: Some(parent),
+            children: IndexMap::new(),
+            file,
+            uses: Vec::new(),
+        });
+        self.modules[parent]
+            .children
+            .insert(name.to_string(), index);
+        index
+    }
+
+    /// Parse the file of `module`, whose out-of-line child modules live in `child_dir`.
+    fn parse_file(&mut self, module: usize, file: &str, child_dir: &str) {
+        let Some(src) = self.files.get(file).copied() else {
+            return;
+        };
+        let tokens = tokenize(src);
+
+        // (module, the directory of its child modules, brace depth it was opened at)
+        let mut stack = vec![(module, child_dir.to_string(), 0)];
+        let mut depth = 0;
+        let mut cfg_test = false;
+        let mut i = 0;
+        while i < tokens.len() {
+            let current = stack.last().unwrap().0;
+            match tokens[i] {
+                Token::Punct("#") if tokens.get(i + 1) == Some(&Token::Punct("[")) => {
+                    let end = tokens[i..]
+                        .iter()
+                        .position(|t| *t == Token::Punct("]"))
+                        .map_or(tokens.len(), |n| i + n);
+                    let attr = &tokens[i + 2..end];
+                    if attr
+                        == [
+                            Token::Ident("cfg"),
+                            Token::Punct("("),
+                            Token::Ident("test"),
+                            Token::Punct(")"),
+                        ]
+                    {
+                        cfg_test = true;
+                    }
+                    i = end + 1;
+                    continue;
+                }
+                Token::Ident("mod") => {
+                    if let (Some(Token::Ident(name)), Some(Token::Punct(p @ (";" | "{")))) =
+                        (tokens.get(i + 1), tokens.get(i + 2))
+                    {
+                        let name = *name;
+                        i += 3;
+                        if cfg_test {
+                            cfg_test = false;
+                            if *p == "{" {
+                                i = skip_block(&tokens, i);
+                            }
+                            continue;
+                        }
+                        let dir = &stack.last().unwrap().1;
+                        if *p == ";" {
+                            let dir = dir.clone();
+                            self.add_file_module(current, name, &dir);
+                        } else {
+                            let dir = format!("{dir}{name}/");
+                            depth += 1;
+                            let child = self.add_module(current, name, Some(file.to_string()));
+                            stack.push((child, dir, depth));
+                        }
+                        continue;
+                    }
+                }
+                Token::Ident("use") => {
+                    let mut pos = i + 1;
+                    let paths = use_paths(&tokens, &mut pos, Vec::new());
+                    self.modules[current]
+                        .uses
+                        .extend(paths.into_iter().map(to_owned_path));
+                    i = pos;
+                    continue;
+                }
+                Token::Ident(first @ ("crate" | "super"))
+                    if tokens.get(i + 1) == Some(&Token::Punct("::")) =>
+                {
+                    let mut path = vec![first];
+                    i += 1;
+                    while tokens.get(i) == Some(&Token::Punct("::"))
+                        && let Some(Token::Ident(ident)) = tokens.get(i + 1)
+                    {
+                        path.push(ident);
+                        i += 2;
+                    }
+                    self.modules[current].uses.push(to_owned_path(path));
+                    continue;
+                }
+                Token::Punct("{") => depth += 1,
+                Token::Punct("}") => {
+                    if stack.last().is_some_and(|(_, _, d)| *d == depth) && stack.len() > 1 {
+                        stack.pop();
+                    }
+                    depth -= 1;
+                }
+                _ => {}
+            }
+            if matches!(tokens[i], Token::Punct(";" | "{" | "}")) {
+                cfg_test = false;
+            }
+            i += 1;
+        }
+    }
+
+    /// Add a `mod name;` module, and parse its file.
+    fn add_file_module(&mut self, parent: usize, name: &str, dir: &str) {
+        let candidates = [format!("{dir}{name}.rs"), format!("{dir}{name}/mod.rs")];
+        let file = candidates.into_iter().find(|f| self.files.contains_key(f));
+        if file.is_none() {
+            tracing::warn!("No file found for module {name} in {dir:?}");
+        }
+        let child = self.add_module(parent, name, file.clone());
+        if let Some(file) = file {
+            self.parse_file(child, &file, &format!("{dir}{name}/"));
+        }
+    }
+
+    /// Resolve a path to the deepest module it names, or `None` for paths into other crates.
+    fn resolve(&self, module: usize, path: &[String]) -> Option<usize> {
+        let mut segments = path.iter().map(String::as_str).peekable();
+        let mut current = match *segments.peek()? {
+            "crate" => {
+                segments.next();
+                0
+            }
+            "self" => {
+                segments.next();
+                module
+            }
+            "super" => {
+                let mut current = module;
+                while segments.next_if_eq(&"super").is_some() {
+                    current = self.modules[current].parent?;
+                }
+                current
+            }
+            // Uniform paths can start with a child module
+            first if self.modules[module].children.contains_key(first) => module,
+            _ => return None,
+        };
+        for segment in segments {
+            match self.modules[current].children.get(segment) {
+                Some(&child) => current = child,
+                None if segment == "self" => {}
+                None => break,
+            }
+        }
+        Some(current)
+    }
+
+    fn add_to_graph(&self, graph: &mut DepGraph, module: usize) {
+        let info = &self.modules[module];
+        let id = info.id();
+        let mut attrs = IndexMap::new();
+        if let Some(file) = &info.file {
+            attrs.insert("file".to_string(), file.clone());
+        }
+        graph.nodes.insert(
+            id.clone(),
+            NodeInfo {
+                label: id.strip_prefix("crate::").unwrap_or(&id).to_string(),
+                node_type: Some("module".to_string()),
+                attrs,
+            },
+        );
+        for &child in info.children.values() {
+            if self.modules[child].children.is_empty() {
+                self.add_to_graph(graph, child);
+            } else {
+                let mut subgraph = DepGraph {
+                    id: Some(self.modules[child].id()),
+                    ..Default::default()
+                };
+                self.add_to_graph(&mut subgraph, child);
+                graph.subgraphs.push(subgraph);
+            }
+        }
+    }
+}
+
+fn to_owned_path(path: Vec<&str>) -> Vec<String> {
+    path.into_iter().map(str::to_string).collect()
+}
+
+fn skip_block(tokens: &[Token], mut i: usize) -> usize {
+    let mut depth = 1;
+    while i < tokens.len() && depth > 0 {
+        match tokens[i] {
+            Token::Punct("{") => depth += 1,
+            Token::Punct("}") => depth -= 1,
+            _ => {}
+        }
+        i += 1;
+    }
+    i
+}
+
+/// Parse a crate's source files into a module graph.
+///
+/// The input is the crate's `.rs` files, each after a `==> path <==` header like `tail -n +1`
+/// prints, which is what [`super::read_input`] produces for a crate directory. The crate root is
+/// the `lib.rs`, or failing that the `main.rs`, with the shortest path. Input without headers is
+/// a single root file.
+///
+/// Modules are found by following `mod` declarations from the crate root, so files that aren't
+/// part of the module tree (`src/bin/`, ...) are ignored, as are `#[cfg(test)]` modules. Each
+/// module becomes a node keyed by its path (`crate::parse::dot`) with the `module` node type, and
+/// its file in `attrs["file"]`. Modules with submodules become subgraphs containing the module
+/// and its submodules.
+///
+/// `use` declarations and `crate::`/`super::` paths in code become edges to the deepest module
+/// they name, so `use crate::parse::dot::parse` is an edge to `crate::parse::dot`. Parsing is
+/// lexical: macros aren't expanded, `#[path]` attributes aren't followed, and paths into other
+/// crates are ignored.
+pub fn parse(input: &str) -> eyre::Result<DepGraph> {
+    let files = split_files(input);
+    let root = ["lib.rs", "main.rs"].iter().find_map(|name| {
+        files
+            .keys()
+            .filter(|path| Path::new(path).file_name().is_some_and(|f| f == *name))
+            .min_by_key(|path| path.len())
+            .cloned()
+    });
+    let Some(root) = root else {
+        eyre::bail!("no lib.rs or main.rs crate root in the input");
+    };
+    let root_dir = match root.rfind('/') {
+        Some(slash) => root[..=slash].to_string(),
+        None => String::new(),
+    };
+
+    let mut tree = ModuleTree {
+        files,
+        modules: vec![Module {
+            path: Vec::new(),
+            parent: None,
+            children: IndexMap::new(),
+            file: Some(root.clone()),
+            uses: Vec::new(),
+        }],
+    };
+    tree.parse_file(0, &root, &root_dir);
+
+    let mut graph = DepGraph::default();
+    tree.add_to_graph(&mut graph, 0);
+
+    let mut seen = HashSet::new();
+    for (index, module) in tree.modules.iter().enumerate() {
+        for path in &module.uses {
+            let Some(target) = tree.resolve(index, path) else {
+                continue;
+            };
+            if target != index && seen.insert((index, target)) {
+                graph.edges.push(Edge {
+                    from: module.id(),
+                    to: tree.modules[target].id(),
+                    ..Default::default()
+                });
+            }
+        }
+    }
+
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edge_tuples(graph: &DepGraph) -> Vec<(&str, &str)> {
+        graph
+            .edges
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn tokens() {
+        let src = r##"
+            // use crate::comment;
+            /* use crate::block /* nested */ comment; */
+            let s = "use crate::string;";
+            let r = r#"use crate::"raw";"#;
+            let c = '"';
+            fn f<'a>(x: &'a str) -> r#type::T {}
+        "##;
+        let idents: Vec<&str> = tokenize(src)
+            .into_iter()
+            .filter_map(|t| match t {
+                Token::Ident(ident) => Some(ident),
+                Token::Punct(_) => None,
+            })
+            .collect();
+        assert_eq!(
+            idents,
+            vec![
+                "let", "s", "let", "r", "let", "c", "fn", "f", "a", "x", "a", "str", "type", "T"
+            ]
+        );
+    }
+
+    #[test]
+    fn use_trees() {
+        let tokens = tokenize("crate::{a, b::{self, c as d}, e::*};");
+        let paths = use_paths(&tokens, &mut 0, Vec::new());
+        assert_eq!(
+            paths,
+            vec![
+                vec!["crate", "a"],
+                vec!["crate", "b", "self"],
+                vec!["crate", "b", "c"],
+                vec!["crate", "e"],
+            ]
+        );
+    }
+
+    #[test]
+    fn split_tail_output() {
+        let files = split_files("==> src/lib.rs <==\nmod a;\n\n==> src/a.rs <==\nfn f() {}\n");
+        assert_eq!(
+            files.keys().collect::<Vec<_>>(),
+            vec!["src/lib.rs", "src/a.rs"]
+        );
+        assert_eq!(files["src/lib.rs"], "mod a;\n\n");
+        assert_eq!(files["src/a.rs"], "fn f() {}\n");
+
+        let files = split_files("mod a {}\n");
+        assert_eq!(files.keys().collect::<Vec<_>>(), vec!["lib.rs"]);
+    }
+
+    #[test]
+    fn inline_modules() {
+        let input = "\
+mod a {
+    pub mod b {
+        use super::super::c;
+    }
+    fn f() { crate::c::g(); }
+}
+mod c {
+    pub fn g() { super::a::b::h(); }
+}
+#[cfg(test)]
+mod tests {
+    use crate::a;
+}
+";
+        let graph = parse(input).unwrap();
+        let ids: Vec<&str> = graph.all_nodes().keys().map(|s| s.as_str()).collect();
+        assert_eq!(ids, vec!["crate", "crate::c", "crate::a", "crate::a::b"]);
+        assert_eq!(graph.subgraphs[0].id.as_deref(), Some("crate::a"));
+        assert_eq!(graph.nodes["crate::c"].label, "c");
+        assert_eq!(graph.nodes["crate::c"].attrs["file"], "lib.rs");
+        assert_eq!(
+            edge_tuples(&graph),
+            vec![
+                ("crate::a", "crate::c"),
+                ("crate::a::b", "crate::c"),
+                ("crate::c", "crate::a::b"),
+            ]
+        );
+    }
+
+    #[test]
+    fn no_crate_root() {
+        assert!(parse("==> src/foo.rs <==\nfn f() {}\n").is_err());
+    }
+
+    #[test]
+    fn fixture() {
+        let dir = Path::new(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../data/depconv/rust-crate"
+        ));
+        let input = read_crate(dir).unwrap();
+        let graph = parse(&input).unwrap();
+
+        let ids: Vec<&str> = graph.all_nodes().keys().map(|s| s.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "crate",
+                "crate::graph",
+                "crate::util",
+                "crate::parse",
+                "crate::parse::json",
+                "crate::parse::text",
+            ]
+        );
+        assert_eq!(
+            graph.all_nodes()["crate::parse::json"].attrs["file"],
+            "src/parse/json.rs"
+        );
+        assert_eq!(
+            edge_tuples(&graph),
+            vec![
+                ("crate", "crate::graph"),
+                ("crate::graph", "crate::util"),
+                ("crate::parse", "crate"),
+                ("crate::parse::json", "crate::parse::text"),
+                ("crate::parse::json", "crate::graph"),
+                ("crate::parse::text", "crate"),
+                ("crate::parse::text", "crate::util"),
+                ("crate::util", "crate::graph"),
+            ]
+        );
+    }
+}
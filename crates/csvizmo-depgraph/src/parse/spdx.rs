@@ -0,0 +1,236 @@
+use std::collections::HashSet;
+
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::{DepGraph, Edge, NodeInfo};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Document {
+    spdx_version: String,
+    #[serde(default)]
+    packages: Vec<Package>,
+    #[serde(default)]
+    relationships: Vec<Relationship>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Package {
+    #[serde(rename = "SPDXID")]
+    spdx_id: String,
+    name: String,
+    version_info: Option<String>,
+    supplier: Option<String>,
+    license_concluded: Option<String>,
+    license_declared: Option<String>,
+    primary_package_purpose: Option<String>,
+    #[serde(default)]
+    external_refs: Vec<ExternalRef>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExternalRef {
+    reference_type: String,
+    reference_locator: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Relationship {
+    spdx_element_id: String,
+    relationship_type: String,
+    related_spdx_element: String,
+}
+
+/// `NOASSERTION` and `NONE` mean the field carries no information.
+fn assertion(value: Option<String>) -> Option<String> {
+    value.filter(|v| v != "NOASSERTION" && v != "NONE")
+}
+
+/// Strip the `Organization: ` or `Person: ` prefix from a supplier.
+fn supplier_name(supplier: &str) -> &str {
+    supplier
+        .strip_prefix("Organization:")
+        .or_else(|| supplier.strip_prefix("Person:"))
+        .or_else(|| supplier.strip_prefix("Tool:"))
+        .unwrap_or(supplier)
+        .trim()
+}
+
+/// Map a dependency relationship onto a `(reversed, kind)` pair, or `None` if the relationship
+/// isn't a dependency.
+fn dependency_kind(relationship_type: &str) -> Option<(bool, &'static str)> {
+    match relationship_type {
+        "DEPENDS_ON" => Some((false, "normal")),
+        "DEPENDENCY_OF" | "RUNTIME_DEPENDENCY_OF" => Some((true, "normal")),
+        "BUILD_DEPENDENCY_OF" => Some((true, "build")),
+        "DEV_DEPENDENCY_OF" | "TEST_DEPENDENCY_OF" => Some((true, "dev")),
+        "OPTIONAL_DEPENDENCY_OF" => Some((true, "optional")),
+        "PROVIDED_DEPENDENCY_OF" => Some((true, "provided")),
+        _ => None,
+    }
+}
+
+/// Parse an SPDX 2.x JSON SBOM into a [`DepGraph`].
+///
+/// Each entry in `packages` becomes a node keyed by its `SPDXID` and labeled with the package
+/// name. `versionInfo` is stored in `attrs["version"]`, the first `purl` external reference in
+/// `attrs["purl"]`, the concluded license (falling back to the declared license) in
+/// `attrs["license"]`, and the supplier name in `attrs["supplier"]`. `NOASSERTION` and `NONE`
+/// values are dropped. The `primaryPackagePurpose` becomes the `node_type`, lowercased, with
+/// `LIBRARY` and `APPLICATION` mapped to `lib` and `bin`.
+///
+/// `DEPENDS_ON` relationships become edges, as do the `*_DEPENDENCY_OF` relationships, reversed
+/// so that edges always point from the dependent to the dependency. The relationship sets the edge
+/// `kind` attr: `build`, `dev` (including test dependencies), `optional`, `provided`, or `normal`.
+/// Other relationships (`CONTAINS`, `DESCRIBES`, ...) and relationships with files or external
+/// documents are skipped.
+pub fn parse(input: &str) -> eyre::Result<DepGraph> {
+    let document: Document = serde_json::from_str(input)?;
+    if !document.spdx_version.starts_with("SPDX-2.") {
+        eyre::bail!("unsupported spdxVersion: {:?}", document.spdx_version);
+    }
+
+    let mut graph = DepGraph::default();
+    for package in document.packages {
+        let mut attrs = IndexMap::new();
+        if let Some(version) = package.version_info {
+            attrs.insert("version".to_string(), version);
+        }
+        if let Some(purl) = package
+            .external_refs
+            .into_iter()
+            .find(|r| r.reference_type == "purl")
+        {
+            attrs.insert("purl".to_string(), purl.reference_locator);
+        }
+        if let Some(license) =
+            assertion(package.license_concluded).or(assertion(package.license_declared))
+        {
+            attrs.insert("license".to_string(), license);
+        }
+        if let Some(supplier) = assertion(package.supplier) {
+            attrs.insert("supplier".to_string(), supplier_name(&supplier).to_string());
+        }
+        let node_type = package
+            .primary_package_purpose
+            .map(|purpose| match purpose.as_str() {
+                "LIBRARY" => "lib".to_string(),
+                "APPLICATION" => "bin".to_string(),
+                other => other.to_lowercase(),
+            });
+
+        graph.nodes.entry(package.spdx_id).or_insert(NodeInfo {
+            label: package.name,
+            node_type,
+            attrs,
+        });
+    }
+
+    let mut seen = HashSet::new();
+    for relationship in document.relationships {
+        let Some((reversed, kind)) = dependency_kind(&relationship.relationship_type) else {
+            continue;
+        };
+        let (from, to) = if reversed {
+            (
+                relationship.related_spdx_element,
+                relationship.spdx_element_id,
+            )
+        } else {
+            (
+                relationship.spdx_element_id,
+                relationship.related_spdx_element,
+            )
+        };
+        if !graph.nodes.contains_key(&from) || !graph.nodes.contains_key(&to) {
+            tracing::debug!("Skipping relationship {from:?} -> {to:?} between non-packages");
+            continue;
+        }
+        if seen.insert((from.clone(), to.clone(), kind)) {
+            graph.edges.push(Edge {
+                from,
+                to,
+                label: None,
+                attrs: IndexMap::from([("kind".to_string(), kind.to_string())]),
+            });
+        }
+    }
+
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unsupported_version() {
+        assert!(parse(r#"{"spdxVersion": "SPDX-3.0"}"#).is_err());
+        assert!(parse(r#"{"packages": []}"#).is_err());
+    }
+
+    #[test]
+    fn suppliers() {
+        assert_eq!(
+            supplier_name("Organization: OpenEmbedded ()"),
+            "OpenEmbedded ()"
+        );
+        assert_eq!(supplier_name("Person: Jane Doe"), "Jane Doe");
+        assert_eq!(supplier_name("Acme"), "Acme");
+    }
+
+    #[test]
+    fn fixture() {
+        let input = include_str!("../../../../data/depconv/small.spdx.json");
+        let graph = parse(input).unwrap();
+
+        let ids: Vec<&str> = graph.nodes.keys().map(|s| s.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "SPDXRef-Package-myapp",
+                "SPDXRef-Package-libfoo",
+                "SPDXRef-Package-openssl",
+                "SPDXRef-Package-zlib",
+                "SPDXRef-Package-gcc",
+            ]
+        );
+
+        let app = &graph.nodes["SPDXRef-Package-myapp"];
+        assert_eq!(app.label, "myapp");
+        assert_eq!(app.node_type.as_deref(), Some("bin"));
+        // licenseConcluded is NOASSERTION; fall back to licenseDeclared
+        assert_eq!(app.attrs["license"], "MIT");
+        assert!(!app.attrs.contains_key("supplier"));
+
+        let openssl = &graph.nodes["SPDXRef-Package-openssl"];
+        assert_eq!(openssl.node_type.as_deref(), Some("lib"));
+        assert_eq!(openssl.attrs["version"], "3.0.13");
+        assert_eq!(openssl.attrs["purl"], "pkg:generic/openssl@3.0.13");
+        assert_eq!(openssl.attrs["license"], "Apache-2.0");
+        assert_eq!(openssl.attrs["supplier"], "OpenSSL Software Foundation");
+
+        let edges: Vec<(&str, &str, &str)> = graph
+            .edges
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str(), e.attrs["kind"].as_str()))
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                ("SPDXRef-Package-myapp", "SPDXRef-Package-libfoo", "normal"),
+                (
+                    "SPDXRef-Package-libfoo",
+                    "SPDXRef-Package-openssl",
+                    "normal"
+                ),
+                ("SPDXRef-Package-openssl", "SPDXRef-Package-zlib", "normal"),
+                ("SPDXRef-Package-myapp", "SPDXRef-Package-gcc", "build"),
+            ]
+        );
+    }
+}
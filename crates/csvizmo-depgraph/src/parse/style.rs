@@ -33,6 +33,10 @@ pub fn apply_default_styles(graph: &mut DepGraph) {
                 set_default(&mut edge.attrs, "color", "gray60");
             } else if kinds.contains(&"build") {
                 set_default(&mut edge.attrs, "style", "dashed");
+            } else if kinds.contains(&"recommends") {
+                set_default(&mut edge.attrs, "style", "dotted");
+            } else if kinds.contains(&"dependency") || kinds.contains(&"implements") {
+                set_default(&mut edge.attrs, "style", "dashed");
             }
         }
     }
@@ -250,6 +254,36 @@ mod tests {
         assert!(graph.edges[0].attrs.get("color").is_none());
     }
 
+    #[test]
+    fn edge_recommends_kind() {
+        let mut graph = DepGraph {
+            edges: vec![Edge {
+                from: "a".into(),
+                to: "b".into(),
+                attrs: IndexMap::from([("kind".into(), "recommends".into())]),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        apply_default_styles(&mut graph);
+        assert_eq!(graph.edges[0].attrs.get("style").unwrap(), "dotted");
+    }
+
+    #[test]
+    fn edge_uml_dependency_kind() {
+        let mut graph = DepGraph {
+            edges: vec![Edge {
+                from: "a".into(),
+                to: "b".into(),
+                attrs: IndexMap::from([("kind".into(), "dependency".into())]),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        apply_default_styles(&mut graph);
+        assert_eq!(graph.edges[0].attrs.get("style").unwrap(), "dashed");
+    }
+
     #[test]
     fn edge_normal_kind_no_styling() {
         let mut graph = DepGraph {
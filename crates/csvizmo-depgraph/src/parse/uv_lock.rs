@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::{DepGraph, Edge, NodeInfo};
+
+#[derive(Deserialize)]
+struct Lockfile {
+    #[serde(default, rename = "package")]
+    packages: Vec<Package>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct Package {
+    name: String,
+    version: Option<String>,
+    #[serde(default)]
+    dependencies: Vec<Dependency>,
+    #[serde(default)]
+    optional_dependencies: IndexMap<String, Vec<Dependency>>,
+    #[serde(default)]
+    dev_dependencies: IndexMap<String, Vec<Dependency>>,
+    #[serde(default)]
+    metadata: Metadata,
+}
+
+#[derive(Deserialize)]
+struct Dependency {
+    name: String,
+    /// Only present when several versions of the package are locked
+    version: Option<String>,
+    #[serde(default)]
+    extra: Vec<String>,
+    marker: Option<String>,
+}
+
+/// Requirements as written in `pyproject.toml`. Only recorded for workspace members.
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct Metadata {
+    #[serde(default)]
+    requires_dist: Vec<Requirement>,
+    #[serde(default)]
+    requires_dev: IndexMap<String, Vec<Requirement>>,
+}
+
+#[derive(Deserialize)]
+struct Requirement {
+    name: String,
+    specifier: Option<String>,
+}
+
+fn package_id(name: &str, version: Option<&str>) -> String {
+    match version {
+        Some(version) => format!("{name} {version}"),
+        None => name.to_string(),
+    }
+}
+
+/// Parse a uv `uv.lock` file into a [`DepGraph`].
+///
+/// Each locked package becomes a node keyed `name version`, with the version in
+/// `attrs["version"]`. Each dependency becomes an edge. Edges are labeled with the version
+/// specifier when uv recorded one, which it only does for workspace members. Requested extras
+/// are stored in a comma-separated `extras` attr, and environment markers in a `marker` attr.
+///
+/// Dependency groups map onto edge attrs the same way as for cargo: `dev-dependencies` get
+/// `kind = "dev"`, and `optional-dependencies` get `optional` set to the extra that enables them.
+/// Dependencies that aren't locked are skipped, like `poetry.lock`.
+pub fn parse(input: &str) -> eyre::Result<DepGraph> {
+    let lockfile: Lockfile = toml::from_str(input)?;
+    let mut graph = DepGraph::default();
+
+    // Dependencies only name a version when it's ambiguous, so map names to their first version
+    let mut by_name: HashMap<&str, String> = HashMap::new();
+    for package in &lockfile.packages {
+        let id = package_id(&package.name, package.version.as_deref());
+        let mut attrs = IndexMap::new();
+        if let Some(version) = &package.version {
+            attrs.insert("version".to_string(), version.clone());
+        }
+        by_name.entry(&package.name).or_insert_with(|| id.clone());
+        graph.nodes.insert(
+            id,
+            NodeInfo {
+                label: package.name.clone(),
+                node_type: None,
+                attrs,
+            },
+        );
+    }
+
+    for package in &lockfile.packages {
+        let from = package_id(&package.name, package.version.as_deref());
+        let specifiers: HashMap<&str, &str> = package
+            .metadata
+            .requires_dist
+            .iter()
+            .filter_map(|r| Some((r.name.as_str(), r.specifier.as_deref()?)))
+            .collect();
+        let dev_specifiers: HashMap<&str, &str> = package
+            .metadata
+            .requires_dev
+            .values()
+            .flatten()
+            .filter_map(|r| Some((r.name.as_str(), r.specifier.as_deref()?)))
+            .collect();
+
+        let groups = std::iter::once((&package.dependencies, None, &specifiers))
+            .chain(
+                package
+                    .optional_dependencies
+                    .iter()
+                    .map(|(extra, deps)| (deps, Some(("optional", extra.as_str())), &specifiers)),
+            )
+            .chain(
+                package
+                    .dev_dependencies
+                    .values()
+                    .map(|deps| (deps, Some(("kind", "dev")), &dev_specifiers)),
+            );
+
+        for (deps, group_attr, specifiers) in groups {
+            for dep in deps {
+                let to = match &dep.version {
+                    Some(version) => package_id(&dep.name, Some(version)),
+                    None => match by_name.get(dep.name.as_str()) {
+                        Some(id) => id.clone(),
+                        None => {
+                            tracing::debug!("{from:?} dependency {:?} is not locked", dep.name);
+                            continue;
+                        }
+                    },
+                };
+
+                let mut attrs = IndexMap::new();
+                if let Some((key, value)) = group_attr {
+                    attrs.insert(key.to_string(), value.to_string());
+                }
+                if !dep.extra.is_empty() {
+                    attrs.insert("extras".to_string(), dep.extra.join(","));
+                }
+                if let Some(marker) = &dep.marker {
+                    attrs.insert("marker".to_string(), marker.clone());
+                }
+                graph.edges.push(Edge {
+                    from: from.clone(),
+                    to,
+                    label: specifiers.get(dep.name.as_str()).map(|s| s.to_string()),
+                    attrs,
+                });
+            }
+        }
+    }
+
+    Ok(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type EdgeTuple<'a> = (&'a str, &'a str, Option<&'a str>, Vec<(&'a str, &'a str)>);
+
+    fn edge_tuples(graph: &DepGraph) -> Vec<EdgeTuple<'_>> {
+        graph
+            .edges
+            .iter()
+            .map(|e| {
+                let attrs = e.attrs.iter().map(|(k, v)| (k.as_str(), v.as_str()));
+                (
+                    e.from.as_str(),
+                    e.to.as_str(),
+                    e.label.as_deref(),
+                    attrs.collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn empty_lockfile() {
+        let graph = parse("version = 1\nrequires-python = \">=3.12\"\n").unwrap();
+        assert!(graph.nodes.is_empty());
+    }
+
+    #[test]
+    fn invalid_toml() {
+        assert!(parse("[[package]\n").is_err());
+    }
+
+    #[test]
+    fn unlocked_dependency_skipped() {
+        let input = r#"
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = [{ name = "requests" }, { name = "certifi" }]
+
+[[package]]
+name = "certifi"
+version = "2024.2.2"
+"#;
+        let graph = parse(input).unwrap();
+        assert_eq!(graph.nodes.len(), 2);
+        assert_eq!(
+            edge_tuples(&graph),
+            vec![("app 0.1.0", "certifi 2024.2.2", None, vec![])]
+        );
+    }
+
+    #[test]
+    fn fixture() {
+        let input = include_str!("../../../../data/depconv/uv.lock");
+        let graph = parse(input).unwrap();
+
+        let ids: Vec<&str> = graph.nodes.keys().map(|s| s.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "app 0.1.0",
+                "certifi 2024.2.2",
+                "colorama 0.4.6",
+                "iniconfig 2.0.0",
+                "pysocks 1.7.1",
+                "pytest 8.0.0",
+                "requests 2.31.0",
+                "urllib3 2.2.0",
+            ]
+        );
+        assert_eq!(graph.nodes["urllib3 2.2.0"].attrs["version"], "2.2.0");
+
+        assert_eq!(
+            edge_tuples(&graph),
+            vec![
+                (
+                    "app 0.1.0",
+                    "colorama 0.4.6",
+                    Some(">=0.4"),
+                    vec![("marker", "sys_platform == 'win32'")]
+                ),
+                (
+                    "app 0.1.0",
+                    "requests 2.31.0",
+                    Some(">=2.31"),
+                    vec![("extras", "socks")]
+                ),
+                (
+                    "app 0.1.0",
+                    "pytest 8.0.0",
+                    Some(">=8"),
+                    vec![("kind", "dev")]
+                ),
+                ("pytest 8.0.0", "iniconfig 2.0.0", None, vec![]),
+                ("requests 2.31.0", "certifi 2024.2.2", None, vec![]),
+                ("requests 2.31.0", "urllib3 2.2.0", None, vec![]),
+                (
+                    "requests 2.31.0",
+                    "pysocks 1.7.1",
+                    None,
+                    vec![("optional", "socks")]
+                ),
+            ]
+        );
+    }
+}
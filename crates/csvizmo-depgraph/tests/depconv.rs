@@ -572,7 +572,7 @@ fn cargo_metadata_to_dot() {
             .unwrap()
             .trim(),
         "\"csvizmo-depgraph 0.5.0\" [label=\"csvizmo-depgraph\", type=\"lib\", \
-         version=\"0.5.0\", features=\"default,dot\", shape=\"ellipse\"];"
+         version=\"0.5.0\", license=\"MIT\", features=\"default,dot\", shape=\"ellipse\"];"
     );
 
     // Verify optional dependencies have exact edge attributes
@@ -593,7 +593,8 @@ fn cargo_metadata_to_dot() {
             .unwrap()
             .trim(),
         "\"clap_derive 4.5.55\" [label=\"clap_derive\", type=\"proc-macro\", \
-         version=\"4.5.55\", features=\"default\", shape=\"diamond\"];"
+         version=\"4.5.55\", license=\"MIT OR Apache-2.0\", features=\"default\", \
+         shape=\"diamond\"];"
     );
 
     // Verify dev dependency edge has styling
@@ -930,3 +931,619 @@ fn mermaid_edge_labels_to_tgf() {
         "A\tmyapp\nB\tlibfoo\nC\tlibbar\n#\nA\tB\tstatic\nA\tC\tdynamic\nB\tC\n"
     );
 }
+
+#[test]
+fn graphml_to_dot() {
+    let input = include_str!("../../../data/depconv/small.graphml");
+    let output = tool!("depconv")
+        .args(["--input-format", "graphml", "--output-format", "dot"])
+        .write_stdin(input)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "\
+digraph deps {
+    rankdir=\"LR\";
+    subgraph cluster_libs {
+        label=\"Libraries\";
+        libfoo [type=\"lib\", version=\"0.2.1\", shape=\"ellipse\"];
+        libbar [type=\"lib\", version=\"0.1.0\", shape=\"ellipse\"];
+        libfoo -> libbar;
+    }
+    myapp [label=\"My Application\", type=\"bin\", shape=\"box\"];
+    myapp -> libfoo [label=\"uses\"];
+    myapp -> libbar [kind=\"dev\", style=\"dashed\", color=\"gray60\"];
+}
+"
+    );
+}
+
+#[test]
+fn graphml_auto_detect_content() {
+    let input = include_str!("../../../data/depconv/small.graphml");
+    let output = tool!("depconv")
+        .arg("--detect")
+        .write_stdin(input)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "graphml\n");
+}
+
+#[test]
+fn graphml_auto_detect_extension() {
+    let fixture = "../../data/depconv/small.graphml";
+    let output = tool!("depconv")
+        .args(["--output-format", "tgf", "-i", fixture])
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "myapp\tMy Application\nlibfoo\nlibbar\n#\n\
+         myapp\tlibfoo\tuses\nmyapp\tlibbar\nlibfoo\tlibbar\n"
+    );
+}
+
+#[test]
+fn tgf_to_graphml_to_tgf_roundtrip() {
+    let input = "a\tAlpha\nb\tBravo\n#\na\tb\tuses\n";
+    let graphml_output = tool!("depconv")
+        .args(["--input-format", "tgf", "--output-format", "graphml"])
+        .write_stdin(input)
+        .captured_output()
+        .unwrap();
+    assert!(graphml_output.status.success());
+    let graphml = String::from_utf8_lossy(&graphml_output.stdout);
+
+    let tgf_output = tool!("depconv")
+        .args(["--output-format", "tgf"])
+        .write_stdin(graphml.as_ref())
+        .captured_output()
+        .unwrap();
+    assert!(tgf_output.status.success());
+    let tgf = String::from_utf8_lossy(&tgf_output.stdout);
+    assert_eq!(tgf, input);
+}
+
+#[test]
+fn json_to_dot() {
+    let input = include_str!("../../../data/depconv/small.jgf");
+    let output = tool!("depconv")
+        .args(["--input-format", "json", "--output-format", "dot"])
+        .write_stdin(input)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "\
+digraph deps {
+    rankdir=\"LR\";
+    subgraph cluster_libs {
+        label=\"Libraries\";
+        libfoo [type=\"lib\", version=\"0.2.1\", shape=\"ellipse\"];
+        libbar [type=\"lib\", version=\"0.1.0\", shape=\"ellipse\"];
+        libfoo -> libbar;
+    }
+    myapp [label=\"My Application\", type=\"bin\", shape=\"box\"];
+    myapp -> libfoo [label=\"uses\"];
+    myapp -> libbar [kind=\"dev\", style=\"dashed\", color=\"gray60\"];
+}
+"
+    );
+}
+
+#[test]
+fn json_to_plantuml() {
+    let input = include_str!("../../../data/depconv/small.jgf");
+    let output = tool!("depconv")
+        .args(["--input-format", "json", "--output-format", "plantuml"])
+        .write_stdin(input)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "\
+@startuml
+left to right direction
+package \"Libraries\" as libs {
+  component \"libfoo\" as libfoo
+  component \"libbar\" as libbar
+}
+node \"My Application\" as myapp
+myapp --> libfoo : uses
+myapp ..> libbar
+libfoo --> libbar
+@enduml
+"
+    );
+}
+
+#[test]
+fn json_to_d2() {
+    let input = include_str!("../../../data/depconv/small.jgf");
+    let output = tool!("depconv")
+        .args(["--input-format", "json", "--output-format", "d2"])
+        .write_stdin(input)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "\
+direction: right
+libs: \"Libraries\" {
+  libfoo: {shape: oval}
+  libbar: {shape: oval}
+}
+myapp: \"My Application\"
+myapp -> libs.libfoo: \"uses\"
+myapp -> libs.libbar: {style.stroke-dash: 3}
+libs.libfoo -> libs.libbar
+"
+    );
+}
+
+#[test]
+fn json_to_html() {
+    let input = include_str!("../../../data/depconv/small.jgf");
+    let output = tool!("depconv")
+        .args(["--input-format", "json", "--output-format", "html"])
+        .write_stdin(input)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("<!DOCTYPE html>\n"));
+    assert!(stdout.contains(r#""myapp":{"label":"My Application","metadata":{"type":"bin""#));
+    assert!(stdout.contains(r#""label":"Libraries""#));
+    assert!(!stdout.contains("<script src"));
+}
+
+#[test]
+fn json_to_svg() {
+    let input = include_str!("../../../data/depconv/small.jgf");
+    let output = tool!("depconv")
+        .args(["--input-format", "json", "--output-format", "svg"])
+        .write_stdin(input)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("<svg "));
+    assert!(stdout.ends_with("</svg>\n"));
+    assert!(stdout.contains(">My Application</text>"));
+    assert!(stdout.contains(">Libraries</text>"));
+    assert_eq!(stdout.matches(r#"<g class="node""#).count(), 3);
+}
+
+#[test]
+fn json_to_dsm() {
+    let input = include_str!("../../../data/depconv/small.jgf");
+    let output = tool!("depconv")
+        .args(["--input-format", "json", "--output-format", "dsm"])
+        .write_stdin(input)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "                     1 2 3
+┌─ Libraries
+│ 1 libbar         │ ■ · ·
+└ 2 libfoo         │ 1 ■ ·
+  3 My Application │ 1 1 ■
+"
+    );
+}
+
+#[test]
+fn json_to_csv() {
+    let input = include_str!("../../../data/depconv/small.jgf");
+    let output = tool!("depconv")
+        .args(["--input-format", "json", "--output-format", "csv"])
+        .write_stdin(input)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "\
+from,to,label,kind,style,color
+myapp,libfoo,uses,,,
+myapp,libbar,,dev,dashed,gray60
+libfoo,libbar,,,,
+"
+    );
+
+    let output = tool!("depconv")
+        .args(["--input-format", "json", "--output-format", "csv-nodes"])
+        .write_stdin(input)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "\
+id,label,type,shape,version
+myapp,My Application,bin,box,
+libfoo,libfoo,lib,ellipse,0.2.1
+libbar,libbar,lib,ellipse,0.1.0
+"
+    );
+}
+
+#[test]
+fn csv_auto_detect() {
+    let input = "id,label\nmyapp,My App\n\nfrom,to,kind\nmyapp,libfoo,dev\nlibfoo,libbar,\n";
+    let output = tool!("depconv")
+        .args(["--output-format", "tgf"])
+        .write_stdin(input)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "myapp\tMy App\nlibfoo\nlibbar\n#\nmyapp\tlibfoo\nlibfoo\tlibbar\n"
+    );
+}
+
+#[test]
+fn json_auto_detect_content() {
+    let input = include_str!("../../../data/depconv/small.jgf");
+    let output = tool!("depconv")
+        .arg("--detect")
+        .write_stdin(input)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "json\n");
+}
+
+#[test]
+fn json_extension_falls_back_to_content() {
+    // .json is shared by cargo metadata and JGF, so the extension alone doesn't decide
+    let fixture = "../../data/depconv/cargo-metadata.json";
+    let output = tool!("depconv")
+        .args(["--detect", "-i", fixture])
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "cargo-metadata\n");
+}
+
+#[test]
+fn graphml_to_json_to_graphml_roundtrip() {
+    let input = include_str!("../../../data/depconv/small.graphml");
+    let json_output = tool!("depconv")
+        .args(["--input-format", "graphml", "--output-format", "json"])
+        .write_stdin(input)
+        .captured_output()
+        .unwrap();
+    assert!(json_output.status.success());
+    let json = String::from_utf8_lossy(&json_output.stdout);
+
+    let graphml_output = tool!("depconv")
+        .args(["--output-format", "graphml"])
+        .write_stdin(json.as_ref())
+        .captured_output()
+        .unwrap();
+    assert!(graphml_output.status.success());
+    let graphml = String::from_utf8_lossy(&graphml_output.stdout);
+
+    let direct_output = tool!("depconv")
+        .args(["--input-format", "graphml", "--output-format", "graphml"])
+        .write_stdin(input)
+        .captured_output()
+        .unwrap();
+    assert!(direct_output.status.success());
+    assert_eq!(graphml, String::from_utf8_lossy(&direct_output.stdout));
+}
+
+#[test]
+fn ninja_to_dot() {
+    let input = include_str!("../../../data/depconv/small.ninja");
+    let output = tool!("depconv")
+        .args(["--output-format", "dot"])
+        .write_stdin(input)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "\
+digraph {
+    \"CMakeFiles/app.dir/foo.cpp.o\" [type=\"CXX_COMPILER__app_Debug\", duration=\"1532\"];
+    \"CMakeFiles/app.dir/main.cpp.o\" [type=\"CXX_COMPILER__app_Debug\", duration=\"1204\"];
+    app [type=\"CXX_EXECUTABLE_LINKER__app_Debug\", duration=\"257\"];
+    all [type=\"phony\"];
+    \"../src/foo.cpp\";
+    \"../include/foo.h\";
+    \"/usr/include/stdio.h\";
+    \"../src/main.cpp\";
+    \"CMakeFiles/app.dir/foo.cpp.o\" -> \"../src/foo.cpp\";
+    \"CMakeFiles/app.dir/foo.cpp.o\" -> \"../include/foo.h\";
+    \"CMakeFiles/app.dir/foo.cpp.o\" -> \"/usr/include/stdio.h\";
+    \"CMakeFiles/app.dir/main.cpp.o\" -> \"../src/main.cpp\";
+    \"CMakeFiles/app.dir/main.cpp.o\" -> \"../include/foo.h\";
+}
+"
+    );
+}
+
+#[test]
+fn cmake_file_api_to_dot() {
+    let input = include_str!("../../../data/depconv/cmake-file-api.json");
+    let output = tool!("depconv")
+        .args(["--output-format", "dot"])
+        .write_stdin(input)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "\
+digraph {
+    app [type=\"EXECUTABLE\"];
+    core [type=\"STATIC_LIBRARY\"];
+    headers [type=\"INTERFACE_LIBRARY\"];
+    docs [type=\"UTILITY\"];
+    app -> core [label=\"PRIVATE\"];
+    app -> headers;
+    app -> docs;
+    core -> headers [label=\"PUBLIC\"];
+}
+"
+    );
+}
+
+#[test]
+fn go_mod_graph_to_tgf() {
+    let input = "\
+example.com/app go@1.22
+example.com/app github.com/pkg/errors@v0.9.1
+example.com/app golang.org/x/net@v0.20.0
+golang.org/x/net@v0.20.0 golang.org/x/text@v0.14.0
+";
+    let output = tool!("depconv")
+        .args(["--output-format", "tgf"])
+        .write_stdin(input)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "\
+example.com/app
+github.com/pkg/errors@v0.9.1\tgithub.com/pkg/errors
+golang.org/x/net@v0.20.0\tgolang.org/x/net
+golang.org/x/text@v0.14.0\tgolang.org/x/text
+#
+example.com/app\tgithub.com/pkg/errors@v0.9.1
+example.com/app\tgolang.org/x/net@v0.20.0
+golang.org/x/net@v0.20.0\tgolang.org/x/text@v0.14.0
+"
+    );
+}
+
+#[test]
+fn npm_lock_dev_edges_dashed() {
+    let input = include_str!("../../../data/depconv/package-lock.json");
+    let output = tool!("depconv")
+        .args(["--output-format", "dot"])
+        .write_stdin(input)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(
+        "\"app 1.0.0\" -> \"typescript 5.3.3\" [kind=\"dev\", style=\"dashed\", color=\"gray60\"];"
+    ));
+    assert!(stdout.contains("\"app 1.0.0\" -> \"debug 4.3.4\" [kind=\"normal\"];"));
+}
+
+#[test]
+fn pnpm_lock_auto_detect() {
+    let input = include_str!("../../../data/depconv/pnpm-lock.yaml");
+    let output = tool!("depconv")
+        .arg("--detect")
+        .write_stdin(input)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "pnpm-lock\n");
+}
+
+#[test]
+fn uv_lock_to_tgf() {
+    let input = include_str!("../../../data/depconv/uv.lock");
+    let output = tool!("depconv")
+        .args(["--output-format", "tgf"])
+        .write_stdin(input)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "\
+app_0.1.0\tapp
+certifi_2024.2.2\tcertifi
+colorama_0.4.6\tcolorama
+iniconfig_2.0.0\tiniconfig
+pysocks_1.7.1\tpysocks
+pytest_8.0.0\tpytest
+requests_2.31.0\trequests
+urllib3_2.2.0\turllib3
+#
+app_0.1.0\tcolorama_0.4.6\t>=0.4
+app_0.1.0\trequests_2.31.0\t>=2.31
+app_0.1.0\tpytest_8.0.0\t>=8
+pytest_8.0.0\tiniconfig_2.0.0
+requests_2.31.0\tcertifi_2024.2.2
+requests_2.31.0\turllib3_2.2.0
+requests_2.31.0\tpysocks_1.7.1
+"
+    );
+}
+
+#[test]
+fn gradle_auto_detect() {
+    let input = include_str!("../../../data/depconv/gradle-dependencies.txt");
+    let output = tool!("depconv")
+        .arg("--detect")
+        .write_stdin(input)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "gradle\n");
+}
+
+#[test]
+fn maven_to_tgf() {
+    let input = include_str!("../../../data/depconv/mvn-dependency-tree.txt");
+    let output = tool!("depconv")
+        .args(["--output-format", "tgf"])
+        .write_stdin(input)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "\
+com.example:core_1.0-SNAPSHOT\tcom.example:core
+org.slf4j:slf4j-api_2.0.9\torg.slf4j:slf4j-api
+com.example:app_1.0-SNAPSHOT\tcom.example:app
+com.google.guava:guava_32.1.2-jre\tcom.google.guava:guava
+com.google.guava:failureaccess_1.0.1\tcom.google.guava:failureaccess
+com.google.code.findbugs:jsr305_3.0.2\tcom.google.code.findbugs:jsr305
+junit:junit_4.13.2\tjunit:junit
+org.hamcrest:hamcrest-core_1.3\torg.hamcrest:hamcrest-core
+#
+com.example:core_1.0-SNAPSHOT\torg.slf4j:slf4j-api_2.0.9
+com.example:app_1.0-SNAPSHOT\tcom.example:core_1.0-SNAPSHOT
+com.example:app_1.0-SNAPSHOT\tcom.google.guava:guava_32.1.2-jre
+com.google.guava:guava_32.1.2-jre\tcom.google.guava:failureaccess_1.0.1
+com.google.guava:guava_32.1.2-jre\tcom.google.code.findbugs:jsr305_3.0.2
+com.example:app_1.0-SNAPSHOT\tjunit:junit_4.13.2
+junit:junit_4.13.2\torg.hamcrest:hamcrest-core_1.3
+com.example:app_1.0-SNAPSHOT\torg.slf4j:slf4j-api_2.0.9
+"
+    );
+}
+
+#[test]
+fn cyclonedx_to_tgf() {
+    let input = include_str!("../../../data/depconv/small.cdx.json");
+    let output = tool!("depconv")
+        .args(["--output-format", "tgf"])
+        .write_stdin(input)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "\
+pkg:cargo/myapp@1.0.0\tmyapp
+pkg:cargo/libfoo@0.2.1\tlibfoo
+pkg:cargo/libbar@0.1.0\tlibbar
+pkg:generic/openssl@3.0.13\topenssl
+#
+pkg:cargo/myapp@1.0.0\tpkg:cargo/libfoo@0.2.1
+pkg:cargo/myapp@1.0.0\tpkg:cargo/libbar@0.1.0
+pkg:cargo/libfoo@0.2.1\tpkg:cargo/libbar@0.1.0
+pkg:cargo/libbar@0.1.0\tpkg:generic/openssl@3.0.13
+"
+    );
+}
+
+#[test]
+fn cargo_metadata_to_cyclonedx() {
+    let input = include_str!("../../../data/depconv/cargo-metadata.json");
+    let output = tool!("depconv")
+        .args(["--output-format", "cyclonedx"])
+        .write_stdin(input)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let bom: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(bom["bomFormat"], "CycloneDX");
+    assert_eq!(bom["specVersion"], "1.5");
+
+    let components = bom["components"].as_array().unwrap();
+    let clap_derive = components
+        .iter()
+        .find(|c| c["bom-ref"] == "clap_derive 4.5.55")
+        .unwrap();
+    assert_eq!(
+        *clap_derive,
+        serde_json::json!({
+            "type": "library",
+            "bom-ref": "clap_derive 4.5.55",
+            "name": "clap_derive",
+            "version": "4.5.55",
+            "licenses": [{"expression": "MIT OR Apache-2.0"}]
+        })
+    );
+
+    // The output is itself valid depconv input
+    let output = tool!("depconv")
+        .arg("--detect")
+        .write_stdin(output.stdout)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "cyclonedx\n");
+}
+
+#[test]
+fn directory_explicit_format() {
+    // An explicit format picks the directory reader, so a crate isn't read as its Rust sources
+    let output = tool!("depconv")
+        .args([
+            "-i",
+            "../../data/depconv/rust-crate",
+            "-I",
+            "depfile",
+            "-O",
+            "tgf",
+        ])
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "#\n");
+
+    let output = tool!("depconv")
+        .args(["-i", "../../data/depconv/rust-crate", "-I", "tgf"])
+        .captured_output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("cannot read tgf input from a directory"),
+        "{stderr}"
+    );
+}
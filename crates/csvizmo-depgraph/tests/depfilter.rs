@@ -916,3 +916,135 @@ digraph {
 "
     );
 }
+
+#[test]
+fn select_rdeps_from_spdx() {
+    // Who pulls in openssl?
+    let input = include_str!("../../../data/depconv/small.spdx.json");
+    let output = tool!("depfilter")
+        .args(["select", "--include", "openssl", "--rdeps"])
+        .args(["--output-format", "tgf"])
+        .write_stdin(input)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "\
+SPDXRef-Package-myapp\tmyapp
+SPDXRef-Package-libfoo\tlibfoo
+SPDXRef-Package-openssl\topenssl
+#
+SPDXRef-Package-myapp\tSPDXRef-Package-libfoo
+SPDXRef-Package-libfoo\tSPDXRef-Package-openssl
+"
+    );
+}
+
+#[test]
+fn select_rdeps_from_dpkg_status() {
+    // Which installed packages need libssl3, including through a virtual package?
+    let input = include_str!("../../../data/depconv/dpkg-status");
+    let output = tool!("depfilter")
+        .args(["select", "--include", "libssl3", "--rdeps"])
+        .args(["--output-format", "tgf"])
+        .write_stdin(input)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "\
+libssl3
+openssh-client
+openssh-server
+postfix
+logrotate
+mail-transport-agent
+#
+openssh-client\tlibssl3
+openssh-server\topenssh-client
+postfix\tlibssl3
+logrotate\tmail-transport-agent
+mail-transport-agent\tpostfix
+"
+    );
+}
+
+#[test]
+fn cycles_in_elf_link_graph() {
+    let input = include_str!("../../../data/depconv/elf-deps.txt");
+    let output = tool!("depfilter")
+        .args(["cycles", "--output-format", "tgf"])
+        .write_stdin(input)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // libfoo and libbar need each other
+    assert_eq!(
+        stdout,
+        "\
+/opt/app/lib/libfoo.so.1\tlibfoo.so.1
+/opt/app/lib/libbar.so.2\tlibbar.so.2
+libfoo.so.1
+libbar.so.2
+#
+/opt/app/lib/libfoo.so.1\tlibbar.so.2
+/opt/app/lib/libbar.so.2\tlibfoo.so.1
+libfoo.so.1\t/opt/app/lib/libfoo.so.1
+libbar.so.2\t/opt/app/lib/libbar.so.2
+"
+    );
+}
+
+#[test]
+fn cycles_in_rust_module_graph() {
+    let output = tool!("depfilter")
+        .args(["cycles", "--output-format", "tgf"])
+        .args(["--input", "../../data/depconv/rust-crate"])
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "\
+crate::graph\tgraph
+crate::util\tutil
+#
+crate::graph\tcrate::util
+crate::util\tcrate::graph
+"
+    );
+}
+
+#[test]
+fn between_bazel_labels() {
+    let input = include_str!("../../../data/depconv/bazel-query.jsonl");
+    let output = tool!("depfilter")
+        .args(["between", "-g", "//app:app", "-g", "//lib:*.txt"])
+        .args(["--output-format", "tgf"])
+        .write_stdin(input)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "\
+//app:app
+//lib:foo
+//lib:version.h
+//lib:gen_version
+//lib:version.txt
+#
+//app:app\t//lib:foo\tdeps
+//lib:foo\t//lib:version.h\thdrs
+//lib:gen_version\t//lib:version.txt\tsrcs
+//lib:version.h\t//lib:gen_version
+"
+    );
+}
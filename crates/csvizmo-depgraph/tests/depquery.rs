@@ -161,6 +161,22 @@ fn nodes_limit() {
     assert_eq!(stdout, "a\nb\n");
 }
 
+#[test]
+fn nodes_sort_ancestors_in_depfile_directory() {
+    // The headers that cause the most rebuilds across a build tree
+    let output = tool!("depquery")
+        .args(["nodes", "--sort", "ancestors", "-g", "*.h", "--limit", "3"])
+        .args(["--input", "../../data/depconv/depfiles"])
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "../include/config.h\t3\n../src/util.h\t3\n../src/net/net.h\t2\n"
+    );
+}
+
 #[test]
 fn nodes_deps_from_roots() {
     let output = tool!("depquery")
@@ -241,6 +257,21 @@ fn nodes_include_and_mode() {
     assert_eq!(stdout, "foo-alpha\n");
 }
 
+#[test]
+fn nodes_sort_betweenness() {
+    let output = tool!("depquery")
+        .args(["nodes", "--sort", "betweenness", "--input-format", "tgf"])
+        .write_stdin(DIAMOND_GRAPH)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "b\t0.500000\nc\t0.500000\na\t0.000000\nd\t0.000000\n"
+    );
+}
+
 // -- edges subcommand --
 
 #[test]
@@ -378,6 +409,27 @@ components\t1
     );
 }
 
+#[test]
+fn metrics_centrality() {
+    let output = tool!("depquery")
+        .args(["metrics", "--centrality", "--input-format", "tgf"])
+        .write_stdin(DIAMOND_GRAPH)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("nodes\t4\n"));
+    assert!(stdout.ends_with(
+        "\
+components\t1
+max_betweenness\t0.500000
+max_page_rank\t0.470608
+max_closeness\t0.750000
+max_k_core\t2
+"
+    ));
+}
+
 #[test]
 fn metrics_cycle() {
     let graph = "a\nb\nc\n#\na\tb\nb\tc\nc\ta\n";
@@ -464,3 +516,172 @@ components\t2
 "
     );
 }
+
+// -- path subcommand --
+
+#[test]
+fn path_shortest_from_roots() {
+    let output = tool!("depquery")
+        .args(["path", "--to", "d", "--input-format", "tgf"])
+        .write_stdin(DIAMOND_GRAPH)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "a -> b -> d\n");
+}
+
+#[test]
+fn path_all_shortest() {
+    let output = tool!("depquery")
+        .args(["path", "--all", "-t", "d", "--input-format", "tgf"])
+        .write_stdin(DIAMOND_GRAPH)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "a -> b -> d\na -> c -> d\n");
+}
+
+#[test]
+fn path_k_shortest_with_edge_labels() {
+    // alpha -> beta -> gamma, alpha -> gamma
+    let output = tool!("depquery")
+        .args(["path", "-f", "alpha", "-t", "gamma", "-k", "2"])
+        .args(["--input-format", "tgf"])
+        .write_stdin("1\talpha\n2\tbeta\n3\tgamma\n#\n1\t2\tuses\n2\t3\n1\t3\tdev\n")
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "alpha -[dev]-> gamma\nalpha -[uses]-> beta -> gamma\n"
+    );
+}
+
+#[test]
+fn path_format_id() {
+    let output = tool!("depquery")
+        .args([
+            "path",
+            "-t",
+            "gamma",
+            "--format",
+            "id",
+            "--input-format",
+            "tgf",
+        ])
+        .write_stdin(SIMPLE_GRAPH)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "1 -> 3\n");
+}
+
+#[test]
+fn path_not_found() {
+    let output = tool!("depquery")
+        .args(["path", "-f", "d", "-t", "a", "--input-format", "tgf"])
+        .write_stdin(DIAMOND_GRAPH)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+// -- dominators subcommand --
+
+#[test]
+fn dominators_diamond() {
+    let output = tool!("depquery")
+        .args(["dominators", "--input-format", "tgf"])
+        .write_stdin(DIAMOND_GRAPH)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "a\t3\t\nb\t0\ta\nc\t0\ta\nd\t0\ta\n");
+}
+
+#[test]
+fn dominators_chain_from() {
+    let output = tool!("depquery")
+        .args([
+            "dominators",
+            "--from",
+            "b",
+            "--limit",
+            "2",
+            "--input-format",
+            "tgf",
+        ])
+        .write_stdin(CHAIN_GRAPH)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "b\t2\t\nc\t1\tb\n");
+}
+
+const BUILD_GRAPH: &str = r#"{
+  "graph": {
+    "directed": true,
+    "nodes": {
+      "app": {"metadata": {"duration": "2"}},
+      "lib": {"metadata": {"duration": "5"}},
+      "codegen": {"metadata": {"duration": "1.5"}},
+      "core": {"metadata": {"duration": "3"}}
+    },
+    "edges": [
+      {"source": "app", "target": "lib"},
+      {"source": "app", "target": "codegen"},
+      {"source": "lib", "target": "core"},
+      {"source": "codegen", "target": "core"}
+    ]
+  }
+}"#;
+
+#[test]
+fn critical_path_chain() {
+    let output = tool!("depquery")
+        .args(["critical-path", "--input-format", "json"])
+        .write_stdin(BUILD_GRAPH)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "core\t3\t3\nlib\t5\t8\napp\t2\t10\n");
+}
+
+#[test]
+fn critical_path_slack() {
+    let output = tool!("depquery")
+        .args([
+            "critical-path",
+            "--slack",
+            "--limit",
+            "3",
+            "--input-format",
+            "json",
+        ])
+        .write_stdin(BUILD_GRAPH)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout, "core\t3\t0\nlib\t5\t0\napp\t2\t0\n");
+}
+
+#[test]
+fn critical_path_cycle_fails() {
+    let output = tool!("depquery")
+        .args(["critical-path", "--input-format", "tgf"])
+        .write_stdin("a\nb\n#\na\tb\nb\ta\n")
+        .captured_output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("graph contains cycles"));
+}
@@ -594,3 +594,48 @@ fn flatten_no_subgraphs_unchanged() {
     let stdout = String::from_utf8_lossy(&output.stdout);
     assert_eq!(stdout, "a\nb\n#\na\tb\n");
 }
+
+#[test]
+fn critical_path_highlights_chain() {
+    let graph = r#"{
+  "graph": {
+    "directed": true,
+    "nodes": {
+      "a": {"metadata": {"duration": "1"}},
+      "b": {"metadata": {"duration": "4"}},
+      "c": {"metadata": {"duration": "2"}}
+    },
+    "edges": [
+      {"source": "a", "target": "b"},
+      {"source": "a", "target": "c"}
+    ]
+  }
+}"#;
+    let output = tool!("deptransform")
+        .args([
+            "critical-path",
+            "--input-format",
+            "json",
+            "--output-format",
+            "mermaid",
+        ])
+        .write_stdin(graph)
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout,
+        "\
+flowchart LR
+    a[\"a\"]
+    style a stroke:red,stroke-width:2px
+    b[\"b\"]
+    style b stroke:red,stroke-width:2px
+    c[\"c\"]
+    a --> b
+    a --> c
+    linkStyle 0 stroke:red,stroke-width:2px
+"
+    );
+}
@@ -187,6 +187,31 @@ fn list_empty_diff() {
     assert_eq!(stdout, "");
 }
 
+#[test]
+fn list_plantuml_architecture_against_actual() {
+    // The binaries and their dependencies on the library modules, as actually built
+    let actual = tempfile(
+        "User\ndepconv\ndepfilter\nparse\nemit\nalgorithm\ngraph\ndot_parser\n#\n\
+         User\tdepconv\ndepconv\tparse\ndepconv\temit\ndepfilter\tparse\n\
+         depfilter\talgorithm\ndepfilter\temit\nparse\tgraph\nemit\tgraph\n\
+         algorithm\tgraph\nparse\tdot_parser\n",
+    )
+    .unwrap();
+
+    let output = tool!("graphdiff")
+        .arg("list")
+        .arg("../../data/depconv/architecture.puml")
+        .arg(actual.path())
+        .captured_output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // Nodes and edges in both are marked changed, because the diagram has node types and edge
+    // kinds that TGF doesn't. Only the dependency missing from the diagram is added.
+    let added_removed: Vec<&str> = stdout.lines().filter(|l| !l.starts_with('~')).collect();
+    assert_eq!(added_removed, vec!["+\tdepfilter\temit"]);
+}
+
 // -- subtract --
 
 #[test]
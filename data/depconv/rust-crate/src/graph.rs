@@ -0,0 +1,13 @@
+use crate::util::join;
+
+pub const SEPARATOR: &str = ",";
+
+pub struct Graph {
+    pub nodes: Vec<String>,
+}
+
+impl Graph {
+    pub fn label(&self) -> String {
+        join(&self.nodes)
+    }
+}
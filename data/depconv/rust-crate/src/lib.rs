@@ -0,0 +1,6 @@
+//! A tiny crate for testing module graphs. `mod not_a_module;` in a comment is ignored.
+pub mod graph;
+pub mod parse;
+mod util;
+
+pub use graph::Graph;
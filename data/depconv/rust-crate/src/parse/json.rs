@@ -0,0 +1,6 @@
+use super::text;
+use crate::graph::Graph;
+
+pub fn parse(input: &str) -> Graph {
+    text::parse(input.trim_matches(['{', '}']))
+}
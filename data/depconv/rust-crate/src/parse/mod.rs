@@ -0,0 +1,12 @@
+mod json;
+mod text;
+
+use crate::Graph;
+
+pub fn parse(input: &str) -> Graph {
+    if input.starts_with('{') {
+        json::parse(input)
+    } else {
+        text::parse(input)
+    }
+}
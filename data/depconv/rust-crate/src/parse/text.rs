@@ -0,0 +1,10 @@
+use crate::{
+    Graph,
+    util::{self, split},
+};
+
+pub fn parse(input: &str) -> Graph {
+    let nodes = split(input);
+    assert!(util::is_sorted(&nodes));
+    Graph { nodes }
+}
@@ -0,0 +1,22 @@
+/// Not a dependency: "use crate::parse;"
+pub fn join<'a>(nodes: &'a [String]) -> String {
+    nodes.join(crate::graph::SEPARATOR)
+}
+
+pub fn split(s: &str) -> Vec<String> {
+    s.split(',').map(|n| n.trim().to_string()).collect()
+}
+
+pub fn is_sorted(nodes: &[String]) -> bool {
+    nodes.windows(2).all(|w| w[0] <= w[1])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse::parse;
+
+    #[test]
+    fn roundtrip() {
+        assert_eq!(parse("a, b").label(), "a,b");
+    }
+}